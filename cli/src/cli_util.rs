@@ -1035,7 +1035,7 @@ impl WorkspaceCommandEnvironment {
 
     /// Resolves the effective `immutable()` expression to test against commits
     /// during a rewrite, taking the `--ignore-immutable` flag into account.
-    fn resolve_immutable_expression(
+    pub(crate) fn resolve_immutable_expression(
         &self,
         repo: &dyn Repo,
     ) -> Result<Arc<ResolvedRevsetExpression>, CommandError> {
@@ -1331,6 +1331,11 @@ impl WorkspaceCommandHelper {
         let mut tx = tx.into_inner();
         let old_git_head = self.repo().view().git_head().clone();
         let new_git_head = tx.repo().view().git_head().clone();
+        let description = format!(
+            "import git head: {} -> {}",
+            describe_git_head(&old_git_head),
+            describe_git_head(&new_git_head)
+        );
         if let Some(new_git_head_id) = new_git_head.as_normal() {
             let workspace_name = self.workspace_name().to_owned();
             let new_git_head_commit = tx.repo().store().get_commit_async(new_git_head_id).await?;
@@ -1347,7 +1352,7 @@ impl WorkspaceCommandHelper {
             self.user_repo = ReadonlyUserRepo::new(
                 self.env
                     .command
-                    .maybe_commit_transaction(tx, "import git head")
+                    .maybe_commit_transaction(tx, description)
                     .await?,
             );
             if self.env.command.should_commit_transaction() {
@@ -1376,7 +1381,7 @@ impl WorkspaceCommandHelper {
             if num_rebased > 0 {
                 writeln!(ui.status(), "Rebased {num_rebased} descendant commits.")?;
             }
-            self.finish_transaction(ui, tx, "import git head", git_import_export_lock)
+            self.finish_transaction(ui, tx, description, git_import_export_lock)
                 .await?;
         }
         Ok(())
@@ -2274,6 +2279,16 @@ to the current parents may contain changes from multiple commits.
     ) -> Result<(), CommandError> {
         let old_repo = tx.base_repo().clone();
 
+        #[cfg(feature = "git")]
+        if self.working_copy_shared_with_git && self.env.command.should_commit_transaction() {
+            // Commits are rewritten before the new working-copy commit is
+            // looked up since it may be rebased.
+            crate::git_util::add_change_id_trailers_for_export(ui, self, tx.repo_mut(), |_, _| {
+                true
+            })
+            .await?;
+        }
+
         let maybe_old_wc_commit = old_repo
             .view()
             .get_wc_commit_id(self.workspace_name())
@@ -2288,10 +2303,12 @@ to the current parents may contain changes from multiple commits.
 
         #[cfg(feature = "git")]
         if self.working_copy_shared_with_git && self.env.command.should_commit_transaction() {
+            // Refs are exported first so that Git HEAD can be attached to a
+            // bookmark moved by this transaction.
+            let stats = jj_lib::git::export_refs(tx.repo_mut())?;
             if let Some(wc_commit) = &maybe_new_wc_commit {
                 try_reset_git_head(ui, tx.repo_mut(), wc_commit, git_import_export_lock).await?;
             }
-            let stats = jj_lib::git::export_refs(tx.repo_mut())?;
             crate::git_util::print_git_export_stats(ui, &stats)?;
         }
 
@@ -2585,6 +2602,51 @@ to the current parents may contain changes from multiple commits.
 
         Ok(advanceable_bookmarks)
     }
+
+    /// Returns the bookmark to be advanced to the `commit` if
+    /// `git.advance-head-bookmark` is enabled in colocated workspace.
+    ///
+    /// If the `commit` has no bookmarks and its only parent has exactly one
+    /// bookmark, which has been exported to Git, that bookmark is advanced
+    /// like Git `commit` would do. Git HEAD can then stay attached to it.
+    #[cfg(feature = "git")]
+    pub fn get_advanceable_head_bookmark(
+        &self,
+        commit: &Commit,
+    ) -> Result<Vec<AdvanceableBookmark>, CommandError> {
+        if !self.working_copy_shared_with_git
+            || !self.settings().get_bool("git.advance-head-bookmark")?
+        {
+            return Ok(Vec::new());
+        }
+        let view = self.repo().view();
+        let [from_commit_id] = commit.parent_ids() else {
+            return Ok(Vec::new());
+        };
+        if view
+            .local_bookmarks_for_commit(commit.id())
+            .next()
+            .is_some()
+            || view.local_bookmarks_for_commit(from_commit_id).count() != 1
+        {
+            return Ok(Vec::new());
+        }
+        let Some(name) = jj_lib::git::find_exported_bookmark(view, from_commit_id) else {
+            return Ok(Vec::new());
+        };
+        Ok(vec![AdvanceableBookmark {
+            name: name.to_owned(),
+            old_commit_id: from_commit_id.clone(),
+        }])
+    }
+
+    #[cfg(not(feature = "git"))]
+    pub fn get_advanceable_head_bookmark(
+        &self,
+        _commit: &Commit,
+    ) -> Result<Vec<AdvanceableBookmark>, CommandError> {
+        Ok(Vec::new())
+    }
 }
 
 #[cfg(feature = "git")]
@@ -2728,6 +2790,23 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.env.parse_template(ui, &language, template_text)
     }
 
+    /// Adds `Change-Id` trailers to the commits to be exported if
+    /// `git.write-change-id-trailer` is enabled.
+    #[cfg(feature = "git")]
+    pub async fn add_change_id_trailers_for_export(
+        &mut self,
+        ui: &Ui,
+        git_ref_filter: impl Fn(jj_lib::git::GitRefKind, jj_lib::ref_name::RemoteRefSymbol<'_>) -> bool,
+    ) -> Result<(), CommandError> {
+        crate::git_util::add_change_id_trailers_for_export(
+            ui,
+            self.helper,
+            self.tx.repo_mut(),
+            git_ref_filter,
+        )
+        .await
+    }
+
     pub async fn finish(self, ui: &Ui, description: impl Into<String>) -> Result<(), CommandError> {
         let Self { helper, mut tx, .. } = self;
         if !tx.repo().has_changes() {
@@ -3394,6 +3473,19 @@ pub fn short_commit_hash(commit_id: &CommitId) -> String {
     format!("{commit_id:.12}")
 }
 
+/// Describes the Git HEAD target recorded in the view for operation
+/// descriptions.
+#[cfg(feature = "git")]
+fn describe_git_head(target: &RefTarget) -> String {
+    if let Some(id) = target.as_normal() {
+        short_commit_hash(id)
+    } else if target.is_absent() {
+        "unborn".to_owned()
+    } else {
+        "conflicted".to_owned()
+    }
+}
+
 pub fn short_change_hash(change_id: &ChangeId) -> String {
     format!("{change_id:.12}")
 }
@@ -515,6 +515,7 @@ impl From<TrailerParseError> for CommandError {
 
 #[cfg(feature = "git")]
 mod git {
+    use jj_lib::git::GitAnnotatedTagError;
     use jj_lib::git::GitDefaultRefspecError;
     use jj_lib::git::GitExportError;
     use jj_lib::git::GitFetchError;
@@ -523,6 +524,8 @@ mod git {
     use jj_lib::git::GitRefExpansionError;
     use jj_lib::git::GitRemoteManagementError;
     use jj_lib::git::GitResetHeadError;
+    use jj_lib::git::GitSubmoduleListError;
+    use jj_lib::git::GitWorktreeRemoveError;
     use jj_lib::git::UnexpectedGitBackendError;
 
     use super::*;
@@ -538,8 +541,10 @@ jj currently does not support partial clones. To use jj with this repository, tr
                      the full repository contents."
                         .to_string(),
                 ),
+                GitImportError::NoSuchWorktree(_) => None,
                 GitImportError::Backend(_) => None,
                 GitImportError::Index(_) => None,
+                GitImportError::IndexStore(_) => None,
                 GitImportError::RevsetEvaluation(_) => None,
                 GitImportError::Git(_) => None,
                 GitImportError::UnexpectedBackend(_) => None,
@@ -565,6 +570,19 @@ jj currently does not support partial clones. To use jj with this repository, tr
                     user_error(err).hinted("Run `jj git remote rename` to give a different name.")
                 }
                 GitFetchError::RejectedUpdates(_) | GitFetchError::Subprocess(_) => user_error(err),
+                GitFetchError::InternalGitError(_) => internal_error(err),
+            }
+        }
+    }
+
+    impl From<GitAnnotatedTagError> for CommandError {
+        fn from(err: GitAnnotatedTagError) -> Self {
+            match err {
+                GitAnnotatedTagError::Git(err) => {
+                    internal_error_with_message("Failed to write annotated tag", err)
+                }
+                GitAnnotatedTagError::InvalidName(_) => user_error(err),
+                GitAnnotatedTagError::UnexpectedBackend(err) => user_error(err),
             }
         }
     }
@@ -595,7 +613,11 @@ jj currently does not support partial clones. To use jj with this repository, tr
                 GitPushError::RemoteName(_) => {
                     user_error(err).hinted("Run `jj git remote rename` to give a different name.")
                 }
+                GitPushError::UnsupportedSignedPush => user_error(err)
+                    .hinted("Use `--signed=if-asked` to sign only if the remote supports it."),
+                GitPushError::UnexpectedRemoteHead { .. } => user_error(err),
                 GitPushError::Subprocess(_) => user_error(err),
+                GitPushError::Export(err) => err.into(),
                 GitPushError::UnexpectedBackend(_) => user_error(err),
             }
         }
@@ -613,6 +635,24 @@ jj currently does not support partial clones. To use jj with this repository, tr
         }
     }
 
+    impl From<GitSubmoduleListError> for CommandError {
+        fn from(err: GitSubmoduleListError) -> Self {
+            match err {
+                GitSubmoduleListError::Backend(err) => err.into(),
+                GitSubmoduleListError::ConflictedGitmodules
+                | GitSubmoduleListError::InvalidGitmodules(_) => {
+                    user_error_with_message("Failed to list Git submodules", err)
+                }
+            }
+        }
+    }
+
+    impl From<GitWorktreeRemoveError> for CommandError {
+        fn from(err: GitWorktreeRemoveError) -> Self {
+            user_error_with_message("Failed to remove Git worktree", err)
+        }
+    }
+
     impl From<UnexpectedGitBackendError> for CommandError {
         fn from(err: UnexpectedGitBackendError) -> Self {
             user_error(err)
@@ -940,8 +980,16 @@ fn revset_resolution_error_hints(err: &RevsetResolutionError) -> Vec<String> {
             symbol: _,
             targets,
         } => vec![multiple_targets_hint(targets)],
+        RevsetResolutionError::AmbiguousWorkingCopyBookmark {
+            name: _,
+            candidates,
+        } => vec![format!(
+            "Use one of the remote bookmarks instead: {}",
+            candidates.join(", ")
+        )],
         RevsetResolutionError::EmptyString
         | RevsetResolutionError::WorkspaceMissingWorkingCopy { .. }
+        | RevsetResolutionError::NoWorkingCopyBookmark { .. }
         | RevsetResolutionError::AmbiguousCommitIdPrefix(_)
         | RevsetResolutionError::AmbiguousChangeIdPrefix(_)
         | RevsetResolutionError::Backend(_)
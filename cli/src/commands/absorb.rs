@@ -48,7 +48,9 @@ pub(crate) struct AbsorbArgs {
 
     /// Destination revisions to absorb into
     ///
-    /// Only ancestors of the source revision will be considered.
+    /// Only ancestors of the source revision will be considered. Changes to
+    /// lines that were last modified outside of these revisions are left in
+    /// the source revision.
     #[arg(
         long,
         short = 't',
@@ -46,6 +46,15 @@ pub struct BookmarkForgetArgs {
     #[arg(long)]
     include_remotes: bool,
 
+    /// Keep the corresponding Git branch instead of deleting it on the next
+    /// export
+    ///
+    /// The Git-tracking state of the bookmark is forgotten as well, so the
+    /// branch will be imported again as a new bookmark by the next `jj git
+    /// import`. In colocated workspaces, this happens on the next command.
+    #[arg(long)]
+    keep_git_ref: bool,
+
     /// The bookmarks to forget
     ///
     /// By default, the specified pattern matches bookmark names with glob
@@ -81,6 +90,10 @@ pub async fn cmd_bookmark_forget(
         }
         tx.repo_mut()
             .set_local_bookmark_target(name, RefTarget::absent());
+        #[cfg(feature = "git")]
+        if args.keep_git_ref && ignored_remote.is_some() {
+            jj_lib::git::forget_bookmark_git_tracking(tx.repo_mut(), name);
+        }
         for (remote, _) in &bookmark_target.remote_refs {
             let symbol = name.to_remote_symbol(remote);
             // If `--include-remotes` is specified, we forget the corresponding remote
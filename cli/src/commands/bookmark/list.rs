@@ -14,10 +14,14 @@
 
 use std::collections::HashSet;
 use std::rc::Rc;
+use std::slice;
 
 use clap_complete::ArgValueCandidates;
 use futures::TryStreamExt as _;
-use jj_lib::repo::Repo as _;
+use itertools::Itertools as _;
+use jj_lib::ref_name::RemoteName;
+use jj_lib::repo::Repo;
+use jj_lib::revset;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::str_util::StringExpression;
 
@@ -26,11 +30,14 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
 use crate::commit_ref_list;
 use crate::commit_ref_list::RefFilterPredicates;
+use crate::commit_ref_list::RefListItem;
 use crate::commit_ref_list::SortKey;
 use crate::commit_templater::CommitRef;
 use crate::complete;
+use crate::formatter::FormatterExt as _;
 use crate::revset_util::parse_union_name_patterns;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
@@ -78,6 +85,13 @@ pub struct BookmarkListArgs {
     #[arg(long, short, conflicts_with = "all_remotes")]
     conflicted: bool,
 
+    /// Show bookmarks exported to the underlying Git repo only
+    ///
+    /// Each bookmark is listed with its Git-tracking target (`@git`), even if
+    /// the target is synchronized with the local bookmark.
+    #[arg(long, conflicts_with_all = ["all_remotes", "remotes", "tracked"])]
+    git: bool,
+
     /// Show bookmarks whose local name matches
     ///
     /// By default, the specified pattern matches bookmark names with glob
@@ -113,6 +127,15 @@ pub struct BookmarkListArgs {
     #[arg(add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
 
+    /// Show a summary of the commits each local bookmark is ahead of its
+    /// tracked remote bookmarks
+    ///
+    /// For each tracked remote bookmark behind the local bookmark, prints the
+    /// number of commits only reachable from the local bookmark, and the first
+    /// line of the description of the local target.
+    #[arg(long)]
+    preview: bool,
+
     /// Sort bookmarks based on the given key (or multiple keys)
     ///
     /// Suffix the key with `-` to sort in descending order of the value (e.g.
@@ -171,6 +194,11 @@ pub async fn cmd_bookmark_list(
     ) {
         (Some(texts), _) => parse_union_name_patterns(ui, texts)?,
         (None, Some(ignored)) => StringExpression::exact(ignored).negated(),
+        (None, None) if args.git => {
+            let git_remote = ignored_tracked_remote
+                .ok_or_else(|| user_error("The repo is not backed by a Git repo"))?;
+            StringExpression::exact(git_remote)
+        }
         (None, None) => StringExpression::all(),
     };
 
@@ -179,11 +207,15 @@ pub async fn cmd_bookmark_list(
         remote_matcher: remote_expr.to_matcher(),
         matched_local_targets,
         conflicted: args.conflicted,
-        include_local_only: !args.tracked && args.remotes.is_none(),
-        include_synced_remotes: args.tracked || args.all_remotes || args.remotes.is_some(),
+        include_local_only: !args.tracked && !args.git && args.remotes.is_none(),
+        include_synced_remotes: args.tracked
+            || args.git
+            || args.all_remotes
+            || args.remotes.is_some(),
         include_untracked_remotes: !args.tracked && (args.all_remotes || args.remotes.is_some()),
     };
-    let mut bookmark_list_items = commit_ref_list::collect_items(view.bookmarks(), &predicates);
+    let mut bookmark_list_items =
+        commit_ref_list::collect_items(view.bookmarks(), &predicates, ignored_tracked_remote);
     let sort_keys = if args.sort.is_empty() {
         workspace_command.settings().get_value_with(
             "ui.bookmark-list-sort-keys",
@@ -193,13 +225,29 @@ pub async fn cmd_bookmark_list(
         args.sort.clone()
     };
     commit_ref_list::sort(repo.store(), &mut bookmark_list_items, &sort_keys)?;
+    for item in &bookmark_list_items {
+        item.primary.populate_git_divergence(repo.as_ref())?;
+    }
+
+    let mut previews = Vec::new();
+    if args.preview {
+        for item in &bookmark_list_items {
+            previews
+                .push(collect_ahead_previews(repo.as_ref(), item, ignored_tracked_remote).await?);
+        }
+    }
 
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
-    bookmark_list_items
-        .iter()
-        .flat_map(|item| itertools::chain([&item.primary], &item.tracked))
-        .try_for_each(|commit_ref| template.format(commit_ref, formatter.as_mut()))?;
+    for (i, item) in bookmark_list_items.iter().enumerate() {
+        for commit_ref in itertools::chain([&item.primary], &item.tracked) {
+            template.format(commit_ref, formatter.as_mut())?;
+        }
+        for line in previews.get(i).into_iter().flatten() {
+            let mut formatter = formatter.as_mut().labeled("bookmark_list");
+            writeln!(formatter.labeled("preview"), "  {line}")?;
+        }
+    }
     drop(formatter);
 
     warn_unmatched_local_or_remote_bookmarks(ui, view, &name_expr)?;
@@ -250,3 +298,43 @@ pub async fn cmd_bookmark_list(
 
     Ok(())
 }
+
+/// Summarizes the commits the local bookmark is ahead of each of its tracked
+/// remote bookmarks, e.g. "2 commits ahead of @origin: top commit summary".
+async fn collect_ahead_previews(
+    repo: &dyn Repo,
+    item: &RefListItem,
+    ignored_remote: Option<&RemoteName>,
+) -> Result<Vec<String>, CommandError> {
+    let primary = &item.primary;
+    let Some(local_id) = primary.target().as_normal().filter(|_| primary.is_local()) else {
+        return Ok(vec![]);
+    };
+    let mut previews = Vec::new();
+    for remote_ref in &item.tracked {
+        let remote = remote_ref
+            .remote_name()
+            .expect("tracked ref should be remote");
+        if ignored_remote.is_some_and(|ignored| remote == ignored.as_str()) {
+            continue;
+        }
+        let remote_ids = remote_ref.target().added_ids().cloned().collect_vec();
+        let ahead_ids: Vec<_> = revset::walk_revs(repo, slice::from_ref(local_id), &remote_ids)?
+            .stream()
+            .try_collect()
+            .await?;
+        if ahead_ids.is_empty() {
+            continue;
+        }
+        let commit = repo.store().get_commit_async(local_id).await?;
+        let summary = commit
+            .description()
+            .lines()
+            .next()
+            .unwrap_or("(no description set)");
+        let count = ahead_ids.len();
+        let commits = if count == 1 { "commit" } else { "commits" };
+        previews.push(format!("{count} {commits} ahead of @{remote}: {summary}"));
+    }
+    Ok(previews)
+}
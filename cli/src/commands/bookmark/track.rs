@@ -175,7 +175,8 @@ pub async fn cmd_bookmark_track(
             let commit_ref = CommitRef::local(
                 name,
                 local_target.clone(),
-                bookmark_target.remote_refs.iter().map(|x| x.1),
+                bookmark_target.remote_refs.iter().copied(),
+                default_ignored_remote_name(workspace_command.repo().store()),
             );
             template.format(&commit_ref, formatter.as_mut())?;
 
@@ -28,6 +28,7 @@ use crate::description_util::add_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
+use crate::git_util::run_git_pre_commit_hook;
 use crate::ui::Ui;
 
 /// Update the description and create a new change on top [default alias: ci]
@@ -73,6 +74,13 @@ pub(crate) struct CommitArgs {
     #[arg(long)]
     editor: bool,
 
+    /// Don't run the Git `pre-commit` hook
+    ///
+    /// The hook is only run in colocated workspaces if `git.run-hooks` is
+    /// enabled.
+    #[arg(long)]
+    no_verify: bool,
+
     /// Put these paths in the current commit
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     #[arg(add = ArgValueCompleter::new(complete::modified_files))]
@@ -176,6 +184,9 @@ new working-copy commit.
         description
     };
     commit_builder.set_description(description);
+    if !args.no_verify {
+        run_git_pre_commit_hook(ui, &tx, &commit_builder.tree())?;
+    }
     let new_commit = commit_builder.write(tx.repo_mut()).await?;
 
     let workspace_names = tx.repo().view().workspaces_for_wc_commit_id(commit.id());
@@ -0,0 +1,120 @@
+// Copyright 2023 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::io::Write as _;
+
+use jj_lib::git;
+use jj_lib::git::GitIndexDiffKind;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::CommandError;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::ui::Ui;
+
+/// Show the entries of the Git index of a colocated workspace
+///
+/// Each entry is printed with its stage, mode, object id, stat information,
+/// flags, and path.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugGitIndexArgs {
+    /// Compare the Git index with the index jj would write instead
+    ///
+    /// In a colocated workspace, jj keeps the Git index in sync with the
+    /// parent of the working-copy commit, with files added in the working-copy
+    /// commit marked as intent-to-add. Lists the paths that differ, and exits
+    /// with an error if there are any.
+    ///
+    /// Each differing path is prefixed with `A` if it is only in the Git index,
+    /// `D` if it is missing from the Git index, or `M` if its index entries
+    /// differ.
+    #[arg(long)]
+    check: bool,
+}
+
+pub async fn cmd_debug_git_index(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugGitIndexArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = if args.check {
+        command.workspace_helper(ui).await?
+    } else {
+        command.workspace_helper_no_snapshot(ui).await?
+    };
+    if !workspace_command.working_copy_shared_with_git() {
+        return Err(user_error(
+            "This command requires a workspace colocated with Git",
+        ));
+    }
+    if args.check {
+        return check_git_index(ui, &workspace_command).await;
+    }
+    let git_repo = git::get_git_backend(workspace_command.repo().store())?.git_repo();
+    let index = git_repo.index_or_empty().map_err(internal_error)?;
+    let format_time = |time: gix::index::entry::stat::Time| format!("{}:{}", time.secs, time.nsecs);
+    let mut formatter = ui.stdout_formatter();
+    for entry in index.entries() {
+        writeln!(
+            formatter,
+            "{:12} {:?} {} ctime={} mtime={} size={} flags={:x} {}",
+            format!("{:?}", entry.stage()),
+            entry.mode,
+            entry.id,
+            format_time(entry.stat.ctime),
+            format_time(entry.stat.mtime),
+            entry.stat.size,
+            entry.flags.bits(),
+            entry.path_in(index.path_backing()),
+        )?;
+    }
+    Ok(())
+}
+
+async fn check_git_index(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let wc_commit_id = workspace_command
+        .get_wc_commit_id()
+        .ok_or_else(|| user_error("This command requires a working copy"))?;
+    let wc_commit = repo.store().get_commit_async(wc_commit_id).await?;
+    let diff = git::diff_git_index(repo.as_ref(), &wc_commit)
+        .await
+        .map_err(|err| user_error_with_message("Failed to compare Git index", err))?;
+    if diff.is_empty() {
+        writeln!(
+            ui.status(),
+            "The Git index matches the working-copy commit."
+        )?;
+        return Ok(());
+    }
+
+    let mut formatter = ui.stdout_formatter();
+    for entry in &diff {
+        let prefix = match entry.kind {
+            GitIndexDiffKind::Added => "A",
+            GitIndexDiffKind::Removed => "D",
+            GitIndexDiffKind::Modified => "M",
+        };
+        writeln!(formatter, "{prefix} {}", entry.path)?;
+    }
+    drop(formatter);
+    Err(user_error(
+        "The Git index doesn't match the working-copy commit",
+    ))
+}
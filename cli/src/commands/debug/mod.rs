@@ -14,6 +14,8 @@
 
 mod copy_detection;
 mod fileset;
+#[cfg(feature = "git")]
+mod git_index;
 mod index;
 mod index_changed_paths;
 mod init_simple;
@@ -36,6 +38,10 @@ use self::copy_detection::CopyDetectionArgs;
 use self::copy_detection::cmd_debug_copy_detection;
 use self::fileset::DebugFilesetArgs;
 use self::fileset::cmd_debug_fileset;
+#[cfg(feature = "git")]
+use self::git_index::DebugGitIndexArgs;
+#[cfg(feature = "git")]
+use self::git_index::cmd_debug_git_index;
 use self::index::DebugIndexArgs;
 use self::index::cmd_debug_index;
 use self::index_changed_paths::DebugIndexChangedPathsArgs;
@@ -73,6 +79,8 @@ use crate::ui::Ui;
 pub enum DebugCommand {
     CopyDetection(CopyDetectionArgs),
     Fileset(DebugFilesetArgs),
+    #[cfg(feature = "git")]
+    GitIndex(DebugGitIndexArgs),
     Index(DebugIndexArgs),
     IndexChangedPaths(DebugIndexChangedPathsArgs),
     InitSimple(DebugInitSimpleArgs),
@@ -98,6 +106,8 @@ pub async fn cmd_debug(
     match subcommand {
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args).await,
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args).await,
+        #[cfg(feature = "git")]
+        DebugCommand::GitIndex(args) => cmd_debug_git_index(ui, command, args).await,
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args).await,
         DebugCommand::IndexChangedPaths(args) => {
             cmd_debug_index_changed_paths(ui, command, args).await
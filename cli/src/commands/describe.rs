@@ -38,6 +38,7 @@ use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
 use crate::description_util::join_message_paragraphs;
 use crate::description_util::parse_trailers_template;
+use crate::git_util::run_git_pre_commit_hook;
 use crate::text_util::complete_newline;
 use crate::ui::Ui;
 
@@ -81,6 +82,13 @@ pub(crate) struct DescribeArgs {
     /// allow the message to be edited afterwards.
     #[arg(long)]
     editor: bool,
+
+    /// Don't run the Git `pre-commit` hook
+    ///
+    /// The hook is only run in colocated workspaces if `git.run-hooks` is
+    /// enabled.
+    #[arg(long)]
+    no_verify: bool,
 }
 
 #[instrument(skip_all)]
@@ -224,6 +232,11 @@ pub(crate) async fn cmd_describe(
         })
         .map(|(old_commit, commit_builder)| (old_commit.id(), commit_builder))
         .collect();
+    if !args.no_verify {
+        for commit_builder in commit_builders.values() {
+            run_git_pre_commit_hook(ui, &tx, &commit_builder.tree())?;
+        }
+    }
 
     let mut num_described = 0;
     let mut num_reparented = 0;
@@ -49,6 +49,9 @@ use crate::ui::Ui;
 /// (perhaps a bookmark name) to the working-copy commit.
 ///
 /// If no option is specified, it defaults to `-r @`.
+///
+/// With the `--reverse` option, the two sides of the diff are swapped, which
+/// can be used to generate a patch that undoes the changes.
 #[derive(clap::Args, Clone, Debug)]
 #[command(mut_arg("ignore_all_space", |a| a.short('w')))]
 #[command(mut_arg("ignore_space_change", |a| a.short('b')))]
@@ -84,6 +87,13 @@ pub(crate) struct DiffArgs {
     #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
     to: Option<RevisionArg>,
 
+    /// Show the diff with the two sides swapped
+    ///
+    /// The changes are shown from the "to" side to the "from" side, like
+    /// `git diff -R`.
+    #[arg(long)]
+    reverse: bool,
+
     /// Restrict the diff to these paths
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     #[arg(add = ArgValueCompleter::new(complete::modified_revision_or_range_files))]
@@ -124,8 +134,8 @@ pub(crate) async fn cmd_diff(
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let matcher = fileset_expression.to_matcher();
 
-    let from_tree;
-    let to_tree;
+    let mut from_tree;
+    let mut to_tree;
     let mut copy_records = CopyRecords::default();
     if args.from.is_some() || args.to.is_some() {
         let resolve_revision = async |r: &Option<RevisionArg>| {
@@ -138,7 +148,12 @@ pub(crate) async fn cmd_diff(
         from_tree = from.tree();
         to_tree = to.tree();
 
-        let records = get_copy_records(repo.store(), from.id(), to.id(), &matcher).await?;
+        let (source, target) = if args.reverse {
+            (to.id(), from.id())
+        } else {
+            (from.id(), to.id())
+        };
+        let records = get_copy_records(repo.store(), source, target, &matcher).await?;
         copy_records.add_records(records);
     } else {
         let revision_args = args
@@ -186,11 +201,19 @@ pub(crate) async fn cmd_diff(
 
         for p in &parents {
             for to in &heads {
-                let records = get_copy_records(repo.store(), p.id(), to.id(), &matcher).await?;
+                let (source, target) = if args.reverse {
+                    (to.id(), p.id())
+                } else {
+                    (p.id(), to.id())
+                };
+                let records = get_copy_records(repo.store(), source, target, &matcher).await?;
                 copy_records.add_records(records);
             }
         }
     }
+    if args.reverse {
+        std::mem::swap(&mut from_tree, &mut to_tree);
+    }
 
     // -T disables both short/long rendering formats, but it might be okay to
     // enable long format if explicitly specified (assuming -T is for short or
@@ -33,8 +33,8 @@ enum ChmodMode {
     #[value(name = "n", alias("normal"))]
     Normal,
 
-    /// Make a path executable (alias: executable)
-    #[value(name = "x", alias("executable"))]
+    /// Make a path executable (aliases: executable, +x)
+    #[value(name = "x", aliases(["executable", "+x"]))]
     Executable,
 }
 
@@ -23,6 +23,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::git;
 use jj_lib::git::GitPushOptions;
+use jj_lib::git::GitPushSigned;
 use jj_lib::git::GitRefUpdate;
 use jj_lib::git::GitSubprocessOptions;
 use jj_lib::merge::Diff;
@@ -419,6 +420,11 @@ pub async fn cmd_gerrit_upload(
     // Do this first because the validation is cheap.
     let push_options = GitPushOptions {
         remote_push_options: push_options(args)?,
+        no_thin: false,
+        atomic: false,
+        signed: GitPushSigned::No,
+        expected_remote_head: None,
+        tag_source_remote: None,
     };
 
     let mut workspace_command = command.workspace_helper(ui).await?;
@@ -697,8 +703,10 @@ pub async fn cmd_gerrit_upload(
         .map_err(|err| match err {
             git::GitPushError::NoSuchRemote(_)
             | git::GitPushError::RemoteName(_)
+            | git::GitPushError::UnsupportedSignedPush
+            | git::GitPushError::UnexpectedRemoteHead { .. }
             | git::GitPushError::UnexpectedBackend(_) => user_error(err),
-            git::GitPushError::Subprocess(_) => {
+            git::GitPushError::Subprocess(_) | git::GitPushError::Export(_) => {
                 user_error_with_message("Internal git error while pushing to gerrit", err)
             }
         })?;
@@ -22,6 +22,8 @@ use itertools::Itertools as _;
 use jj_lib::file_util;
 use jj_lib::git;
 use jj_lib::git::GitFetch;
+use jj_lib::git::GitFetchDepth;
+use jj_lib::git::GitFetchError;
 use jj_lib::git::GitFetchRefExpression;
 use jj_lib::git::GitImportOptions;
 use jj_lib::git::GitSettings;
@@ -144,9 +146,10 @@ pub struct GitCloneArgs {
     /// Object hash algorithm for the local Git repository.
     ///
     /// *Must* match the remote's hash algorithm, otherwise the operation will
-    /// fail. Most existing repositories today still use the classic SHA-1
-    /// format, which is also the default if not configured otherwise by the
-    /// [git.object-hash config].
+    /// fail. If not given, the [git.object-hash config] determines the
+    /// default value, and the repository is recreated with the hash algorithm
+    /// of the remote if they turn out to differ. Most existing repositories
+    /// today still use the classic SHA-1 format.
     ///
     /// See also Git's [hash-function-transition] document for an in-depth
     /// explanation of the migration towards stronger hash functions.
@@ -191,6 +194,11 @@ pub async fn cmd_git_clone(
         ));
     }
 
+    let object_hash = args.object_hash.map_or_else(
+        || command.settings().get::<ObjectHash>("git.object-hash"),
+        Result::Ok,
+    )?;
+
     // will create a tree dir in case if was deleted after last check
     fs::create_dir_all(&wc_path)
         .map_err(|err| user_error_with_message(format!("Failed to create {wc_path_str}"), err))?;
@@ -209,65 +217,91 @@ pub async fn cmd_git_clone(
         Some(texts) => Some(parse_union_name_patterns(ui, texts)?),
         None => is_specific.then(StringExpression::none),
     };
-    let object_hash = args.object_hash.map_or_else(
-        || command.settings().get::<ObjectHash>("git.object-hash"),
-        Result::Ok,
-    )?;
 
     // Canonicalize because fs::remove_dir_all() doesn't seem to like e.g.
     // `/some/path/.`
     let canonical_wc_path = dunce::canonicalize(&wc_path)
         .map_err(|err| user_error_with_message(format!("Failed to create {wc_path_str}"), err))?;
 
+    let remove_repo_dirs = || -> io::Result<()> {
+        let sub_dirs = [Some(".jj"), colocate.then_some(".git")];
+        for &name in sub_dirs.iter().flatten() {
+            let dir = canonical_wc_path.join(name);
+            fs::remove_dir_all(&dir).or_else(|err| match err.kind() {
+                io::ErrorKind::NotFound => Ok(()),
+                _ => Err(err),
+            })?;
+        }
+        Ok(())
+    };
+
     let clone_result: Result<_, CommandError> = async {
-        let (workspace_command, config_env) = init_workspace(
-            ui,
-            command,
-            &canonical_wc_path,
-            colocate,
-            object_hash.into(),
-        )
-        .await?;
-        let remote_settings = workspace_command.settings().remote_settings()?;
-        let bookmark = if let Some(expr) = &specific_bookmark_expr {
-            expr.clone()
-        } else if let Some(expr) = parse_remote_fetch_bookmarks(ui, &remote_settings, remote_name)?
-        {
-            expr
-        } else {
-            StringExpression::all()
-        };
-        let tag = if let Some(expr) = specific_tag_expr {
-            expr
-        } else if let Some(expr) = parse_remote_fetch_tags(ui, &remote_settings, remote_name)? {
-            expr
-        } else {
-            StringExpression::all()
-        };
-        let mut workspace_command =
-            configure_remote(ui, command, workspace_command, remote_name, &source).await?;
-        let ref_expr = GitFetchRefExpression { bookmark, tag };
-        let default_branch = fetch_new_remote(
-            ui,
-            &mut workspace_command,
-            remote_name,
-            &ref_expr,
-            args.depth,
-        )
-        .await?;
-        Ok((workspace_command, default_branch, config_env))
+        let mut object_hash = object_hash.into();
+        let mut is_retry = false;
+        loop {
+            let (workspace_command, config_env) =
+                init_workspace(ui, command, &canonical_wc_path, colocate, object_hash).await?;
+            let remote_settings = workspace_command.settings().remote_settings()?;
+            let bookmark = if let Some(expr) = &specific_bookmark_expr {
+                expr.clone()
+            } else if let Some(expr) =
+                parse_remote_fetch_bookmarks(ui, &remote_settings, remote_name)?
+            {
+                expr
+            } else {
+                StringExpression::all()
+            };
+            let tag = if let Some(expr) = &specific_tag_expr {
+                expr.clone()
+            } else if let Some(expr) = parse_remote_fetch_tags(ui, &remote_settings, remote_name)? {
+                expr
+            } else {
+                StringExpression::all()
+            };
+            let mut workspace_command =
+                configure_remote(ui, command, workspace_command, remote_name, &source).await?;
+            let ref_expr = GitFetchRefExpression { bookmark, tag };
+            if !is_retry {
+                writeln!(
+                    ui.status(),
+                    r#"Fetching into new repo in "{}""#,
+                    workspace_command.workspace_root().display()
+                )?;
+            }
+            let err = match fetch_new_remote(
+                ui,
+                &mut workspace_command,
+                remote_name,
+                &ref_expr,
+                args.depth,
+            )
+            .await
+            {
+                Ok(default_branch) => return Ok((workspace_command, default_branch, config_env)),
+                Err(err) => err,
+            };
+            // The object hash of the remote isn't known until the fetch fails.
+            // Unless it was specified, recreate the repo with the remote's.
+            let Some(remote_hash) = err
+                .error
+                .downcast_ref::<GitFetchError>()
+                .and_then(GitFetchError::mismatched_remote_object_hash)
+                .filter(|&remote_hash| args.object_hash.is_none() && remote_hash != object_hash)
+            else {
+                return Err(err);
+            };
+            drop(workspace_command);
+            remove_repo_dirs().map_err(|err| {
+                user_error_with_message(format!("Failed to clean up {wc_path_str}"), err)
+            })?;
+            object_hash = remote_hash;
+            is_retry = true;
+        }
     }
     .await;
     if clone_result.is_err() {
         let clean_up_dirs = || -> io::Result<()> {
-            let sub_dirs = [Some(".jj"), colocate.then_some(".git")];
-            for &name in sub_dirs.iter().flatten() {
-                let dir = canonical_wc_path.join(name);
-                fs::remove_dir_all(&dir).or_else(|err| match err.kind() {
-                    io::ErrorKind::NotFound => Ok(()),
-                    _ => Err(err),
-                })?;
-            }
+            remove_repo_dirs()?;
             if !wc_path_existed {
                 fs::remove_dir(&canonical_wc_path)?;
             }
@@ -372,11 +406,6 @@ async fn fetch_new_remote(
     ref_expr: &GitFetchRefExpression,
     depth: Option<NonZeroU32>,
 ) -> Result<(Option<RefNameBuf>, bool), CommandError> {
-    writeln!(
-        ui.status(),
-        r#"Fetching into new repo in "{}""#,
-        workspace_command.workspace_root().display()
-    )?;
     let settings = workspace_command.settings();
     let git_settings = GitSettings::from_settings(settings)?;
     let remote_settings = settings.remote_settings()?;
@@ -398,7 +427,7 @@ async fn fetch_new_remote(
             remote_name,
             fetch_refspecs,
             &mut GitSubprocessUi::new(ui),
-            depth,
+            depth.map(GitFetchDepth::Depth),
         )?;
 
         let import_stats = git_fetch.import_refs().await?;
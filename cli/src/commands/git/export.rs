@@ -12,29 +12,394 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+use std::error;
+use std::io::Write as _;
+use std::iter;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::git;
+use jj_lib::git::GitExportPreview;
+use jj_lib::git::GitExportStats;
+use jj_lib::git::GitRefKind;
+use jj_lib::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::repo::Repo as _;
+use jj_lib::view::View;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::git_util::non_clashing_bookmark_name;
 use crate::git_util::print_git_export_stats;
+use crate::git_util::rename_local_bookmark;
+use crate::revset_util::parse_union_name_patterns;
 use crate::ui::Ui;
 
 /// Update the underlying Git repo with changes made in the repo
 ///
 /// There is no need to run this command if you're in colocated workspace
 /// because the export happens automatically there.
+///
+/// If `git.export.attach-head` is enabled and exactly one bookmark points to
+/// the working-copy commit, the Git HEAD is made a symbolic ref to that branch.
+/// In colocated workspaces, the Git HEAD follows the parent of the
+/// working-copy commit instead, and is attached to the bookmark pointing to it.
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitExportArgs {}
+pub struct GitExportArgs {
+    /// Only display what will change in the Git repo, but don't export
+    #[arg(long)]
+    dry_run: bool,
+    /// Output format of the `--dry-run` plan
+    ///
+    /// `json` prints the ref changes that would be made, the branches the Git
+    /// HEAD would be detached from, and the refs that would fail to export as
+    /// a JSON object to stdout.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ExportPlanFormat::Text,
+        requires = "dry_run"
+    )]
+    format: ExportPlanFormat,
+    /// Export all refs in a single transaction
+    ///
+    /// If any bookmark or tag can't be exported, none of the refs is updated
+    /// in the Git repo.
+    #[arg(long)]
+    atomic: bool,
+    /// Rename bookmarks that clash with other bookmarks as Git ref paths
+    ///
+    /// Git can't store both `foo` and `foo/bar` as branches. If exporting
+    /// fails because of such clash, the shorter bookmark `foo` is renamed to
+    /// the first available name in `foo-1`, `foo-2`, etc., and the export is
+    /// retried. The remote bookmarks of the old name are untracked so they
+    /// won't be deleted by the next push.
+    #[arg(long, conflicts_with_all = ["dry_run", "atomic"])]
+    rename_conflicting: bool,
+    /// Export only this bookmark, or bookmarks matching a pattern (can be
+    /// repeated)
+    ///
+    /// Matching bookmarks which were deleted are deleted from the Git repo.
+    /// Other bookmarks, remote bookmarks, and tags are left unexported.
+    ///
+    /// By default, the specified pattern matches bookmark names with glob
+    /// syntax. You can also use other [string pattern syntax].
+    ///
+    /// [string pattern syntax]:
+    ///     https://docs.jj-vcs.dev/latest/revsets/#string-patterns
+    #[arg(long, value_name = "BOOKMARK")]
+    #[arg(add = ArgValueCandidates::new(complete::local_bookmarks))]
+    only: Option<Vec<String>>,
+    /// Export bookmarks to another Git repository at the given path
+    ///
+    /// Local bookmarks are written as branches of the given repository, and
+    /// the missing objects are copied to it. Existing branches are
+    /// overwritten. Branches of deleted bookmarks are left as is. The export
+    /// state of the underlying Git repo isn't changed.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["dry_run", "atomic", "rename_conflicting"],
+        value_hint = clap::ValueHint::DirPath,
+    )]
+    to_repo: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum ExportPlanFormat {
+    /// Human-readable lines
+    Text,
+    /// JSON object
+    Json,
+}
 
 pub async fn cmd_git_export(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitExportArgs,
+    args: &GitExportArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui).await?;
+    let bookmark_matcher = if let Some(patterns) = &args.only {
+        let view = workspace_command.repo().view();
+        warn_unmatched_bookmark_patterns(ui, view, patterns)?;
+        Some(parse_union_name_patterns(ui, patterns)?.to_matcher())
+    } else {
+        None
+    };
+    let git_ref_filter = |kind: GitRefKind, symbol: RemoteRefSymbol<'_>| {
+        bookmark_matcher.as_ref().is_none_or(|matcher| {
+            kind == GitRefKind::Bookmark
+                && symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                && matcher.is_match(symbol.name.as_str())
+        })
+    };
+    if let Some(path) = &args.to_repo {
+        let git_repo_path = command.cwd().join(path);
+        let stats =
+            git::export_refs_to_repo(workspace_command.repo().as_ref(), &git_repo_path, |name| {
+                git_ref_filter(
+                    GitRefKind::Bookmark,
+                    name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO),
+                )
+            })?;
+        print_git_export_stats(ui, &stats)?;
+        return Ok(());
+    }
+    if args.dry_run {
+        let preview =
+            git::preview_export_some_refs(workspace_command.repo().as_ref(), git_ref_filter)?;
+        match args.format {
+            ExportPlanFormat::Text => print_git_export_preview(ui, &preview)?,
+            ExportPlanFormat::Json => print_git_export_preview_json(ui, &preview)?,
+        }
+        writeln!(ui.status(), "Dry-run requested, not exporting.")?;
+        return Ok(());
+    }
     let mut tx = workspace_command.start_transaction();
-    let stats = git::export_refs(tx.repo_mut())?;
+    tx.add_change_id_trailers_for_export(ui, git_ref_filter)
+        .await?;
+    let mut stats = if args.atomic {
+        git::export_some_refs_atomic(tx.repo_mut(), git_ref_filter)?
+    } else {
+        git::export_some_refs(tx.repo_mut(), git_ref_filter)?
+    };
+    if args.rename_conflicting && rename_conflicting_bookmarks(ui, &mut tx, &stats)? {
+        stats = git::export_some_refs(tx.repo_mut(), git_ref_filter)?;
+    }
+    if args.atomic && !(stats.failed_bookmarks.is_empty() && stats.failed_tags.is_empty()) {
+        print_git_export_stats(ui, &stats)?;
+        return Err(user_error(
+            "Failed to export some refs atomically, no refs were exported",
+        ));
+    }
+    // In a colocated workspace, the HEAD is attached when it is reset to the
+    // parent of the working-copy commit.
+    if !tx.base_workspace_helper().working_copy_shared_with_git() {
+        attach_head_to_working_copy_bookmark(ui, &mut tx)?;
+    }
     tx.finish(ui, "export git refs").await?;
     print_git_export_stats(ui, &stats)?;
     Ok(())
 }
+
+/// Warns about patterns that match neither local bookmarks nor bookmarks
+/// exported to Git before.
+fn warn_unmatched_bookmark_patterns(
+    ui: &Ui,
+    view: &View,
+    patterns: &[String],
+) -> Result<(), CommandError> {
+    let mut unmatched = Vec::new();
+    for pattern in patterns {
+        let matcher = parse_union_name_patterns(ui, [pattern])?.to_matcher();
+        let mut names = itertools::chain(
+            view.local_bookmarks().map(|(name, _)| name),
+            view.remote_bookmarks(REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+                .map(|(name, _)| name),
+        );
+        if !names.any(|name| matcher.is_match(name.as_str())) {
+            unmatched.push(pattern);
+        }
+    }
+    if !unmatched.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "No matching bookmarks for patterns: {}",
+            unmatched.iter().join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Renames bookmarks which are path prefixes of failed bookmarks or vice
+/// versa. Returns true if any bookmark was renamed.
+fn rename_conflicting_bookmarks(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    stats: &GitExportStats,
+) -> Result<bool, CommandError> {
+    let local_names: BTreeSet<RefNameBuf> = tx
+        .repo()
+        .view()
+        .local_bookmarks()
+        .map(|(name, _)| name.to_owned())
+        .collect();
+    // (shorter name, longer name) pairs, sorted for deterministic renaming
+    let mut clashes: BTreeSet<(RefNameBuf, RefNameBuf)> = BTreeSet::new();
+    for (symbol, _) in &stats.failed_bookmarks {
+        for name in &local_names {
+            if !git::bookmark_names_clash(name, &symbol.name) {
+                continue;
+            }
+            if name.as_str().len() < symbol.name.as_str().len() {
+                clashes.insert((name.clone(), symbol.name.clone()));
+            } else {
+                clashes.insert((symbol.name.clone(), name.clone()));
+            }
+        }
+    }
+    let mut taken = local_names;
+    let mut renamed = BTreeSet::new();
+    for (old_name, other_name) in clashes {
+        if !renamed.insert(old_name.clone()) {
+            continue;
+        }
+        let new_name = non_clashing_bookmark_name(tx.repo().view(), &taken, &old_name, &other_name);
+        taken.insert(new_name.clone());
+        writeln!(
+            ui.status(),
+            "Renamed bookmark {old} to {new} to avoid conflict with {other}",
+            old = old_name.as_symbol(),
+            new = new_name.as_symbol(),
+            other = other_name.as_symbol(),
+        )?;
+        rename_local_bookmark(tx.repo_mut(), &old_name, &new_name);
+    }
+    Ok(!renamed.is_empty())
+}
+
+fn attach_head_to_working_copy_bookmark(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+) -> Result<(), CommandError> {
+    let workspace_name = tx.base_workspace_helper().workspace_name().to_owned();
+    let Some(wc_commit_id) = tx.repo().view().get_wc_commit_id(&workspace_name).cloned() else {
+        return Ok(());
+    };
+    if let Some(name) = git::attach_head_to_exported_bookmark(tx.repo_mut(), &wc_commit_id)? {
+        writeln!(
+            ui.status(),
+            "Attached Git HEAD to bookmark {}.",
+            name.as_symbol()
+        )?;
+    }
+    Ok(())
+}
+
+fn print_git_export_preview(ui: &Ui, preview: &GitExportPreview) -> std::io::Result<()> {
+    let Some(mut formatter) = ui.status_formatter() else {
+        return Ok(());
+    };
+    let short_hash = |oid: &gix::ObjectId| short_commit_hash(&CommitId::from_bytes(oid.as_bytes()));
+    for git_ref_name in &preview.detached_head_refs {
+        writeln!(
+            formatter,
+            "would detach HEAD from {}",
+            git_ref_name.as_str()
+        )?;
+    }
+    for (git_ref_name, diff) in &preview.ref_changes {
+        let git_ref_name = git_ref_name.as_str();
+        match (&diff.before, &diff.after) {
+            (None, Some(new_oid)) => {
+                writeln!(
+                    formatter,
+                    "would create {git_ref_name}: {}",
+                    short_hash(new_oid)
+                )?;
+            }
+            (Some(old_oid), Some(new_oid)) => writeln!(
+                formatter,
+                "would update {git_ref_name}: {} -> {}",
+                short_hash(old_oid),
+                short_hash(new_oid)
+            )?,
+            (Some(old_oid), None) => {
+                writeln!(
+                    formatter,
+                    "would delete {git_ref_name}: {}",
+                    short_hash(old_oid)
+                )?;
+            }
+            (None, None) => {}
+        }
+    }
+    for (symbol, reason) in itertools::chain(&preview.failed_bookmarks, &preview.failed_tags) {
+        let reasons = iter::successors(Some(reason as &dyn error::Error), |err| err.source());
+        writeln!(
+            formatter,
+            "would fail: {symbol} ({})",
+            itertools::join(reasons, ": ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Planned ref operation in the JSON output of `--dry-run`.
+#[derive(Debug, serde::Serialize)]
+struct JsonRefChange<'a> {
+    name: &'a str,
+    action: &'static str,
+    old_target: Option<String>,
+    new_target: Option<String>,
+}
+
+/// Ref that would fail to export in the JSON output of `--dry-run`.
+#[derive(Debug, serde::Serialize)]
+struct JsonRefFailure {
+    kind: &'static str,
+    name: String,
+    remote: String,
+    reason: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JsonExportPlan<'a> {
+    detached_head_refs: Vec<&'a str>,
+    ref_changes: Vec<JsonRefChange<'a>>,
+    failed_refs: Vec<JsonRefFailure>,
+}
+
+fn print_git_export_preview_json(ui: &Ui, preview: &GitExportPreview) -> std::io::Result<()> {
+    let ref_changes = preview
+        .ref_changes
+        .iter()
+        .filter_map(|(git_ref_name, diff)| {
+            let action = match (&diff.before, &diff.after) {
+                (None, Some(_)) => "create",
+                (Some(_), Some(_)) => "update",
+                (Some(_), None) => "delete",
+                (None, None) => return None,
+            };
+            Some(JsonRefChange {
+                name: git_ref_name.as_str(),
+                action,
+                old_target: diff.before.map(|oid| oid.to_string()),
+                new_target: diff.after.map(|oid| oid.to_string()),
+            })
+        })
+        .collect();
+    let failed_refs = itertools::chain(
+        iter::repeat("bookmark").zip(&preview.failed_bookmarks),
+        iter::repeat("tag").zip(&preview.failed_tags),
+    )
+    .map(|(kind, (symbol, reason))| {
+        let reasons = iter::successors(Some(reason as &dyn error::Error), |err| err.source());
+        JsonRefFailure {
+            kind,
+            name: symbol.name.as_str().to_owned(),
+            remote: symbol.remote.as_str().to_owned(),
+            reason: itertools::join(reasons, ": "),
+        }
+    })
+    .collect();
+    let plan = JsonExportPlan {
+        detached_head_refs: preview
+            .detached_head_refs
+            .iter()
+            .map(|git_ref_name| git_ref_name.as_str())
+            .collect(),
+        ref_changes,
+        failed_refs,
+    };
+    let mut stdout = ui.stdout();
+    serde_json::to_writer_pretty(&mut stdout, &plan)?;
+    writeln!(stdout)
+}
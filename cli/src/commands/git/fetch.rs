@@ -12,35 +12,62 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::error::Error as _;
 use std::io;
+use std::num::NonZeroU32;
 
 use clap_complete::ArgValueCandidates;
+use futures::TryStreamExt as _;
 use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitFetch;
+use jj_lib::git::GitFetchDepth;
+use jj_lib::git::GitFetchError;
+use jj_lib::git::GitFetchPrune;
 use jj_lib::git::GitFetchRefExpression;
+use jj_lib::git::GitImportStats;
+use jj_lib::git::GitPullRequestRefs;
+use jj_lib::git::GitPushOptions;
+use jj_lib::git::GitPushRefTargets;
 use jj_lib::git::GitSettings;
 use jj_lib::git::IgnoredRefspec;
 use jj_lib::git::IgnoredRefspecs;
 use jj_lib::git::expand_fetch_refspecs;
 use jj_lib::git::get_git_backend;
 use jj_lib::git::load_default_fetch_bookmarks;
+use jj_lib::merge::Diff;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
 use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RefNameBuf;
 use jj_lib::ref_name::RemoteName;
+use jj_lib::ref_name::RemoteNameBuf;
 use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::settings::RemoteSettingsMap;
+use jj_lib::signing::SigStatus;
 use jj_lib::str_util::StringExpression;
+use jj_lib::str_util::StringMatcher;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
+use crate::command_error::print_error_sources;
 use crate::command_error::user_error;
 use crate::commands::git::get_single_remote;
+use crate::commands::git::push::print_commits_ready_to_push;
 use crate::complete;
+use crate::git_util::GitProgressMode;
 use crate::git_util::GitSubprocessUi;
 use crate::git_util::load_git_import_options;
+use crate::git_util::print_git_import_preview;
 use crate::git_util::print_git_import_stats;
+use crate::git_util::print_push_stats;
 use crate::revset_util::parse_remote_fetch_bookmarks;
 use crate::revset_util::parse_remote_fetch_tags;
 use crate::revset_util::parse_union_name_patterns;
@@ -52,11 +79,20 @@ use crate::ui::Ui;
 /// `git.fetch` setting. If that is not configured and there are multiple
 /// remotes, the remote named "origin" will be used.
 ///
+/// When fetching from multiple remotes, a remote that fails to fetch is
+/// reported and skipped. The refs fetched from the other remotes are still
+/// imported, and the command fails at the end.
+///
 /// If no branches nor tags are specified, fetches bookmarks and tags specified
 /// by the `remotes.<name>.fetch-bookmarks`/`fetch-tags` settings. If
 /// `remotes.<name>.fetch-bookmarks` is not configured, the default fetch
 /// refspecs for the selected remotes are read from the Git configuration.
 ///
+/// Remote bookmarks whose branches were deleted on the remote are pruned, as
+/// with `git fetch --prune`, unless disabled by `--no-prune` or the Git config
+/// `fetch.prune`. Only bookmarks matching the fetched branch patterns are
+/// pruned.
+///
 /// Commits that are no longer reachable from any branch on the remote will be
 /// considered abandoned by the remote, and will be abandoned in the local repo
 /// to match the remote. Set `git.abandon-unreachable-commits` to `false` to
@@ -64,6 +100,9 @@ use crate::ui::Ui;
 ///
 /// If a working-copy commit gets abandoned, it will be given a new, empty
 /// commit. This is true in general; it is not specific to this command.
+///
+/// Use `--dry-run` to see which bookmarks would be updated and which commits
+/// would be abandoned without changing the repo.
 #[derive(clap::Args, Clone, Debug)]
 #[command(group(clap::ArgGroup::new("specific").multiple(true)))]
 pub struct GitFetchArgs {
@@ -102,6 +141,24 @@ pub struct GitFetchArgs {
     #[arg(long = "tag", short, group = "specific", value_name = "TAG")]
     tags: Option<Vec<String>>,
 
+    /// Import only some of the fetched tags (can be repeated)
+    ///
+    /// Tags not matching the pattern are fetched into the underlying Git
+    /// repository, but not imported. Tags that were already imported are kept
+    /// even if they don't match the pattern. The pattern syntax is the same as
+    /// `--tag`. Use `--import-tags=''` to import no tags.
+    #[arg(long, value_name = "TAG")]
+    import_tags: Option<Vec<String>>,
+
+    /// Allow fetched tags to move or delete existing local tags
+    ///
+    /// By default, if a fetched tag was moved or deleted on the remote, the
+    /// remote tag is updated, but the local tag of the same name is kept as
+    /// is, like `git fetch` does. Local tags left behind by earlier fetches
+    /// aren't updated.
+    #[arg(long)]
+    force_tags: bool,
+
     /// Fetch only tracked bookmarks and tags
     ///
     /// This fetches only bookmarks and tags that are already tracked from the
@@ -124,6 +181,124 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+
+    /// Track all fetched bookmarks that are new to the local repo
+    ///
+    /// This overrides the `remotes.<name>.auto-track-bookmarks` setting for
+    /// this fetch.
+    #[arg(long)]
+    auto_local_bookmark: bool,
+
+    /// Don't track any fetched bookmarks that are new to the local repo
+    ///
+    /// This overrides the `remotes.<name>.auto-track-bookmarks` setting for
+    /// this fetch.
+    #[arg(long, conflicts_with = "auto_local_bookmark")]
+    no_auto_local_bookmark: bool,
+
+    /// Push the fetched bookmarks and tags to another remote
+    ///
+    /// After fetching, the bookmarks and tags of the (single) fetched remote
+    /// are pushed to the specified remote as they are, so the remote can be
+    /// maintained as a backup mirror. Bookmarks and tags are updated based on
+    /// the last known state of the mirror remote.
+    #[arg(long, value_name = "REMOTE")]
+    #[arg(add = ArgValueCandidates::new(complete::git_remotes))]
+    mirror_into: Option<RemoteNameBuf>,
+
+    /// Delete remote bookmarks and tags that no longer exist on the remote
+    ///
+    /// With `--mirror-into`, such bookmarks and tags are also deleted from the
+    /// mirror remote.
+    ///
+    /// If neither `--prune` nor `--no-prune` is specified, the Git config
+    /// `fetch.prune` is respected, and tags are pruned only if
+    /// `fetch.pruneTags` is also set. If `fetch.prune` isn't set, remote
+    /// bookmarks and tags are pruned, but nothing is deleted from the mirror
+    /// remote.
+    #[arg(long)]
+    prune: bool,
+
+    /// Keep remote bookmarks and tags that no longer exist on the remote
+    ///
+    /// This overrides the `fetch.prune` and `fetch.pruneTags` Git config.
+    #[arg(long, conflicts_with = "prune")]
+    no_prune: bool,
+
+    /// Only display what would be imported, without changing the repo
+    ///
+    /// The objects are downloaded into the underlying Git repository, but the
+    /// remote-tracking refs are restored afterwards, and no bookmarks are
+    /// updated or commits abandoned.
+    #[arg(long, conflicts_with = "mirror_into")]
+    dry_run: bool,
+
+    /// Also fetch the heads of pull requests as `pr/<number>` bookmarks
+    ///
+    /// Fetches `refs/pull/<number>/head` (GitHub) or
+    /// `refs/merge-requests/<number>/head` (GitLab) refs into
+    /// `refs/jj/pull/<remote>/`, and imports them as remote bookmarks such as
+    /// `pr/123@origin`. Pull requests whose bookmark name is taken by a branch
+    /// on the remote aren't imported. By default, the forge is detected from
+    /// the host name of the remote URL.
+    #[arg(
+        long,
+        value_name = "FORGE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "auto"
+    )]
+    prs: Option<PullRequestForge>,
+
+    /// How to show the progress of the fetch
+    ///
+    /// `auto` shows a progress bar if stderr is a terminal. `lines` prints
+    /// periodic updates on separate lines, which is suitable for logs.
+    #[arg(long, value_name = "MODE", default_value = "auto")]
+    progress: GitProgressMode,
+
+    /// Deepen the history of a shallow repository by this many commits
+    ///
+    /// The ancestors that become available are imported. If the history is
+    /// deepened past the root commits, the repository is no longer shallow.
+    #[arg(long, value_name = "N", conflicts_with = "dry_run")]
+    depth: Option<NonZeroU32>,
+
+    /// Fetch the full history of a shallow repository
+    ///
+    /// All missing ancestors are imported, and the repository is no longer
+    /// shallow.
+    #[arg(long, conflicts_with_all = ["dry_run", "depth"])]
+    unshallow: bool,
+
+    /// Fetch the missing parents of local commits instead of bookmarks and
+    /// tags
+    ///
+    /// Commits whose parents are missing in the underlying Git repository,
+    /// e.g. after an interrupted operation, are looked up, and only the missing
+    /// commits and their ancestors are fetched. No bookmarks or tags are
+    /// updated.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "specific",
+            "tracked",
+            "mirror_into",
+            "dry_run",
+            "prs",
+            "import_tags",
+            "depth",
+            "unshallow",
+        ],
+    )]
+    import_missing_parents: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum PullRequestForge {
+    Auto,
+    Github,
+    Gitlab,
 }
 
 #[tracing::instrument(skip_all)]
@@ -164,6 +339,38 @@ pub async fn cmd_git_fetch(
     if matching_remotes.is_empty() {
         return Err(user_error("No git remotes to fetch from"));
     }
+    if let Some(mirror_remote) = &args.mirror_into {
+        if matching_remotes.len() > 1 {
+            return Err(user_error(
+                "--mirror-into requires a single remote to fetch from",
+            ));
+        }
+        if matching_remotes.contains(&mirror_remote.as_ref()) {
+            return Err(user_error(format!(
+                "Cannot mirror remote {remote} into itself",
+                remote = mirror_remote.as_symbol()
+            )));
+        }
+    }
+
+    if args.import_missing_parents {
+        return fetch_missing_parents(ui, &workspace_command, &matching_remotes, args.progress)
+            .await;
+    }
+
+    let mut depth = if args.unshallow {
+        Some(GitFetchDepth::Unshallow)
+    } else {
+        args.depth.map(GitFetchDepth::Deepen)
+    };
+    if depth.is_some() && git::shallow_commit_ids(workspace_command.repo().store())?.is_empty() {
+        let flag = if args.unshallow {
+            "--unshallow"
+        } else {
+            "--depth"
+        };
+        return Err(user_error(format!("{flag} requires a shallow repository")));
+    }
 
     let mut tx = workspace_command.start_transaction();
     let remote_settings = tx.settings().remote_settings()?;
@@ -205,19 +412,13 @@ pub async fn cmd_git_fetch(
         for remote in &matching_remotes {
             let bookmark = if let Some(expr) = &common_bookmark_expr {
                 expr.clone()
-            } else if let Some(expr) = parse_remote_fetch_bookmarks(ui, &remote_settings, remote)? {
-                expr
             } else {
-                let (ignored, expr) = load_default_fetch_bookmarks(remote, &git_repo)?;
-                warn_ignored_refspecs(ui, remote, ignored)?;
-                expr
+                get_default_fetch_bookmarks(ui, &remote_settings, &git_repo, remote)?
             };
             let tag = if let Some(expr) = &common_tag_expr {
                 expr.clone()
-            } else if let Some(expr) = parse_remote_fetch_tags(ui, &remote_settings, remote)? {
-                expr
             } else {
-                StringExpression::all()
+                get_default_fetch_tags(ui, &remote_settings, remote)?
             };
             let ref_expr = GitFetchRefExpression { bookmark, tag };
             let expanded = expand_fetch_refspecs(remote, ref_expr)?;
@@ -225,35 +426,333 @@ pub async fn cmd_git_fetch(
         }
     }
 
+    if let Some(forge) = args.prs {
+        let git_repo = get_git_backend(tx.repo().store())?.git_repo();
+        for (remote, expanded) in &mut expansions {
+            let kind = match forge {
+                PullRequestForge::Auto => detect_pull_request_refs(&git_repo, remote)?,
+                PullRequestForge::Github => GitPullRequestRefs::GitHub,
+                PullRequestForge::Gitlab => GitPullRequestRefs::GitLab,
+            };
+            expanded.add_pull_requests(remote, kind);
+        }
+    }
+
+    if let Some(texts) = &args.import_tags {
+        // An empty pattern imports no tags.
+        let texts = texts.iter().filter(|text| !text.is_empty());
+        let import_tag_expr = parse_union_name_patterns(ui, texts)?;
+        for (_, expanded) in &mut expansions {
+            expanded.restrict_imported_tags(import_tag_expr.clone());
+        }
+    }
+
     let git_settings = GitSettings::from_settings(tx.settings())?;
-    let import_options = load_git_import_options(ui, &git_settings, &remote_settings)?;
+    let mut import_options = load_git_import_options(ui, &git_settings, &remote_settings)?;
+    if args.auto_local_bookmark {
+        for remote in &matching_remotes {
+            import_options
+                .remote_auto_track_bookmarks
+                .insert((*remote).to_owned(), StringMatcher::all());
+        }
+    } else if args.no_auto_local_bookmark {
+        for remote in &matching_remotes {
+            import_options.remote_auto_track_bookmarks.remove(*remote);
+        }
+    }
+    import_options.keep_existing_tags = !args.force_tags;
+    let prune = if args.prune {
+        Some(GitFetchPrune::ALL)
+    } else if args.no_prune {
+        Some(GitFetchPrune::NONE)
+    } else {
+        let git_repo = get_git_backend(tx.repo().store())?.git_repo();
+        GitFetchPrune::from_git_config(&git_repo)
+    };
+    let store = tx.repo().store().clone();
     let mut git_fetch = GitFetch::new(
         tx.repo_mut(),
         git_settings.to_subprocess_options(),
         &import_options,
     )?;
+    git_fetch.set_prune(prune.unwrap_or(GitFetchPrune::ALL));
 
+    // When fetching from multiple remotes, a failure to fetch from one of them
+    // shouldn't prevent importing the refs fetched from the others.
+    let continue_on_error = matching_remotes.len() > 1;
+    let mut failed_remotes = Vec::new();
     for (remote, expanded) in expansions {
-        let mut callback = GitSubprocessUi::new(ui);
-        git_fetch.fetch(remote, expanded, &mut callback, None)?;
+        if args.dry_run {
+            git_fetch.save_remote_refs(remote)?;
+        }
+        let mut callback = GitSubprocessUi::with_progress_mode(ui, args.progress);
+        match git_fetch.fetch(remote, expanded, &mut callback, depth) {
+            // Git refuses to unshallow a complete repository, so the other
+            // remotes are fetched normally.
+            Ok(()) if args.unshallow && git::shallow_commit_ids(&store)?.is_empty() => {
+                depth = None;
+            }
+            Ok(()) => {}
+            Err(err) if continue_on_error && !matches!(err, GitFetchError::InternalGitError(_)) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to fetch from remote {remote}: {err}",
+                    remote = remote.as_symbol()
+                )?;
+                print_error_sources(ui, err.source())?;
+                failed_remotes.push(remote);
+            }
+            Err(err) => return Err(err.into()),
+        }
     }
+    let check_failed_remotes = || {
+        if failed_remotes.is_empty() {
+            Ok(())
+        } else {
+            Err(user_error(format!(
+                "Failed to fetch from some remotes: {}",
+                failed_remotes.iter().map(|n| n.as_symbol()).join(", ")
+            )))
+        }
+    };
 
     let import_stats = git_fetch.import_refs().await?;
+    let is_unshallowed =
+        (args.depth.is_some() || args.unshallow) && git::shallow_commit_ids(&store)?.is_empty();
+    if args.dry_run {
+        git_fetch.restore_remote_refs()?;
+        print_git_import_preview(ui, &tx, &import_stats)?;
+        writeln!(ui.status(), "Dry-run requested, not importing.")?;
+        return check_failed_remotes();
+    }
     print_git_import_stats(ui, &tx, &import_stats)?;
+    if tx.settings().get_bool("git.verify-tag-signatures")? {
+        print_tag_signatures(ui, &tx, &import_stats)?;
+    }
+    if !args.force_tags {
+        warn_if_tags_not_moved(ui, &tx, &import_stats)?;
+    }
 
     if let Some(bookmark_expr) = &common_bookmark_expr {
         warn_if_branches_not_found(ui, &tx, bookmark_expr, &matching_remotes)?;
     }
     // TODO: warn_if_tags_not_found()
+    let tx_description = format!(
+        "fetch from git remote(s) {}",
+        matching_remotes.iter().map(|n| n.as_symbol()).join(",")
+    );
+    if let Some(mirror_remote) = &args.mirror_into {
+        // Deleting refs from the mirror has to be requested explicitly.
+        return mirror_remote_refs(
+            ui,
+            tx,
+            matching_remotes[0],
+            mirror_remote,
+            prune.unwrap_or(GitFetchPrune::NONE),
+            &git_settings,
+            tx_description,
+        )
+        .await;
+    }
+    tx.finish(ui, tx_description).await?;
+    if is_unshallowed {
+        writeln!(ui.status(), "The repository is no longer shallow.")?;
+    }
+    check_failed_remotes()
+}
+
+/// Fetches the missing parents of the visible commits from the remotes.
+async fn fetch_missing_parents(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    remotes: &[&RemoteName],
+    progress: GitProgressMode,
+) -> Result<(), CommandError> {
+    let repo = workspace_command.repo();
+    let store = repo.store();
+    let git_settings = GitSettings::from_settings(workspace_command.settings())?;
+    let commit_ids: Vec<CommitId> = RevsetExpression::all()
+        .evaluate(repo.as_ref())?
+        .stream()
+        .try_collect()
+        .await?;
+    let mut missing_ids = git::find_missing_parents(store, &commit_ids)?;
+    if missing_ids.is_empty() {
+        writeln!(ui.status(), "No missing parent commits found.")?;
+        return Ok(());
+    }
+    // A remote may not have the missing commits. Try the other remotes.
+    let continue_on_error = remotes.len() > 1;
+    for remote in remotes {
+        let mut callback = GitSubprocessUi::with_progress_mode(ui, progress);
+        match git::fetch_commits(
+            store,
+            git_settings.to_subprocess_options(),
+            remote,
+            &missing_ids,
+            &mut callback,
+        ) {
+            Ok(()) => {}
+            Err(err) if continue_on_error && !matches!(err, GitFetchError::InternalGitError(_)) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to fetch from remote {remote}: {err}",
+                    remote = remote.as_symbol()
+                )?;
+                print_error_sources(ui, err.source())?;
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        }
+        let remaining_ids = git::find_missing_parents(store, &commit_ids)?;
+        writeln!(
+            ui.status(),
+            "Fetched {count} missing parent commits from remote {remote}",
+            count = missing_ids.len() - remaining_ids.len(),
+            remote = remote.as_symbol()
+        )?;
+        missing_ids = remaining_ids;
+        if missing_ids.is_empty() {
+            return Ok(());
+        }
+    }
+    Err(user_error(format!(
+        "Failed to fetch some missing parent commits: {}",
+        missing_ids.iter().map(short_commit_hash).join(", ")
+    )))
+}
+
+fn detect_pull_request_refs(
+    git_repo: &gix::Repository,
+    remote: &RemoteName,
+) -> Result<GitPullRequestRefs, CommandError> {
+    git_repo
+        .try_find_remote(remote.as_str())
+        .and_then(Result::ok)
+        .and_then(|remote| {
+            let url = remote.url(gix::remote::Direction::Fetch)?;
+            GitPullRequestRefs::detect_from_url(url)
+        })
+        .ok_or_else(|| {
+            user_error(format!(
+                "Cannot detect the forge of remote {remote}",
+                remote = remote.as_symbol()
+            ))
+            .hinted("Use `--prs=github` or `--prs=gitlab` to specify it.")
+        })
+}
+
+/// Pushes the remote bookmarks and tags of `source_remote` to `mirror_remote`,
+/// and commits the transaction.
+async fn mirror_remote_refs(
+    ui: &mut Ui,
+    mut tx: WorkspaceCommandTransaction<'_>,
+    source_remote: &RemoteName,
+    mirror_remote: &RemoteName,
+    prune: GitFetchPrune,
+    git_settings: &GitSettings,
+    tx_description: String,
+) -> Result<(), CommandError> {
+    let view = tx.repo().view();
+    let ref_updates = GitPushRefTargets {
+        bookmarks: diff_mirror_refs(
+            view.remote_bookmarks(source_remote),
+            view.remote_bookmarks(mirror_remote),
+            prune.bookmarks,
+        ),
+        tags: diff_mirror_refs(
+            view.remote_tags(source_remote),
+            view.remote_tags(mirror_remote),
+            prune.tags,
+        ),
+    };
+    if ref_updates.bookmarks.is_empty() && ref_updates.tags.is_empty() {
+        writeln!(
+            ui.status(),
+            "Mirror remote {remote} is already up to date.",
+            remote = mirror_remote.as_symbol()
+        )?;
+        tx.finish(ui, tx_description).await?;
+        return Ok(());
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Changes to push to {remote}:",
+            remote = mirror_remote.as_symbol()
+        )?;
+        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &ref_updates)?;
+    }
+    let push_stats = git::push_refs(
+        tx.repo_mut(),
+        git_settings.to_subprocess_options(),
+        mirror_remote,
+        &ref_updates,
+        &mut GitSubprocessUi::new(ui),
+        &GitPushOptions {
+            tag_source_remote: Some(source_remote.to_owned()),
+            ..Default::default()
+        },
+    )?;
+    print_push_stats(ui, &push_stats)?;
+    // Fetched refs should be committed even if the push failed.
     tx.finish(
         ui,
         format!(
-            "fetch from git remote(s) {}",
-            matching_remotes.iter().map(|n| n.as_symbol()).join(",")
+            "{tx_description} and mirror into git remote {remote}",
+            remote = mirror_remote.as_symbol()
         ),
     )
     .await?;
-    Ok(())
+    if push_stats.all_ok() {
+        Ok(())
+    } else {
+        Err(user_error("Failed to push some bookmarks"))
+    }
+}
+
+/// Returns `(name, [mirror_target, source_target])`s to make the mirror refs
+/// match the source refs, sorted by name.
+///
+/// Conflicted refs are skipped. Refs missing in the source are deleted from
+/// the mirror only if `prune` is specified.
+fn diff_mirror_refs<'a>(
+    source_refs: impl IntoIterator<Item = (&'a RefName, &'a RemoteRef)>,
+    mirror_refs: impl IntoIterator<Item = (&'a RefName, &'a RemoteRef)>,
+    prune: bool,
+) -> Vec<(RefNameBuf, Diff<Option<CommitId>>)> {
+    let source_refs: HashMap<_, _> = source_refs.into_iter().collect();
+    let mirror_refs: HashMap<_, _> = mirror_refs.into_iter().collect();
+    let mut updates = Vec::new();
+    for (&name, source_ref) in &source_refs {
+        let mirror_target = mirror_refs
+            .get(name)
+            .map_or(RefTarget::absent_ref(), |mirror_ref| &mirror_ref.target);
+        if source_ref.target.has_conflict()
+            || mirror_target.has_conflict()
+            || source_ref.target == *mirror_target
+        {
+            continue;
+        }
+        let update = Diff::new(
+            mirror_target.as_normal().cloned(),
+            source_ref.target.as_normal().cloned(),
+        );
+        updates.push((name.to_owned(), update));
+    }
+    if prune {
+        for (&name, mirror_ref) in &mirror_refs {
+            if source_refs.contains_key(name) {
+                continue;
+            }
+            if let Some(id) = mirror_ref.target.as_normal() {
+                updates.push((name.to_owned(), Diff::new(Some(id.clone()), None)));
+            }
+        }
+    }
+    updates.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    updates
 }
 
 const DEFAULT_REMOTE: &RemoteName = RemoteName::new("origin");
@@ -283,6 +782,32 @@ fn get_default_fetch_remotes(
     }
 }
 
+/// Returns bookmarks to fetch from `remote` if not specified by command
+/// arguments.
+pub(crate) fn get_default_fetch_bookmarks(
+    ui: &Ui,
+    remote_settings: &RemoteSettingsMap,
+    git_repo: &gix::Repository,
+    remote: &RemoteName,
+) -> Result<StringExpression, CommandError> {
+    if let Some(expr) = parse_remote_fetch_bookmarks(ui, remote_settings, remote)? {
+        Ok(expr)
+    } else {
+        let (ignored, expr) = load_default_fetch_bookmarks(remote, git_repo)?;
+        warn_ignored_refspecs(ui, remote, ignored)?;
+        Ok(expr)
+    }
+}
+
+/// Returns tags to fetch from `remote` if not specified by command arguments.
+pub(crate) fn get_default_fetch_tags(
+    ui: &Ui,
+    remote_settings: &RemoteSettingsMap,
+    remote: &RemoteName,
+) -> Result<StringExpression, CommandError> {
+    Ok(parse_remote_fetch_tags(ui, remote_settings, remote)?.unwrap_or_else(StringExpression::all))
+}
+
 fn warn_if_branches_not_found(
     ui: &mut Ui,
     tx: &WorkspaceCommandTransaction,
@@ -314,6 +839,81 @@ fn warn_if_branches_not_found(
     )
 }
 
+fn warn_if_tags_not_moved(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    stats: &GitImportStats,
+) -> io::Result<()> {
+    let view = tx.repo().view();
+    let mut kept_tags = stats
+        .changed_remote_tags
+        .iter()
+        .filter(|update| {
+            let remote_ref = view.get_remote_tag(update.symbol.as_ref());
+            let local_target = view.get_local_tag(&update.symbol.name);
+            remote_ref.is_tracked()
+                && local_target.is_present()
+                && *local_target != update.new_target
+        })
+        .map(|update| update.symbol.as_ref())
+        .peekable();
+    if kept_tags.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(
+        ui.warning_default(),
+        "Local tags were not updated to match the remote tags: {}",
+        kept_tags.join(", ")
+    )?;
+    writeln!(
+        ui.hint_default(),
+        "Use `jj tag set --allow-move` or `jj tag delete` to update the local tags."
+    )
+}
+
+/// Verifies signatures of the fetched annotated tags.
+///
+/// A signature made by a key which isn't in the keyring isn't considered bad.
+fn print_tag_signatures(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    stats: &GitImportStats,
+) -> io::Result<()> {
+    for update in &stats.changed_remote_tags {
+        let Some(commit_id) = update.new_target.as_normal() else {
+            continue;
+        };
+        let symbol = update.symbol.as_ref();
+        match git::verify_tag_signature(tx.repo(), symbol.name, Some(symbol.remote), commit_id) {
+            Ok(None) => {}
+            Ok(Some(verification)) => match verification.status {
+                SigStatus::Good => {
+                    let mut out = ui.status();
+                    write!(out, "Good signature on tag {symbol}")?;
+                    if let Some(signer) = verification.display.or(verification.key) {
+                        write!(out, " by {signer}")?;
+                    }
+                    writeln!(out)?;
+                }
+                SigStatus::Unknown => {
+                    writeln!(ui.status(), "Tag {symbol} is signed by an unknown key")?;
+                }
+                SigStatus::Bad => {
+                    writeln!(ui.warning_default(), "Bad signature on tag {symbol}")?;
+                }
+            },
+            Err(err) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to verify signature on tag {symbol}: {err}"
+                )?;
+                print_error_sources(ui, err.source())?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn warn_ignored_refspecs(
     ui: &Ui,
     remote_name: &RemoteName,
@@ -12,17 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
 use jj_lib::git;
+use jj_lib::git::GitImportOptions;
+use jj_lib::git::GitRefKind;
 use jj_lib::git::GitSettings;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::ref_name::WorkspaceNameBuf;
+use jj_lib::repo::Repo as _;
+use jj_lib::workspace_store::SimpleWorkspaceStore;
+use jj_lib::workspace_store::WorkspaceStore as _;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
 use crate::git_util::load_git_import_options;
-use crate::git_util::print_git_import_stats;
+use crate::git_util::non_clashing_bookmark_name;
+use crate::git_util::print_git_import_stats_with_targets;
+use crate::git_util::rename_local_bookmark;
 use crate::ui::Ui;
 
 /// Update repo with changes made in the underlying Git repo
 ///
+/// The imported bookmarks and tags are listed per category. Updated refs are
+/// printed with their old and new target commits.
+///
 /// Commits that are no longer reachable from any branch in the Git repo will be
 /// considered abandoned in the Git repo, and will be abandoned in the jj
 /// repo to match the Git repo. Set `git.abandon-unreachable-commits` to `false`
@@ -34,23 +53,177 @@ use crate::ui::Ui;
 /// There is no need to run this command if you're in colocated workspace
 /// because the import happens automatically there.
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitImportArgs {}
+pub struct GitImportArgs {
+    /// Import the HEAD of the Git worktree of the given workspace
+    ///
+    /// The working-copy commit of the workspace will be moved on top of the
+    /// HEAD of the Git worktree checked out at the workspace root. Other
+    /// workspaces are left untouched.
+    ///
+    /// This defaults to the current workspace if it's checked out as a linked
+    /// Git worktree. The HEAD of the main worktree isn't imported then.
+    #[arg(long, value_name = "WORKSPACE")]
+    #[arg(add = ArgValueCandidates::new(complete::workspaces))]
+    onto: Option<WorkspaceNameBuf>,
+
+    /// Import only Git branches, leaving tags untouched
+    #[arg(long, conflicts_with = "tags_only")]
+    branches_only: bool,
+
+    /// Import only Git tags, leaving bookmarks untouched
+    #[arg(long)]
+    tags_only: bool,
+
+    /// What to do with imported bookmarks whose names collide with other
+    /// bookmarks
+    ///
+    /// Git doesn't allow a branch name that looks like a parent directory of
+    /// another (e.g. `foo` and `foo/bar`), so such bookmarks can't be exported
+    /// back to Git.
+    #[arg(long, value_name = "POLICY", value_enum, default_value_t = OnCollision::Keep)]
+    on_collision: OnCollision,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum OnCollision {
+    /// Import the bookmark as-is
+    Keep,
+    /// Don't import new tracked bookmarks colliding with other tracked
+    /// bookmarks, and report the Git refs that were skipped
+    Skip,
+    /// Rename the newly imported bookmark by adding a numeric suffix to the
+    /// colliding part of its name (e.g. `foo-1` or `foo-1/bar`)
+    Rename,
+}
 
 pub async fn cmd_git_import(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitImportArgs,
+    args: &GitImportArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui).await?;
     let git_settings = GitSettings::from_settings(workspace_command.settings())?;
     let remote_settings = workspace_command.settings().remote_settings()?;
-    let import_options = load_git_import_options(ui, &git_settings, &remote_settings)?;
+    let import_options = GitImportOptions {
+        skip_clashing_bookmarks: args.on_collision == OnCollision::Skip,
+        ..load_git_import_options(ui, &git_settings, &remote_settings)?
+    };
+    let onto_workspace = args
+        .onto
+        .as_ref()
+        .map(|name| {
+            let repo_path = workspace_command.repo_path();
+            if workspace_command
+                .repo()
+                .view()
+                .get_wc_commit_id(name)
+                .is_none()
+            {
+                return Err(user_error(format!(
+                    "No such workspace: {}",
+                    name.as_symbol()
+                )));
+            }
+            let path = SimpleWorkspaceStore::load(repo_path)?
+                .get_workspace_path(name)?
+                .ok_or_else(|| {
+                    user_error(format!(
+                        "Workspace {} has no recorded path",
+                        name.as_symbol()
+                    ))
+                })?;
+            Ok((name.clone(), repo_path.join(path)))
+        })
+        .transpose()?;
+    // A linked worktree has its own HEAD, which should be routed to the
+    // workspace checked out there.
+    let workspace_root = workspace_command.workspace_root();
+    let onto_workspace = match onto_workspace {
+        Some(onto) => Some(onto),
+        None if git::is_linked_git_worktree(workspace_command.repo().store(), workspace_root)? => {
+            let name = workspace_command.workspace_name().to_owned();
+            Some((name, workspace_root.to_owned()))
+        }
+        None => None,
+    };
     let mut tx = workspace_command.start_transaction();
-    // In non-colocated workspace, Git HEAD will never be moved internally by jj.
-    // That's why cmd_git_export() doesn't export the HEAD ref.
-    git::import_head(tx.repo_mut()).await?;
-    let stats = git::import_refs(tx.repo_mut(), &import_options).await?;
-    print_git_import_stats(ui, &tx, &stats)?;
+    if let Some((name, workspace_root)) = onto_workspace {
+        let head_commit = git::import_worktree_head(tx.repo_mut(), &workspace_root).await?;
+        let wc_commit_id = tx.repo().view().get_wc_commit_id(&name).unwrap().clone();
+        let wc_commit = tx.repo().store().get_commit_async(&wc_commit_id).await?;
+        if let Some(head_commit) = head_commit
+            && wc_commit.parent_ids() != [head_commit.id().clone()]
+        {
+            tx.repo_mut().check_out(name.clone(), &head_commit).await?;
+            writeln!(
+                ui.status(),
+                "Reset the working copy parent of workspace {} to the new Git HEAD.",
+                name.as_symbol()
+            )?;
+        }
+    } else {
+        // In non-colocated workspace, Git HEAD will never be moved internally by
+        // jj. That's why cmd_git_export() doesn't export the HEAD ref.
+        git::import_head(tx.repo_mut()).await?;
+    }
+    let git_ref_filter = |kind, _: RemoteRefSymbol<'_>| match kind {
+        GitRefKind::Bookmark => !args.tags_only,
+        GitRefKind::Tag => !args.branches_only,
+    };
+    let mut stats = git::import_some_refs(tx.repo_mut(), &import_options, git_ref_filter).await?;
+    // Refs with invalid names are only looked up by explicit import because
+    // it requires walking the Git refs directory.
+    stats
+        .failed_ref_names
+        .extend(git::find_invalid_loose_ref_names(
+            tx.repo().store(),
+            git_ref_filter,
+        )?);
+    stats.failed_ref_names.sort_unstable();
+    print_git_import_stats_with_targets(ui, &tx, &stats)?;
+    if args.on_collision == OnCollision::Rename {
+        rename_colliding_bookmarks(ui, &mut tx)?;
+    }
     tx.finish(ui, "import git refs").await?;
     Ok(())
 }
+
+/// Renames local bookmarks created by the import if their names collide with
+/// other local bookmarks.
+fn rename_colliding_bookmarks(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+) -> Result<(), CommandError> {
+    let base_view = tx.base_repo().view();
+    let imported_names: Vec<RefNameBuf> = tx
+        .repo()
+        .view()
+        .local_bookmarks()
+        .filter(|(name, _)| base_view.get_local_bookmark(name).is_absent())
+        .map(|(name, _)| name.to_owned())
+        .collect();
+    for name in imported_names {
+        let view = tx.repo().view();
+        let Some(other_name) = view
+            .local_bookmarks()
+            .map(|(other_name, _)| other_name)
+            .find(|other_name| git::bookmark_names_clash(&name, other_name))
+        else {
+            continue;
+        };
+        let taken = view
+            .local_bookmarks()
+            .map(|(name, _)| name.to_owned())
+            .collect();
+        let new_name = non_clashing_bookmark_name(view, &taken, &name, other_name);
+        writeln!(
+            ui.status(),
+            "Renamed imported bookmark {name} to {new_name} to avoid collision with {other_name}",
+            name = name.as_symbol(),
+            new_name = new_name.as_symbol(),
+            other_name = other_name.as_symbol(),
+        )?;
+        rename_local_bookmark(tx.repo_mut(), &name, &new_name);
+    }
+    Ok(())
+}
@@ -21,6 +21,8 @@ mod init;
 mod push;
 mod remote;
 mod root;
+mod submodule;
+mod verify;
 
 use std::io::Write as _;
 
@@ -56,6 +58,10 @@ use self::remote::RemoteCommand;
 use self::remote::cmd_git_remote;
 use self::root::GitRootArgs;
 use self::root::cmd_git_root;
+use self::submodule::GitSubmoduleCommand;
+use self::submodule::cmd_git_submodule;
+use self::verify::GitVerifyArgs;
+use self::verify::cmd_git_verify;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::CommandError;
@@ -87,6 +93,9 @@ pub enum GitCommand {
     #[command(subcommand)]
     Remote(RemoteCommand),
     Root(GitRootArgs),
+    #[command(subcommand)]
+    Submodule(GitSubmoduleCommand),
+    Verify(GitVerifyArgs),
 }
 
 pub async fn cmd_git(
@@ -104,6 +113,8 @@ pub async fn cmd_git(
         GitCommand::Push(args) => cmd_git_push(ui, command, args).await,
         GitCommand::Remote(args) => cmd_git_remote(ui, command, args).await,
         GitCommand::Root(args) => cmd_git_root(ui, command, args).await,
+        GitCommand::Submodule(subcommand) => cmd_git_submodule(ui, command, subcommand).await,
+        GitCommand::Verify(args) => cmd_git_verify(ui, command, args).await,
     }
 }
 
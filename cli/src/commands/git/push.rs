@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future;
@@ -32,11 +33,17 @@ use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
+use jj_lib::git::GitFetch;
+use jj_lib::git::GitFetchRefExpression;
 use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitPushRefTargets;
+use jj_lib::git::GitPushSigned;
 use jj_lib::git::GitSettings;
+use jj_lib::git::expand_fetch_refspecs;
+use jj_lib::git::get_git_backend;
 use jj_lib::index::IndexResult;
 use jj_lib::merge::Diff;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::RefTarget;
 use jj_lib::operation::Operation;
 use jj_lib::ref_name::RefName;
@@ -58,6 +65,7 @@ use jj_lib::revset::UserRevsetExpression;
 use jj_lib::rewrite::CommitRewriter;
 use jj_lib::signing::SignBehavior;
 use jj_lib::str_util::StringExpression;
+use jj_lib::str_util::StringMatcher;
 use jj_lib::view::View;
 
 use crate::cli_util::CommandHelper;
@@ -73,10 +81,14 @@ use crate::command_error::cli_error;
 use crate::command_error::cli_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
+use crate::commands::git::fetch::get_default_fetch_bookmarks;
+use crate::commands::git::fetch::get_default_fetch_tags;
 use crate::commands::git::get_single_remote;
 use crate::complete;
 use crate::formatter::Formatter;
 use crate::git_util::GitSubprocessUi;
+use crate::git_util::load_git_import_options;
+use crate::git_util::print_git_import_stats;
 use crate::git_util::print_push_stats;
 use crate::progress::ProgressWriter;
 use crate::revset_util::parse_bookmark_name;
@@ -100,9 +112,10 @@ use crate::ui::Ui;
 /// similar to `git push --force-with-lease` - the remote is updated only if its
 /// current state matches what Jujutsu last fetched.
 ///
-/// Unlike in Git, the remote to push to is not derived from the tracked remote
-/// bookmarks. Use `--remote` to select the remote Git repository by name. There
-/// is no option to push to multiple remotes.
+/// Use `--remote` to select the remote Git repository by name. If it isn't
+/// specified and the `git.push` setting isn't configured, bookmarks selected by
+/// `--bookmark` are pushed to the remote they track, similar to Git. There is
+/// no option to push to multiple remotes.
 ///
 /// Before the command actually moves, creates, or deletes a remote bookmark, it
 /// makes several [safety checks]. If there is a problem, you may need to run
@@ -121,8 +134,10 @@ use crate::ui::Ui;
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
     ///
-    /// This defaults to the `git.push` setting. If that is not configured, and
-    /// if there are multiple remotes, the remote named "origin" will be used.
+    /// This defaults to the `git.push` setting. If that is not configured, the
+    /// remote tracked by the bookmarks specified by `--bookmark` will be used,
+    /// preferring "origin" if it is one of the tracked remotes. Otherwise, if
+    /// there are multiple remotes, the remote named "origin" will be used.
     #[arg(long)]
     #[arg(add = ArgValueCandidates::new(complete::git_remotes))]
     remote: Option<RemoteNameBuf>,
@@ -223,6 +238,45 @@ pub struct GitPushArgs {
     #[arg(add = ArgValueCompleter::new(complete::branch_name_equals_any_revision))]
     named: Vec<String>,
 
+    /// Fetch from the remote before pushing
+    ///
+    /// The remote bookmarks and tags are updated first, so the push is checked
+    /// against the current state of the remote. If a bookmark has diverged
+    /// from the remote, it becomes conflicted and the push is aborted.
+    ///
+    /// Nothing is fetched with `--dry-run`.
+    #[arg(long)]
+    fetch_first: bool,
+
+    /// Also push bookmarks on the ancestors of the specified bookmarks
+    ///
+    /// Bookmarks on commits in `remote_bookmarks(remote=<remote>)..<bookmark>`
+    /// are pushed together with the specified bookmarks, parents first. This is
+    /// useful for pushing a stack of bookmarks, one per pull request. Stacked
+    /// bookmarks that can't be pushed are reported and skipped unless
+    /// `--atomic` is specified.
+    #[arg(long, requires = "bookmark")]
+    stack: bool,
+
+    /// Either update all refs on the remote or none of them
+    ///
+    /// If any reference can't be pushed, the push is aborted without updating
+    /// the remote.
+    #[arg(long)]
+    atomic: bool,
+
+    /// Only push if the remote's `HEAD` currently points to this revision
+    ///
+    /// The remote `HEAD` is checked right before pushing. If it points
+    /// elsewhere, for example because the remote's default branch moved, the
+    /// push is aborted.
+    ///
+    /// This check is best-effort: the remote `HEAD` is looked up separately
+    /// from the push, so it may still move in between.
+    #[arg(long, value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    expect_remote_head: Option<RevisionArg>,
+
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
@@ -230,6 +284,44 @@ pub struct GitPushArgs {
     /// Git push options
     #[arg(long, short)]
     option: Vec<String>,
+
+    /// Send a self-contained pack instead of a thin pack
+    ///
+    /// Thin packs may refer to objects the remote is expected to have, which
+    /// some servers fail to handle. This doesn't change which refs are pushed.
+    #[arg(long)]
+    no_thin: bool,
+
+    /// Sign the push with a push certificate
+    ///
+    /// The certificate is signed with the key configured in Git (see `git
+    /// config user.signingKey` and `gpg.format`.) With `if-asked`, the push
+    /// is signed only if the server supports signed pushes.
+    #[arg(
+        long,
+        value_name = "WHEN",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    signed: Option<SignedPush>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum SignedPush {
+    True,
+    False,
+    IfAsked,
+}
+
+impl From<SignedPush> for GitPushSigned {
+    fn from(value: SignedPush) -> Self {
+        match value {
+            SignedPush::True => Self::Yes,
+            SignedPush::False => Self::No,
+            SignedPush::IfAsked => Self::IfAsked,
+        }
+    }
 }
 
 fn make_updates_term(ref_updates: &GitPushRefTargets) -> String {
@@ -267,15 +359,34 @@ pub async fn cmd_git_push(
     args: &GitPushArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui).await?;
+    let expected_remote_head = if let Some(revision) = &args.expect_remote_head {
+        let commit = workspace_command.resolve_single_rev(ui, revision).await?;
+        Some(gix::ObjectId::from_bytes_or_panic(commit.id().as_bytes()))
+    } else {
+        None
+    };
 
     let default_remote;
     let remote = if let Some(name) = &args.remote {
         name
     } else {
-        default_remote = get_default_push_remote(ui, &workspace_command)?;
+        default_remote = get_default_push_remote(ui, &workspace_command, &args.bookmark)?;
         &default_remote
     };
 
+    if args.fetch_first {
+        if args.dry_run {
+            // Fetching would update the remote bookmarks in a new operation.
+            writeln!(
+                ui.warning_default(),
+                "Not fetching from {} because of --dry-run",
+                remote.as_symbol()
+            )?;
+        } else {
+            fetch_before_push(ui, &mut workspace_command, remote).await?;
+        }
+    }
+
     let mut tx = workspace_command.start_transaction();
     let view = tx.repo().view();
     let tx_description;
@@ -440,6 +551,7 @@ pub async fn cmd_git_push(
 
         let view = tx.repo().view();
         let bookmarks_by_name = find_bookmarks_to_push(ui, view, &args.bookmark, remote)?;
+        let stack_index = ref_updates.bookmarks.len();
         for &(name, targets) in &bookmarks_by_name {
             if !seen_bookmarks.insert(name) {
                 continue;
@@ -461,6 +573,49 @@ pub async fn cmd_git_push(
             }
         }
 
+        let mut commits_validator =
+            CommitsValidator::new(ui, tx.base_workspace_helper(), remote, args)?;
+        if args.stack {
+            let heads = bookmarks_by_name
+                .iter()
+                .flat_map(|(_, targets)| targets.local_target.added_ids())
+                .cloned()
+                .collect_vec();
+            let stacked_bookmarks =
+                find_stacked_bookmarks_to_push(tx.base_workspace_helper(), view, remote, heads)
+                    .await?;
+            let mut stack_updates = vec![];
+            for (name, targets) in stacked_bookmarks {
+                if !seen_bookmarks.insert(name) {
+                    continue;
+                }
+                let remote_symbol = name.to_remote_symbol(remote);
+                let allow_new = !has_tracked_remote_bookmarks(tx.repo(), name);
+                let allow_delete = false; // only bookmarks on commits are selected
+                let update =
+                    match classify_bookmark_update(remote_symbol, targets, allow_new, allow_delete)
+                    {
+                        Ok(Some(update)) => update,
+                        Ok(None) => continue,
+                        Err(reason) if args.atomic => return Err(reason.into()),
+                        Err(reason) => {
+                            reason.print(ui)?;
+                            continue;
+                        }
+                    };
+                match commits_validator.validate_update(&update).await? {
+                    Ok(()) => stack_updates.push((name.to_owned(), update)),
+                    Err(reason) if args.atomic => {
+                        return Err(reason.to_command_error(tx.base_workspace_helper()));
+                    }
+                    Err(reason) => reason.print_bookmark(ui, tx.base_workspace_helper(), name)?,
+                }
+            }
+            ref_updates
+                .bookmarks
+                .splice(stack_index..stack_index, stack_updates);
+        }
+
         let tags_by_name = find_tags_to_push(ui, view, &args.tag, remote)?;
         for &(name, targets) in &tags_by_name {
             if !seen_tags.insert(name) {
@@ -481,8 +636,6 @@ pub async fn cmd_git_push(
             }
         }
 
-        let mut commits_validator =
-            CommitsValidator::new(ui, tx.base_workspace_helper(), remote, args)?;
         // Error out if explicitly-specified targets can't be pushed.
         commits_validator
             .validate_updates(&ref_updates)
@@ -565,6 +718,11 @@ pub async fn cmd_git_push(
     let git_settings = GitSettings::from_settings(tx.settings())?;
     let options = GitPushOptions {
         remote_push_options: args.option.clone(),
+        no_thin: args.no_thin,
+        atomic: args.atomic,
+        signed: args.signed.map_or(GitPushSigned::No, GitPushSigned::from),
+        expected_remote_head,
+        tag_source_remote: None,
     };
     let push_stats = git::push_refs(
         tx.repo_mut(),
@@ -587,6 +745,38 @@ pub async fn cmd_git_push(
     }
 }
 
+/// Fetches bookmarks and tags from `remote` in a separate transaction.
+async fn fetch_before_push(
+    ui: &mut Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    remote: &RemoteName,
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let git_settings = GitSettings::from_settings(tx.settings())?;
+    let remote_settings = tx.settings().remote_settings()?;
+    let import_options = load_git_import_options(ui, &git_settings, &remote_settings)?;
+    let git_repo = get_git_backend(tx.repo().store())?.git_repo();
+    let ref_expr = GitFetchRefExpression {
+        bookmark: get_default_fetch_bookmarks(ui, &remote_settings, &git_repo, remote)?,
+        tag: get_default_fetch_tags(ui, &remote_settings, remote)?,
+    };
+    let expanded = expand_fetch_refspecs(remote, ref_expr)?;
+    let mut git_fetch = GitFetch::new(
+        tx.repo_mut(),
+        git_settings.to_subprocess_options(),
+        &import_options,
+    )?;
+    git_fetch.fetch(remote, expanded, &mut GitSubprocessUi::new(ui), None)?;
+    let import_stats = git_fetch.import_refs().await?;
+    print_git_import_stats(ui, &tx, &import_stats)?;
+    tx.finish(
+        ui,
+        format!("fetch from git remote(s) {}", remote.as_symbol()),
+    )
+    .await?;
+    Ok(())
+}
+
 #[derive(Clone, Debug)]
 struct RejectedCommitReason {
     commit: Commit,
@@ -880,7 +1070,7 @@ async fn sign_commits_before_push(
     Ok(ref_updates)
 }
 
-fn print_commits_ready_to_push(
+pub(crate) fn print_commits_ready_to_push(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
     ref_updates: &GitPushRefTargets,
@@ -949,10 +1139,22 @@ fn print_commits_ready_to_push(
 fn get_default_push_remote(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
+    bookmark_patterns: &[String],
 ) -> Result<RemoteNameBuf, CommandError> {
     let settings = workspace_command.settings();
     if let Some(remote) = settings.get_string("git.push").optional()? {
         Ok(remote.into())
+    } else if let Some(remote) =
+        get_tracked_push_remote(ui, workspace_command.repo().view(), bookmark_patterns)?
+    {
+        if remote != DEFAULT_REMOTE {
+            writeln!(
+                ui.hint_default(),
+                "Pushing to the tracked remote: {remote}",
+                remote = remote.as_symbol()
+            )?;
+        }
+        Ok(remote)
     } else if let Some(remote) = get_single_remote(workspace_command.repo().store())? {
         // similar to get_default_fetch_remotes
         if remote != DEFAULT_REMOTE {
@@ -968,6 +1170,44 @@ fn get_default_push_remote(
     }
 }
 
+/// Returns the remote tracked by the local bookmarks matching the patterns.
+///
+/// If the bookmarks are tracking multiple remotes, the default remote is chosen
+/// if it is one of them. Returns `None` if none of the bookmarks are tracking a
+/// remote, and an error if they are tracking different remotes other than the
+/// default remote.
+fn get_tracked_push_remote(
+    ui: &Ui,
+    view: &View,
+    bookmark_patterns: &[String],
+) -> Result<Option<RemoteNameBuf>, CommandError> {
+    if bookmark_patterns.is_empty() {
+        return Ok(None);
+    }
+    let bookmark_matcher = parse_union_name_patterns(ui, bookmark_patterns)?.to_matcher();
+    let tracked_remotes: BTreeSet<&RemoteName> = view
+        .remote_bookmarks_matching(&bookmark_matcher, &StringMatcher::all())
+        .filter(|(symbol, remote_ref)| {
+            remote_ref.is_tracked()
+                && symbol.remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO
+                && view.get_local_bookmark(symbol.name).is_present()
+        })
+        .map(|(symbol, _)| symbol.remote)
+        .collect();
+    if tracked_remotes.contains(&DEFAULT_REMOTE) {
+        return Ok(Some(DEFAULT_REMOTE.to_owned()));
+    }
+    match tracked_remotes.iter().exactly_one() {
+        Ok(remote) => Ok(Some((*remote).to_owned())),
+        Err(remotes) if remotes.len() == 0 => Ok(None),
+        Err(remotes) => Err(user_error(format!(
+            "Bookmarks to push are tracking multiple remotes: {}",
+            remotes.map(|remote| remote.as_symbol()).join(", ")
+        ))
+        .hinted("Use `--remote` to select the remote to push to.")),
+    }
+}
+
 #[derive(Clone, Debug)]
 struct RejectedRefUpdateReason {
     message: String,
@@ -1178,12 +1418,23 @@ async fn create_change_bookmarks(
                 if name.is_empty() {
                     return Err(user_error("Empty bookmark name generated"));
                 }
-                Ok(RefNameBuf::from(name))
+                let name = RefNameBuf::from(name);
+                let git_ref_name = format!("refs/heads/{}", name.as_str());
+                gix::validate::reference::name(git_ref_name.as_str().into()).map_err(|err| {
+                    user_error_with_message(
+                        format!("Invalid Git ref name generated: {}", name.as_symbol()),
+                        err,
+                    )
+                })?;
+                Ok(name)
             })
             .try_collect()?
     };
 
     for (commit, name) in iter::zip(&all_commits, &bookmark_names) {
+        if commit.id() == tx.repo().store().root_commit_id() {
+            return Err(user_error("Cannot push the root commit"));
+        }
         let target = RefTarget::normal(commit.id().clone());
         if tx.repo().view().get_local_bookmark(name) == &target {
             // Existing bookmark pointing to the commit, which is allowed
@@ -1305,6 +1556,47 @@ async fn find_default_target_revisions(
     Ok(commit_ids.try_collect().await?)
 }
 
+/// Finds bookmarks on the ancestors of `heads` which are not ancestors of the
+/// `remote` bookmarks. Bookmarks on the `heads` themselves are excluded. The
+/// bookmarks are ordered parents first.
+async fn find_stacked_bookmarks_to_push<'a>(
+    workspace_command: &WorkspaceCommandHelper,
+    view: &'a View,
+    remote: &RemoteName,
+    heads: Vec<CommitId>,
+) -> Result<Vec<(&'a RefName, LocalAndRemoteRef<'a>)>, CommandError> {
+    // remote_bookmarks(remote=<remote>)..heads- & bookmarks()
+    let expression = RevsetExpression::remote_bookmarks(
+        RemoteRefSymbolExpression {
+            name: StringExpression::all(),
+            remote: StringExpression::exact(remote),
+        },
+        None,
+    )
+    .range(&RevsetExpression::commits(heads).parents())
+    .intersection(&RevsetExpression::bookmarks(StringExpression::all()));
+    let commit_ids: Vec<_> = workspace_command
+        .attach_revset_evaluator(expression)
+        .evaluate_to_commit_ids()?
+        .try_collect()
+        .await?;
+    let bookmarks = commit_ids
+        .iter()
+        .rev()
+        .flat_map(|id| view.local_bookmarks_for_commit(id))
+        .map(|(name, _)| name)
+        .unique()
+        .map(|name| {
+            let targets = LocalAndRemoteRef {
+                local_target: view.get_local_bookmark(name),
+                remote_ref: view.get_remote_bookmark(name.to_remote_symbol(remote)),
+            };
+            (name, targets)
+        })
+        .collect();
+    Ok(bookmarks)
+}
+
 async fn find_target_revisions(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
@@ -15,13 +15,13 @@
 use std::io::Write as _;
 
 use bstr::BString;
-use gix::Remote;
 use jj_lib::git;
 use jj_lib::repo::Repo as _;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::command_error::user_error_with_message;
+use crate::git_util::get_remote_push_url;
 use crate::ui::Ui;
 
 /// List Git remotes
@@ -46,8 +46,8 @@ pub async fn cmd_git_remote_list(
             }
             None => continue, // ignore empty [remote "<name>"] section
         };
-        let fetch_url = get_url(&remote, gix::remote::Direction::Fetch);
-        let push_url = get_url(&remote, gix::remote::Direction::Push);
+        let fetch_url = format_url(remote.url(gix::remote::Direction::Fetch));
+        let push_url = format_url(get_remote_push_url(&remote).as_ref());
         if fetch_url == push_url {
             writeln!(ui.stdout(), "{remote_name} {fetch_url}")?;
         } else {
@@ -57,9 +57,7 @@ pub async fn cmd_git_remote_list(
     Ok(())
 }
 
-fn get_url(remote: &Remote, direction: gix::remote::Direction) -> BString {
-    remote
-        .url(direction)
-        .map(|url| url.to_bstring())
+fn format_url(url: Option<&gix::Url>) -> BString {
+    url.map(|url| url.to_bstring())
         .unwrap_or_else(|| "<no URL>".into())
 }
@@ -0,0 +1,86 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::git;
+use jj_lib::object_id::ObjectId as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// List Git submodules in a revision
+///
+/// Submodules are read from the `.gitmodules` file and the gitlink entries of
+/// the revision. Each submodule is printed with its path, URL, and recorded
+/// commit id. A submodule declared in `.gitmodules` without a gitlink entry is
+/// printed with `<no gitlink>` instead of the commit id. A gitlink without a
+/// `.gitmodules` entry is printed with `<not in .gitmodules>` instead of the
+/// URL.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitSubmoduleListArgs {
+    /// The revision to list submodules in
+    #[arg(long, short, default_value = "@", value_name = "REVSET")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: RevisionArg,
+}
+
+/// Inspect Git submodules
+///
+/// Submodules aren't otherwise supported yet. Their contents aren't checked
+/// out or updated.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum GitSubmoduleCommand {
+    List(GitSubmoduleListArgs),
+}
+
+pub async fn cmd_git_submodule(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &GitSubmoduleCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        GitSubmoduleCommand::List(args) => cmd_git_submodule_list(ui, command, args).await,
+    }
+}
+
+async fn cmd_git_submodule_list(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitSubmoduleListArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui).await?;
+    let commit = workspace_command
+        .resolve_single_rev(ui, &args.revision)
+        .await?;
+    let submodules = git::list_submodules(&commit.tree()).await?;
+    for submodule in &submodules {
+        let path = workspace_command.format_file_path(&submodule.path);
+        let url = match (&submodule.name, &submodule.url) {
+            (Some(_), Some(url)) => url.as_str(),
+            (Some(_), None) => "<no URL>",
+            (None, _) => "<not in .gitmodules>",
+        };
+        let commit_id = match &submodule.commit_id {
+            Some(id) => id.hex(),
+            None => "<no gitlink>".to_owned(),
+        };
+        writeln!(ui.stdout(), "{path} {url} {commit_id}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use futures::TryStreamExt as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetStreamExt as _;
+use jj_lib::signing::SigStatus;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error_with_message;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Verify the signatures of commits
+///
+/// For each commit, prints one of the following statuses followed by the
+/// commit summary:
+///
+/// * `good`: the signature is valid and made by a known key.
+///
+/// * `bad signature`: the signature doesn't match the commit.
+///
+/// * `unknown key`: the signature couldn't be checked, e.g. because the key
+///   isn't in the configured allowed signers.
+///
+/// * `unsigned`: the commit has no signature.
+///
+/// * `n/a`: the commit has conflicts, which can't be represented in a signed
+///   Git commit.
+///
+/// Signatures are verified with the configured [commit signing] backends.
+///
+/// [commit signing]:
+///     https://docs.jj-vcs.dev/latest/config/#commit-signing
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitVerifyArgs {
+    /// The revision(s) to verify
+    #[arg(long = "revision", short, default_value = "@", value_name = "REVSETS")]
+    #[arg(alias = "revisions")]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revisions: Vec<RevisionArg>,
+}
+
+pub async fn cmd_git_verify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitVerifyArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui).await?;
+    let repo = workspace_command.repo();
+    let commits: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate()?
+        .stream()
+        .commits(repo.store())
+        .try_collect()
+        .await?;
+    let mut formatter = ui.stdout_formatter();
+    for commit in &commits {
+        let status = if commit.has_conflict() {
+            "n/a"
+        } else if let Some(verification) = commit
+            .verification()
+            .map_err(|err| user_error_with_message("Failed to verify signature", err))?
+        {
+            match verification.status {
+                SigStatus::Good => "good",
+                SigStatus::Bad => "bad signature",
+                SigStatus::Unknown => "unknown key",
+            }
+        } else {
+            "unsigned"
+        };
+        write!(formatter, "{status}: ")?;
+        workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Reverse;
 use std::cmp::min;
 
 use clap_complete::ArgValueCandidates;
@@ -87,11 +88,17 @@ pub(crate) struct LogArgs {
 
     /// Limit number of revisions to show
     ///
-    /// Applied after revisions are filtered and reordered topologically, but
-    /// before being reversed.
+    /// Applied after revisions are filtered and reordered, but before being
+    /// reversed.
     #[arg(long, short = 'n')]
     limit: Option<usize>,
 
+    /// How to order the revisions
+    ///
+    /// Revisions sorted by date are shown as a flat list without the graph.
+    #[arg(long, value_name = "ORDER", value_enum, default_value_t = LogOrder::Topo)]
+    order: LogOrder,
+
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
@@ -119,18 +126,37 @@ pub(crate) struct LogArgs {
     #[arg(add = ArgValueCandidates::new(complete::template_aliases))]
     template: Option<String>,
 
+    /// Render each revision on one line with ref names decorated like `git log
+    /// --oneline --decorate`
+    ///
+    /// Bookmarks, tags, and remote bookmarks are shown as in `(HEAD -> main,
+    /// tag: v1, origin/main)`. The `HEAD ->` arrow points to the bookmark
+    /// checked out in the colocated Git repo.
+    #[arg(long, conflicts_with = "template")]
+    decorate_git_style: bool,
+
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
 
     /// Print the number of commits instead of showing them
-    #[arg(long, conflicts_with_all = ["DiffFormatArgs", "no_graph", "patch", "reversed", "template"])]
+    #[arg(long, conflicts_with_all = ["DiffFormatArgs", "no_graph", "patch", "reversed", "template", "decorate_git_style"])]
     count: bool,
 
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum LogOrder {
+    /// Children before parents
+    Topo,
+    /// Newest author date first, similar to `git log --author-date-order`
+    AuthorDate,
+    /// Newest committer date first, similar to `git log --date-order`
+    CommitterDate,
+}
+
 #[instrument(skip_all)]
 pub(crate) async fn cmd_log(
     ui: &mut Ui,
@@ -203,6 +229,7 @@ pub(crate) async fn cmd_log(
         let language = workspace_command.commit_template_language();
         let template_string = match &args.template {
             Some(value) => value.clone(),
+            None if args.decorate_git_style => "builtin_log_git_style(self)".to_owned(),
             None => settings.get_string("templates.log")?,
         };
         template = workspace_command
@@ -218,7 +245,7 @@ pub(crate) async fn cmd_log(
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
 
-        if !args.no_graph {
+        if !args.no_graph && args.order == LogOrder::Topo {
             let mut raw_output = formatter.raw()?;
             let mut graph = get_graphlog(graph_style, raw_output.as_mut());
             let mut stream: LocalBoxStream<_> = {
@@ -330,7 +357,25 @@ pub(crate) async fn cmd_log(
             }
         } else {
             let id_stream: LocalBoxStream<Result<CommitId, RevsetEvaluationError>> = {
-                let forward_stream = revset.stream().take(args.limit.unwrap_or(usize::MAX));
+                let forward_stream = match args.order {
+                    LogOrder::Topo => revset.stream().boxed_local(),
+                    LogOrder::AuthorDate | LogOrder::CommitterDate => {
+                        let mut commits: Vec<Commit> =
+                            revset.stream().commits(store).try_collect().await?;
+                        // Ties are broken by commit id to make the output stable.
+                        commits.sort_by_key(|commit| {
+                            let signature = if args.order == LogOrder::AuthorDate {
+                                commit.author()
+                            } else {
+                                commit.committer()
+                            };
+                            (Reverse(signature.timestamp.timestamp), commit.id().clone())
+                        });
+                        let ids = commits.into_iter().map(|commit| Ok(commit.id().clone()));
+                        stream::iter(ids).boxed_local()
+                    }
+                };
+                let forward_stream = forward_stream.take(args.limit.unwrap_or(usize::MAX));
                 if args.reversed {
                     let entries: Vec<_> = forward_stream.try_collect().await?;
                     stream::iter(entries.into_iter().rev().map(Ok)).boxed_local()
@@ -200,6 +200,10 @@ pub(crate) async fn cmd_new(
             advance_bookmarks_target = Some(parent_commit_ids[0].clone());
             advanceable_bookmarks =
                 workspace_command.get_advanceable_bookmarks(ui, parent_commits[0].parent_ids())?;
+            if advanceable_bookmarks.is_empty() {
+                advanceable_bookmarks =
+                    workspace_command.get_advanceable_head_bookmark(&parent_commits[0])?;
+            }
         }
     }
 
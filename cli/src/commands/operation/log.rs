@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
 use std::slice;
 
 use clap_complete::ArgValueCandidates;
@@ -21,6 +22,7 @@ use futures::stream;
 use futures::stream::LocalBoxStream;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::reverse_graph;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
@@ -102,6 +104,12 @@ pub struct OperationLogArgs {
     /// `revsets.op-diff-changes-in` setting.
     #[arg(long, value_name = "REVSETS")]
     show_changes_in: Option<String>,
+
+    /// Print only the full operation ids, one per line
+    ///
+    /// This is meant for scripts. The graph and templates aren't used.
+    #[arg(long, conflicts_with_all = ["template", "op_diff", "patch"])]
+    op_id_only: bool,
 }
 
 pub async fn cmd_op_log(
@@ -134,6 +142,9 @@ async fn do_op_log(
     current_op: &Operation,
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
+    if args.op_id_only {
+        return print_op_ids(ui, current_op, args).await;
+    }
     let settings = repo_loader.settings();
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
@@ -295,3 +306,23 @@ async fn do_op_log(
 
     Ok(())
 }
+
+async fn print_op_ids(
+    ui: &mut Ui,
+    current_op: &Operation,
+    args: &OperationLogArgs,
+) -> Result<(), CommandError> {
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let stream =
+        op_walk::walk_ancestors(slice::from_ref(current_op)).take(args.limit.unwrap_or(usize::MAX));
+    let mut stream: LocalBoxStream<'_, _> = if args.reversed {
+        stream::iter(stream.collect::<Vec<_>>().await.into_iter().rev()).boxed()
+    } else {
+        stream.boxed_local()
+    };
+    while let Some(op) = stream.try_next().await? {
+        writeln!(formatter, "{}", op.id().hex())?;
+    }
+    Ok(())
+}
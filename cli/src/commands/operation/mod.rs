@@ -78,6 +78,11 @@ pub async fn cmd_operation(
 pub(crate) enum RevertWhatToRestore {
     /// The jj repo state and local bookmarks
     Repo,
+    /// The visible commits and the working-copy commits, without the local
+    /// bookmarks and tags
+    Commits,
+    /// The local bookmarks and tags
+    LocalRefs,
     /// The remote-tracking bookmarks. Do not restore these if you'd like to
     /// push after the undo
     RemoteTracking,
@@ -95,7 +100,13 @@ pub(crate) fn view_with_desired_portions_restored(
     current_view: &jj_lib::op_store::View,
     what: &[RevertWhatToRestore],
 ) -> jj_lib::op_store::View {
-    let repo_source = if what.contains(&RevertWhatToRestore::Repo) {
+    let restores = |portion| what.contains(&RevertWhatToRestore::Repo) || what.contains(&portion);
+    let commits_source = if restores(RevertWhatToRestore::Commits) {
+        view_being_restored
+    } else {
+        current_view
+    };
+    let refs_source = if restores(RevertWhatToRestore::LocalRefs) {
         view_being_restored
     } else {
         current_view
@@ -105,13 +116,23 @@ pub(crate) fn view_with_desired_portions_restored(
     } else {
         current_view
     };
+    let mut head_ids = commits_source.head_ids.clone();
+    if !std::ptr::eq(commits_source, refs_source) {
+        // Keep the commits pointed to by local refs visible.
+        let local_ref_ids = itertools::chain(
+            refs_source.local_bookmarks.values(),
+            refs_source.local_tags.values(),
+        )
+        .flat_map(|target| target.added_ids());
+        head_ids.extend(local_ref_ids.cloned());
+    }
     jj_lib::op_store::View {
-        head_ids: repo_source.head_ids.clone(),
-        local_bookmarks: repo_source.local_bookmarks.clone(),
-        local_tags: repo_source.local_tags.clone(),
+        head_ids,
+        local_bookmarks: refs_source.local_bookmarks.clone(),
+        local_tags: refs_source.local_tags.clone(),
         remote_views: remote_source.remote_views.clone(),
         git_refs: current_view.git_refs.clone(),
         git_head: current_view.git_head.clone(),
-        wc_commit_ids: repo_source.wc_commit_ids.clone(),
+        wc_commit_ids: commits_source.wc_commit_ids.clone(),
     }
 }
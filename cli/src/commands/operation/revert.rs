@@ -68,15 +68,43 @@ pub async fn cmd_op_revert(
     let repo_loader = tx.base_repo().loader();
     let repo_at_target_op = &repo_loader.load_at(&target_op).await?;
     let repo_at_target_op_parent = &repo_loader.load_at(&target_op_parent).await?;
-    tx.repo_mut()
-        .merge(repo_at_target_op, repo_at_target_op_parent)
-        .await?;
-    let new_view = view_with_desired_portions_restored(
-        tx.repo().view().store_view(),
-        tx.base_repo().view().store_view(),
-        &args.what,
-    );
+    let restores =
+        |portion| args.what.contains(&RevertWhatToRestore::Repo) || args.what.contains(&portion);
+    let new_view = if restores(RevertWhatToRestore::Commits) {
+        tx.repo_mut()
+            .merge(repo_at_target_op, repo_at_target_op_parent)
+            .await?;
+        view_with_desired_portions_restored(
+            tx.repo().view().store_view(),
+            tx.base_repo().view().store_view(),
+            &args.what,
+        )
+    } else {
+        // Merge in a separate transaction so the rewrites made by the reverted
+        // operation aren't recorded against the commits being kept.
+        let mut merge_tx = tx.base_repo().start_transaction();
+        merge_tx
+            .repo_mut()
+            .merge(repo_at_target_op, repo_at_target_op_parent)
+            .await?;
+        view_with_desired_portions_restored(
+            merge_tx.repo().view().store_view(),
+            tx.base_repo().view().store_view(),
+            &args.what,
+        )
+    };
     tx.repo_mut().set_view(new_view);
+    if restores(RevertWhatToRestore::Commits) && !restores(RevertWhatToRestore::LocalRefs) {
+        // Rebasing the descendants of the reverted rewrites also moves the
+        // local refs, so put them back afterwards.
+        tx.repo_mut().rebase_descendants().await?;
+        let new_view = view_with_desired_portions_restored(
+            tx.base_repo().view().store_view(),
+            tx.repo().view().store_view(),
+            &[RevertWhatToRestore::LocalRefs],
+        );
+        tx.repo_mut().set_view(new_view);
+    }
     if let Some(mut formatter) = ui.status_formatter() {
         write!(formatter, "Reverted operation: ")?;
         let template = tx.base_workspace_helper().operation_summary_template();
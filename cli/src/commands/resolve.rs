@@ -14,8 +14,15 @@
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use std::io::Write as _;
+
 use itertools::Itertools as _;
+use jj_lib::conflicts::ConflictMaterializeOptions;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -24,6 +31,7 @@ use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::complete;
 use crate::formatter::FormatterExt as _;
 use crate::ui::Ui;
@@ -66,6 +74,13 @@ pub(crate) struct ResolveArgs {
     #[arg(add = ArgValueCandidates::new(complete::merge_editors))]
     tool: Option<String>,
 
+    /// Instead of resolving the conflict, print the conflicted file contents
+    ///
+    /// The conflict is materialized using the configured conflict marker
+    /// style. The paths must match exactly one conflicted file.
+    #[arg(long, conflicts_with_all = ["list", "tool"])]
+    print: bool,
+
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
@@ -105,6 +120,45 @@ pub(crate) async fn cmd_resolve(
         );
     }
 
+    if args.print {
+        let num_conflicts = conflicts.len();
+        let Ok((path, value)) = conflicts.into_iter().exactly_one() else {
+            return Err(user_error(format!(
+                "--print requires exactly one conflicted path, but {num_conflicts} paths are \
+                 conflicted"
+            )));
+        };
+        let repo = workspace_command.repo();
+        let materialized =
+            materialize_tree_value(repo.store(), &path, value?, tree.labels()).await?;
+        match materialized {
+            MaterializedTreeValue::FileConflict(file) => {
+                let options = ConflictMaterializeOptions {
+                    marker_style: workspace_command.env().conflict_marker_style(),
+                    marker_len: None,
+                    merge: repo.store().merge_options().clone(),
+                };
+                materialize_merge_result(
+                    &file.contents,
+                    &file.labels,
+                    &mut ui.stdout_formatter(),
+                    &options,
+                )?;
+            }
+            MaterializedTreeValue::OtherConflict { id, labels } => {
+                ui.stdout_formatter()
+                    .write_all(id.describe(&labels).as_bytes())?;
+            }
+            _ => {
+                let ui_path = workspace_command.format_file_path(&path);
+                return Err(user_error(format!(
+                    "Cannot print the conflict at path '{ui_path}'"
+                )));
+            }
+        }
+        return Ok(());
+    }
+
     let repo_paths = conflicts
         .iter()
         .map(|(path, _)| path.as_ref())
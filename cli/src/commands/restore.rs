@@ -16,9 +16,15 @@ use std::io::Write as _;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+#[cfg(feature = "git")]
+use futures::StreamExt as _;
 use indoc::formatdoc;
 use itertools::Itertools as _;
 use jj_lib::commit::conflict_label_for_commits;
+#[cfg(feature = "git")]
+use jj_lib::git;
+#[cfg(feature = "git")]
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::merge::Diff;
 use jj_lib::object_id::ObjectId as _;
 use tracing::instrument;
@@ -28,6 +34,8 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::command_error::user_error;
+#[cfg(feature = "git")]
+use crate::command_error::user_error_with_message;
 use crate::complete;
 use crate::ui::Ui;
 
@@ -45,6 +53,9 @@ use crate::ui::Ui;
 /// similar to `jj abandon`, except that it leaves an empty revision with its
 /// description and other metadata preserved.
 ///
+/// In a workspace colocated with Git, `--from-git-index` restores paths from
+/// the Git index instead, for example to pick up changes staged by a Git tool.
+///
 /// See `jj diffedit` if you'd like to restore portions of files rather than
 /// entire files.
 #[derive(clap::Args, Clone, Debug)]
@@ -98,6 +109,32 @@ pub(crate) struct RestoreArgs {
     /// Preserve the content (not the diff) when rebasing descendants
     #[arg(long)]
     restore_descendants: bool,
+
+    /// Restore from the Git index (source)
+    ///
+    /// Conflicted index entries are restored as conflicts unless `--stage` is
+    /// specified. Files marked as intent-to-add are left unchanged.
+    #[cfg(feature = "git")]
+    #[arg(long, conflicts_with_all = ["from", "changes_in"])]
+    from_git_index: bool,
+
+    /// Restore this stage of conflicted Git index entries
+    ///
+    /// The stage is ignored for paths that aren't conflicted in the index.
+    #[cfg(feature = "git")]
+    #[arg(long, value_enum, requires = "from_git_index")]
+    stage: Option<GitIndexStageArg>,
+}
+
+#[cfg(feature = "git")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+enum GitIndexStageArg {
+    /// The common ancestor of the conflict sides
+    Base,
+    /// The first side of the conflict
+    Ours,
+    /// The second side of the conflict
+    Theirs,
 }
 
 #[instrument(skip_all)]
@@ -107,7 +144,9 @@ pub(crate) async fn cmd_restore(
     args: &RestoreArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui).await?;
-    let (from_commits, from_tree, to_commit);
+    let (from_label, from_summary, from_tree, to_commit);
+    #[cfg(feature = "git")]
+    let mut index_conflicted_paths = None;
     if args.revision.is_some() {
         return Err(
             user_error("`jj restore` does not have a `--revision`/`-r` option.")
@@ -117,7 +156,43 @@ pub(crate) async fn cmd_restore(
                 ),
         );
     }
-    if args.from.is_some() || args.into.is_some() {
+    #[cfg(feature = "git")]
+    let from_git_index = args.from_git_index;
+    #[cfg(not(feature = "git"))]
+    let from_git_index = false;
+    if from_git_index {
+        #[cfg(feature = "git")]
+        {
+            if !workspace_command.working_copy_shared_with_git() {
+                return Err(user_error(
+                    "`--from-git-index` requires a workspace colocated with Git",
+                ));
+            }
+            to_commit = workspace_command
+                .resolve_single_rev(ui, args.into.as_ref().unwrap_or(&RevisionArg::AT))
+                .await?;
+            let stage = args.stage.map(|stage| match stage {
+                GitIndexStageArg::Base => git::GitIndexStage::Base,
+                GitIndexStageArg::Ours => git::GitIndexStage::Ours,
+                GitIndexStageArg::Theirs => git::GitIndexStage::Theirs,
+            });
+            let index_tree = git::tree_from_git_index(
+                workspace_command.repo().as_ref(),
+                &to_commit.tree(),
+                stage,
+            )
+            .await
+            .map_err(|err| user_error_with_message("Failed to read Git index", err))?;
+            from_tree = index_tree.tree;
+            from_label = "Git index".to_owned();
+            from_summary = "the Git index".to_owned();
+            if stage.is_some() {
+                index_conflicted_paths = Some(index_tree.conflicted_paths);
+            }
+        }
+        #[cfg(not(feature = "git"))]
+        unreachable!();
+    } else if args.from.is_some() || args.into.is_some() {
         to_commit = workspace_command
             .resolve_single_rev(ui, args.into.as_ref().unwrap_or(&RevisionArg::AT))
             .await?;
@@ -125,7 +200,8 @@ pub(crate) async fn cmd_restore(
             .resolve_single_rev(ui, args.from.as_ref().unwrap_or(&RevisionArg::AT))
             .await?;
         from_tree = from_commit.tree();
-        from_commits = vec![from_commit];
+        from_label = from_commit.conflict_label();
+        from_summary = workspace_command.format_commit_summary(&from_commit);
     } else {
         to_commit = workspace_command
             .resolve_single_rev(ui, args.changes_in.as_ref().unwrap_or(&RevisionArg::AT))
@@ -133,7 +209,13 @@ pub(crate) async fn cmd_restore(
         from_tree = to_commit
             .parent_tree(workspace_command.repo().as_ref())
             .await?;
-        from_commits = to_commit.parents().await?;
+        let from_commits = to_commit.parents().await?;
+        from_label = conflict_label_for_commits(&from_commits);
+        from_summary = from_commits
+            .iter()
+            .map(|commit| workspace_command.format_commit_summary(commit))
+            //      "You are restoring changes from: "
+            .join("\n                                ");
     }
     workspace_command.check_rewritable([to_commit.id()]).await?;
 
@@ -144,17 +226,12 @@ pub(crate) async fn cmd_restore(
     let to_tree = to_commit.tree();
     let format_instructions = || {
         formatdoc! {"
-            You are restoring changes from: {from_commits}
+            You are restoring changes from: {from_summary}
             to commit: {to_commit}
 
             The diff initially shows all changes restored. Adjust the right side until it
             shows the contents you want for the destination commit.
             ",
-            from_commits = from_commits
-                .iter()
-                .map(|commit| workspace_command.format_commit_summary(commit))
-                //      "You are restoring changes from: "
-                .join("\n                                "),
             to_commit = workspace_command.format_commit_summary(&to_commit),
         }
     };
@@ -162,15 +239,26 @@ pub(crate) async fn cmd_restore(
         .select(
             ui,
             Diff::new(&to_tree, &from_tree),
-            Diff::new(
-                to_commit.conflict_label(),
-                conflict_label_for_commits(&from_commits),
-            ),
+            Diff::new(to_commit.conflict_label(), from_label),
             &matcher,
             format_instructions,
         )
         .await?;
 
+    #[cfg(feature = "git")]
+    if let Some(conflicted_paths) = &index_conflicted_paths {
+        let mut diff_stream = to_tree.diff_stream(&new_tree, &EverythingMatcher);
+        while let Some(entry) = diff_stream.next().await {
+            if !conflicted_paths.contains(&entry.path) {
+                writeln!(
+                    ui.hint_default(),
+                    "Ignored `--stage` for path not conflicted in the Git index: {}",
+                    workspace_command.format_file_path(&entry.path)
+                )?;
+            }
+        }
+    }
+
     print_unmatched_explicit_paths(
         ui,
         &workspace_command,
@@ -14,6 +14,7 @@
 
 use std::collections::BTreeMap;
 
+use futures::StreamExt as _;
 use futures::TryStreamExt as _;
 use itertools::Itertools as _;
 use jj_lib::commit::Commit;
@@ -21,6 +22,7 @@ use jj_lib::copies::CopyRecords;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::Diff;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
@@ -37,6 +39,7 @@ use crate::cli_util::print_unmatched_explicit_paths;
 use crate::command_error::CommandError;
 use crate::diff_util::DiffFormat;
 use crate::diff_util::get_copy_records;
+use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
 use crate::ui::Ui;
 
@@ -61,6 +64,14 @@ pub(crate) struct StatusArgs {
     /// Restrict the status display to these paths
     #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Print working-copy changes in `git status --porcelain` format
+    ///
+    /// Each changed path is printed as `XY PATH`, where `XY` is `A ` (added),
+    /// `M ` (modified), `D ` (deleted), `UU` (conflicted), or `??`
+    /// (untracked). Paths are relative to the workspace root. Renames are
+    /// reported as a deletion and an addition.
+    #[arg(long)]
+    git: bool,
 }
 
 #[instrument(skip_all)]
@@ -82,6 +93,14 @@ pub(crate) async fn cmd_status(
         .transpose()?;
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
     let matcher = fileset_expression.to_matcher();
+    if args.git {
+        if let Some(wc_commit) = &maybe_wc_commit {
+            let status =
+                collect_working_copy_status(repo.as_ref(), wc_commit, snapshot_stats).await?;
+            print_git_porcelain_status(ui.stdout_formatter().as_mut(), &status, &matcher).await?;
+        }
+        return Ok(());
+    }
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
@@ -274,6 +293,38 @@ impl WorkingCopyStatus {
     }
 }
 
+async fn print_git_porcelain_status(
+    formatter: &mut dyn Formatter,
+    status: &WorkingCopyStatus,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    // Conflicts are reported whether or not they were inherited from the parents
+    let mut codes: BTreeMap<RepoPathBuf, &str> = status
+        .tree
+        .conflicts_matching(matcher)
+        .map(|(path, _)| (path, "UU"))
+        .collect();
+    let mut diff_stream = status.parent_tree.diff_stream(&status.tree, matcher);
+    while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+        let values = values?;
+        let code = if values.before.is_absent() {
+            "A "
+        } else if values.after.is_absent() {
+            "D "
+        } else {
+            "M "
+        };
+        codes.entry(path).or_insert(code);
+    }
+    for (path, code) in codes {
+        writeln!(formatter, "{code} {}", path.as_internal_file_string())?;
+    }
+    for path in status.untracked_paths_matching(matcher) {
+        writeln!(formatter, "?? {}", path.as_internal_file_string())?;
+    }
+    Ok(())
+}
+
 async fn collect_working_copy_status(
     repo: &dyn Repo,
     commit: &Commit,
@@ -0,0 +1,107 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCompleter;
+use jj_lib::git;
+use jj_lib::ref_name::RefNameBuf;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::command_error::user_error;
+use crate::complete;
+use crate::revset_util;
+use crate::ui::Ui;
+
+/// Create an annotated tag
+///
+/// The tag is exported to Git as an annotated tag object carrying the message
+/// and the tagger signature.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagCreateArgs {
+    /// Target revision to point to
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        visible_alias = "to",
+        value_name = "REVSET"
+    )]
+    #[arg(add = ArgValueCompleter::new(complete::revset_expression_all))]
+    revision: RevisionArg,
+
+    /// The tag message
+    #[arg(long = "message", short, value_name = "MESSAGE")]
+    message: String,
+
+    /// Tag name to create
+    #[arg(value_parser = revset_util::parse_tag_name)]
+    name: RefNameBuf,
+}
+
+pub async fn cmd_tag_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagCreateArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui).await?;
+    let target_commit = workspace_command
+        .resolve_single_rev(ui, &args.revision)
+        .await?;
+    let repo = workspace_command.repo().as_ref();
+    let name = &args.name;
+
+    if repo.view().get_local_tag(name).is_present() {
+        return Err(user_error(format!(
+            "Tag already exists: {name}",
+            name = name.as_symbol()
+        ))
+        .hinted("Use `jj tag set --allow-move` to move existing tags."));
+    }
+    if target_commit.is_discardable(repo).await? {
+        writeln!(ui.warning_default(), "Target revision is empty.")?;
+    }
+
+    let mut message = args.message.trim_end().to_owned();
+    message.push('\n');
+    let mut tx = workspace_command.start_transaction();
+    git::create_annotated_tag(
+        tx.repo_mut(),
+        name,
+        target_commit.id(),
+        &message,
+        &command.settings().signature(),
+    )?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(
+            formatter,
+            "Created annotated tag {name} pointing to ",
+            name = name.as_symbol()
+        )?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "create tag {name} at commit {id}",
+            name = name.as_symbol(),
+            id = target_commit.id()
+        ),
+    )
+    .await?;
+    Ok(())
+}
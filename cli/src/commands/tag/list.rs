@@ -17,6 +17,7 @@ use std::rc::Rc;
 
 use clap_complete::ArgValueCandidates;
 use futures::TryStreamExt as _;
+use itertools::Itertools as _;
 use jj_lib::repo::Repo as _;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::str_util::StringExpression;
@@ -28,6 +29,7 @@ use crate::cli_util::default_ignored_remote_name;
 use crate::command_error::CommandError;
 use crate::commit_ref_list;
 use crate::commit_ref_list::RefFilterPredicates;
+use crate::commit_ref_list::RefListItem;
 use crate::commit_ref_list::SortKey;
 use crate::commit_templater::CommitRef;
 use crate::complete;
@@ -187,7 +189,14 @@ pub async fn cmd_tag_list(
         include_synced_remotes: args.tracked || args.all_remotes || args.remotes.is_some(),
         include_untracked_remotes: !args.tracked && (args.all_remotes || args.remotes.is_some()),
     };
-    let mut list_items = commit_ref_list::collect_items(view.tags(), &predicates);
+    let mut list_items =
+        commit_ref_list::collect_items(view.tags(), &predicates, ignored_tracked_remote)
+            .into_iter()
+            .map(|item| RefListItem {
+                primary: item.primary.into_tag(),
+                tracked: item.tracked.into_iter().map(CommitRef::into_tag).collect(),
+            })
+            .collect_vec();
     commit_ref_list::sort(repo.store(), &mut list_items, &sort_keys)?;
 
     ui.request_pager();
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "git")]
+mod create;
 mod delete;
 mod list;
 mod set;
@@ -23,6 +25,10 @@ use jj_lib::ref_name::RefName;
 use jj_lib::str_util::StringExpression;
 use jj_lib::view::View;
 
+#[cfg(feature = "git")]
+use self::create::TagCreateArgs;
+#[cfg(feature = "git")]
+use self::create::cmd_tag_create;
 use self::delete::TagDeleteArgs;
 use self::delete::cmd_tag_delete;
 use self::list::TagListArgs;
@@ -36,6 +42,9 @@ use crate::ui::Ui;
 /// Manage tags.
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum TagCommand {
+    #[cfg(feature = "git")]
+    #[command(visible_alias("c"))]
+    Create(TagCreateArgs),
     #[command(visible_alias("d"))]
     Delete(TagDeleteArgs),
     #[command(visible_alias("l"))]
@@ -50,6 +59,8 @@ pub async fn cmd_tag(
     subcommand: &TagCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        #[cfg(feature = "git")]
+        TagCommand::Create(args) => cmd_tag_create(ui, command, args).await,
         TagCommand::Delete(args) => cmd_tag_delete(ui, command, args).await,
         TagCommand::List(args) => cmd_tag_list(ui, command, args).await,
         TagCommand::Set(args) => cmd_tag_set(ui, command, args).await,
@@ -23,6 +23,7 @@ use crate::command_error::user_error;
 #[cfg(feature = "git")]
 use crate::commands::git::is_push_operation;
 use crate::commands::operation::DEFAULT_REVERT_WHAT;
+use crate::commands::operation::RevertWhatToRestore;
 use crate::commands::operation::view_with_desired_portions_restored;
 use crate::ui::Ui;
 
@@ -40,14 +41,35 @@ use crate::ui::Ui;
 /// restore` to explicitly restore an older operation by its id (available in
 /// the operation log).
 #[derive(clap::Args, Clone, Debug)]
-pub struct UndoArgs {}
+pub struct UndoArgs {
+    /// What portions of the local state to undo
+    ///
+    /// `refs` reverts the bookmarks, tags, and remote-tracking bookmarks, and
+    /// leaves the commits and working-copy commits in place. `working-copy`
+    /// reverts the commits and working-copy commits, and leaves the refs in
+    /// place. A partial undo isn't part of the undo stack: it is recorded as a
+    /// regular operation, so a following `jj undo` reverts the partial undo
+    /// rather than an older operation.
+    #[arg(long, value_enum, default_value_t = UndoWhatToRestore::All)]
+    what: UndoWhatToRestore,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum UndoWhatToRestore {
+    /// The whole repo state
+    All,
+    /// The local bookmarks and tags, and the remote-tracking bookmarks
+    Refs,
+    /// The visible commits and the working-copy commits
+    WorkingCopy,
+}
 
 pub(crate) const UNDO_OP_DESC_PREFIX: &str = "undo: restore to operation ";
 
 pub async fn cmd_undo(
     ui: &mut Ui,
     command: &CommandHelper,
-    _: &UndoArgs,
+    args: &UndoArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui).await?;
 
@@ -149,11 +171,28 @@ pub async fn cmd_undo(
     }
 
     let mut tx = workspace_command.start_transaction();
-    let new_view = view_with_desired_portions_restored(
-        target_op_parent.view().await?.store_view(),
-        tx.base_repo().view().store_view(),
-        &DEFAULT_REVERT_WHAT,
-    );
+    let view_being_restored = target_op_parent.view().await?;
+    let current_view = tx.base_repo().view().store_view();
+    let new_view = match args.what {
+        UndoWhatToRestore::All => view_with_desired_portions_restored(
+            view_being_restored.store_view(),
+            current_view,
+            &DEFAULT_REVERT_WHAT,
+        ),
+        UndoWhatToRestore::Refs => view_with_desired_portions_restored(
+            view_being_restored.store_view(),
+            current_view,
+            &[
+                RevertWhatToRestore::LocalRefs,
+                RevertWhatToRestore::RemoteTracking,
+            ],
+        ),
+        UndoWhatToRestore::WorkingCopy => view_with_desired_portions_restored(
+            view_being_restored.store_view(),
+            current_view,
+            &[RevertWhatToRestore::Commits],
+        ),
+    };
     tx.repo_mut().set_view(new_view);
     if let Some(mut formatter) = ui.status_formatter() {
         let template = tx.base_workspace_helper().operation_summary_template();
@@ -166,11 +205,18 @@ pub async fn cmd_undo(
         template.format(&target_op_parent, formatter.as_mut())?;
         writeln!(formatter)?;
     }
-    tx.finish(
-        ui,
-        format!("{UNDO_OP_DESC_PREFIX}{}", target_op_parent.id().hex()),
-    )
-    .await?;
+    let description = match args.what {
+        UndoWhatToRestore::All => {
+            format!("{UNDO_OP_DESC_PREFIX}{}", target_op_parent.id().hex())
+        }
+        // Partial undo shouldn't be a part of the undo-stack. The next `jj undo`
+        // reverts it like any other operation.
+        UndoWhatToRestore::Refs => format!("undo refs of operation {}", target_op.id().hex()),
+        UndoWhatToRestore::WorkingCopy => {
+            format!("undo working copy of operation {}", target_op.id().hex())
+        }
+    };
+    tx.finish(ui, description).await?;
 
     Ok(())
 }
@@ -16,20 +16,51 @@ use std::io::Write as _;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
+use crate::command_error::cli_error;
 use crate::ui::Ui;
 
 /// Print the CLI help for all subcommands in Markdown
 #[derive(clap::Args, Clone, Debug)]
-pub struct UtilMarkdownHelp {}
+pub struct UtilMarkdownHelp {
+    /// Print the help only for the given subcommand and its subcommands
+    ///
+    /// The subcommand path is separated by dots, e.g. `git.fetch`.
+    #[arg(long, value_name = "PATH")]
+    command: Option<String>,
+}
 
 pub async fn cmd_util_markdown_help(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &UtilMarkdownHelp,
+    args: &UtilMarkdownHelp,
 ) -> Result<(), CommandError> {
     // If we ever need more flexibility, the code of `clap_markdown` is simple and
     // readable. We could reimplement the parts we need without trouble.
-    let markdown = clap_markdown::help_markdown_command(command.app()).into_bytes();
-    ui.stdout().write_all(&markdown)?;
+    let markdown = if let Some(path) = &args.command {
+        let mut full_name = command.app().get_name().to_owned();
+        let mut subcommand = command.app();
+        for name in path.split('.') {
+            subcommand = subcommand
+                .find_subcommand(name)
+                .ok_or_else(|| cli_error(format!("Unknown command: {}", path.replace('.', " "))))?;
+            // Resolve aliases so the command is documented under its full
+            // canonical path, e.g. `jj git fetch`.
+            full_name.push(' ');
+            full_name.push_str(subcommand.get_name());
+        }
+        let subcommand = subcommand
+            .clone()
+            .display_name(&full_name)
+            .bin_name(&full_name);
+        // The table of contents links are derived from the display name, which
+        // contains spaces unlike the anchors of the headings.
+        clap_markdown::help_markdown_command(&subcommand).replace(
+            &format!("](#{full_name}"),
+            &format!("](#{}", full_name.replace(' ', "-")),
+        )
+    } else {
+        clap_markdown::help_markdown_command(command.app())
+    };
+    ui.stdout().write_all(markdown.as_bytes())?;
     Ok(())
 }
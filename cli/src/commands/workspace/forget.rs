@@ -14,7 +14,11 @@
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+#[cfg(feature = "git")]
+use jj_lib::git;
 use jj_lib::ref_name::WorkspaceNameBuf;
+#[cfg(feature = "git")]
+use jj_lib::repo::Repo as _;
 use jj_lib::workspace_store::SimpleWorkspaceStore;
 use jj_lib::workspace_store::WorkspaceStore as _;
 use tracing::instrument;
@@ -26,14 +30,23 @@ use crate::ui::Ui;
 
 /// Stop tracking a workspace's working-copy commit in the repo
 ///
-/// The workspace will not be touched on disk. It can be deleted from disk
-/// before or after running this command.
+/// The workspace will not be touched on disk, unless `--colocate-cleanup` is
+/// specified. It can be deleted from disk before or after running this
+/// command.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceForgetArgs {
     /// Names of the workspaces to forget. By default, forgets only the current
     /// workspace.
     #[arg(add = ArgValueCandidates::new(complete::workspaces))]
     workspaces: Vec<WorkspaceNameBuf>,
+
+    /// Also remove the Git worktree registered for the workspaces
+    ///
+    /// This deletes the worktree's metadata from the Git repository and the
+    /// `.git` file in the workspace, so Git no longer lists the worktree.
+    #[cfg(feature = "git")]
+    #[arg(long)]
+    colocate_cleanup: bool,
 }
 
 #[instrument(skip_all)]
@@ -73,6 +86,20 @@ pub async fn cmd_workspace_forget(
     }
 
     let workspace_store = SimpleWorkspaceStore::load(workspace_command.repo_path())?;
+    #[cfg(feature = "git")]
+    let worktree_paths = if args.colocate_cleanup {
+        // Fail early rather than after the workspaces have been forgotten.
+        git::get_git_backend(workspace_command.repo().store())?;
+        let mut paths = Vec::new();
+        for ws in &forget_ws {
+            if let Some(path) = workspace_store.get_workspace_path(ws)? {
+                paths.push((*ws, workspace_command.repo_path().join(path)));
+            }
+        }
+        paths
+    } else {
+        vec![]
+    };
 
     // bundle every workspace forget into a single transaction, so that e.g.
     // undo correctly restores all of them at once.
@@ -94,5 +121,16 @@ pub async fn cmd_workspace_forget(
     };
 
     tx.finish(ui, description).await?;
+
+    #[cfg(feature = "git")]
+    for (ws, path) in worktree_paths {
+        if git::remove_git_worktree(workspace_command.repo().store(), &path)? {
+            writeln!(
+                ui.status(),
+                "Removed Git worktree for workspace {}",
+                ws.as_symbol()
+            )?;
+        }
+    }
     Ok(())
 }
@@ -28,6 +28,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigValue;
 use jj_lib::op_store::LocalRemoteRefTarget;
 use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RemoteName;
 use jj_lib::store::Store;
 use jj_lib::str_util::StringMatcher;
 
@@ -61,9 +62,13 @@ pub struct RefFilterPredicates {
 }
 
 /// Builds a list of local/remote refs matching the given predicates.
+///
+/// If `git_remote` is specified, the remote refs of that name are considered
+/// Git-tracking refs.
 pub fn collect_items<'a>(
     all_refs: impl IntoIterator<Item = (&'a RefName, LocalRemoteRefTarget<'a>)>,
     predicates: &RefFilterPredicates,
+    git_remote: Option<&RemoteName>,
 ) -> Vec<RefListItem> {
     let mut list_items = Vec::new();
     let refs_to_list = all_refs
@@ -96,7 +101,8 @@ pub fn collect_items<'a>(
             let primary = CommitRef::local(
                 name,
                 local_target.clone(),
-                remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
+                remote_refs.iter().copied(),
+                git_remote,
             );
             let tracked = tracked_remote_refs
                 .iter()
@@ -62,6 +62,7 @@ use jj_lib::op_store::OperationId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RemoteName;
 use jj_lib::ref_name::WorkspaceName;
 use jj_lib::ref_name::WorkspaceNameBuf;
 use jj_lib::repo::Repo;
@@ -72,6 +73,7 @@ use jj_lib::revset;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetContainingFn;
 use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::UserRevsetExpression;
 use jj_lib::rewrite::rebase_to_dest_parent;
@@ -91,6 +93,7 @@ use once_cell::unsync::OnceCell;
 use pollster::FutureExt as _;
 use serde::Serialize as _;
 
+use crate::cli_util::default_ignored_remote_name;
 use crate::diff_util;
 use crate::diff_util::DiffStatEntry;
 use crate::diff_util::DiffStats;
@@ -1004,13 +1007,25 @@ pub struct CommitKeywordCache<'repo> {
 
 impl<'repo> CommitKeywordCache<'repo> {
     pub fn bookmarks_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
-        self.bookmarks_index
-            .get_or_init(|| Rc::new(build_local_remote_refs_index(repo.view().bookmarks())))
+        self.bookmarks_index.get_or_init(|| {
+            let git_remote = default_ignored_remote_name(repo.store());
+            Rc::new(build_local_remote_refs_index(
+                repo.view().bookmarks(),
+                git_remote,
+                false,
+            ))
+        })
     }
 
     pub fn tags_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
-        self.tags_index
-            .get_or_init(|| Rc::new(build_local_remote_refs_index(repo.view().tags())))
+        self.tags_index.get_or_init(|| {
+            let git_remote = default_ignored_remote_name(repo.store());
+            Rc::new(build_local_remote_refs_index(
+                repo.view().tags(),
+                git_remote,
+                true,
+            ))
+        })
     }
 
     pub fn git_refs_index(&self, repo: &dyn Repo) -> &Rc<CommitRefsIndex> {
@@ -1137,6 +1152,28 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "git_headers",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            let [name_node] = function.expect_exact_arguments()?;
+            let name = template_parser::expect_string_literal(name_node)?.to_owned();
+            #[cfg(feature = "git")]
+            let git_backend = jj_lib::git::get_git_backend(language.repo.store()).ok();
+            #[cfg(not(feature = "git"))]
+            let _ = language;
+            let out_property = self_property.and_then(move |commit| {
+                #[cfg(feature = "git")]
+                if let Some(git_backend) = git_backend {
+                    let values = git_backend.read_commit_headers(commit.id(), &name)?;
+                    return Ok(values.iter().map(|v| v.to_string()).collect());
+                }
+                #[cfg(not(feature = "git"))]
+                let _ = (&commit, &name);
+                Ok(Vec::<String>::new())
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "working_copies",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1157,6 +1194,25 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "git_head_ref",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            #[cfg(feature = "git")]
+            let head_name = jj_lib::git::get_head_bookmark_name(repo.store())
+                .ok()
+                .flatten()
+                .map(|name| RefSymbolBuf(name.as_str().to_owned()));
+            #[cfg(not(feature = "git"))]
+            let head_name: Option<RefSymbolBuf> = None;
+            let out_property = self_property.map(move |commit| {
+                let is_git_head = repo.view().git_head().as_normal() == Some(commit.id());
+                head_name.clone().filter(|_| is_git_head)
+            });
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "bookmarks",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1302,6 +1358,15 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "conflict_sides",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|commit| Ok(i64::try_from(commit.tree_ids().num_sides())?));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map.insert(
         "empty",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1531,10 +1596,21 @@ pub struct CommitRef {
     #[serde(skip_serializing_if = "Option::is_none")] // local ref shouldn't have this field
     #[serde(serialize_with = "serialize_tracking_target")]
     tracking_ref: Option<TrackingRef>,
+    /// Git-tracking ref metadata if this is a local ref in Git-backed repo.
+    #[serde(flatten)] // remote ref shouldn't have these fields
+    git_tracking_ref: Option<GitTrackingRef>,
     /// Local ref is synchronized with all tracking remotes, or tracking remote
     /// ref is synchronized with the local.
     #[serde(skip)] // internal state used mainly for Template impl
     synced: bool,
+    /// Local ref is synchronized with all tracking remotes but the Git-tracking
+    /// ref, i.e. the local changes have yet to be exported to Git.
+    #[serde(skip)] // internal state used mainly for Template impl
+    unexported: bool,
+    /// Whether the Git tag of this ref has a good signature. `None` if this
+    /// isn't a tag.
+    #[serde(skip)]
+    signature_verified: Option<OnceCell<bool>>,
 }
 
 #[derive(Debug)]
@@ -1547,32 +1623,80 @@ struct TrackingRef {
     behind_count: OnceCell<SizeHint>,
 }
 
+#[derive(Debug, serde::Serialize)]
+struct GitTrackingRef {
+    /// Target of the Git-tracking ref.
+    #[serde(rename = "git_target")]
+    target: RefTarget,
+    /// Whether the local ref has commits not exported to Git.
+    #[serde(rename = "ahead_of_git")]
+    #[serde(skip_serializing_if = "is_uninit")]
+    #[serde(serialize_with = "serialize_once_cell")]
+    ahead: OnceCell<bool>,
+    /// Whether the Git-tracking ref has commits not in the local ref.
+    #[serde(rename = "behind_git")]
+    #[serde(skip_serializing_if = "is_uninit")]
+    #[serde(serialize_with = "serialize_once_cell")]
+    behind: OnceCell<bool>,
+}
+
 impl CommitRef {
     // CommitRef is wrapped by Rc<T> to make it cheaply cloned and share
     // lazy-evaluation results across clones.
 
     /// Creates local ref representation which might track some of the
     /// `remote_refs`.
+    ///
+    /// If `git_remote` is specified, the remote ref of that name is considered
+    /// the Git-tracking ref.
     pub fn local<'a>(
         name: impl Into<String>,
         target: RefTarget,
-        remote_refs: impl IntoIterator<Item = &'a RemoteRef>,
+        remote_refs: impl IntoIterator<Item = (&'a RemoteName, &'a RemoteRef)>,
+        git_remote: Option<&RemoteName>,
     ) -> Rc<Self> {
-        let synced = remote_refs
+        let (git_refs, remote_refs): (Vec<_>, Vec<_>) = remote_refs
             .into_iter()
-            .all(|remote_ref| !remote_ref.is_tracked() || remote_ref.target == target);
+            .filter(|(_, remote_ref)| remote_ref.is_tracked())
+            .partition(|&(remote_name, _)| Some(remote_name) == git_remote);
+        let remotes_synced = remote_refs
+            .iter()
+            .all(|(_, remote_ref)| remote_ref.target == target);
+        let git_synced = git_refs
+            .iter()
+            .all(|(_, remote_ref)| remote_ref.target == target);
+        let git_tracking_ref = git_remote.map(|_| {
+            let git_target = git_refs
+                .first()
+                .map_or_else(RefTarget::absent, |(_, remote_ref)| {
+                    remote_ref.target.clone()
+                });
+            let (ahead, behind) = if git_target == target {
+                (OnceCell::from(false), OnceCell::from(false)) // fast path for synced ref
+            } else {
+                (OnceCell::new(), OnceCell::new())
+            };
+            GitTrackingRef {
+                target: git_target,
+                ahead,
+                behind,
+            }
+        });
         Rc::new(Self {
             name: RefSymbolBuf(name.into()),
             remote: None,
             target,
             tracking_ref: None,
-            synced,
+            git_tracking_ref,
+            synced: remotes_synced && git_synced,
+            unexported: remotes_synced && !git_synced,
+            signature_verified: None,
         })
     }
 
     /// Creates local ref representation which doesn't track any remote refs.
     pub fn local_only(name: impl Into<String>, target: RefTarget) -> Rc<Self> {
-        Self::local(name, target, [])
+        Self::local(name, target, [], None)
     }
 
     /// Creates remote ref representation which might be tracked by a local ref
@@ -1601,7 +1725,10 @@ impl CommitRef {
             remote: Some(RefSymbolBuf(remote_name.into())),
             target: remote_ref.target,
             tracking_ref,
+            git_tracking_ref: None,
             synced,
+            unexported: false,
+            signature_verified: None,
         })
     }
 
@@ -1616,10 +1743,22 @@ impl CommitRef {
             remote: Some(RefSymbolBuf(remote_name.into())),
             target,
             tracking_ref: None,
+            git_tracking_ref: None,
             synced: false, // has no local counterpart
+            unexported: false,
+            signature_verified: None,
         })
     }
 
+    /// Marks the newly-created ref as a tag, whose Git tag signature can be
+    /// verified.
+    pub fn into_tag(mut self: Rc<Self>) -> Rc<Self> {
+        Rc::get_mut(&mut self)
+            .expect("newly-created ref shouldn't be shared")
+            .signature_verified = Some(OnceCell::new());
+        self
+    }
+
     /// Local name.
     pub fn name(&self) -> &str {
         self.name.as_ref()
@@ -1703,6 +1842,52 @@ impl CommitRef {
             })
             .copied()
     }
+
+    /// Whether the ref points to an annotated Git tag with a good signature.
+    fn signature_verified(&self, repo: &dyn Repo) -> Result<bool, TemplatePropertyError> {
+        let Some(verified) = &self.signature_verified else {
+            return Err(TemplatePropertyError("Not a tag".into()));
+        };
+        verified
+            .get_or_try_init(|| self.verify_git_tag_signature(repo))
+            .copied()
+    }
+
+    #[cfg(feature = "git")]
+    fn verify_git_tag_signature(&self, repo: &dyn Repo) -> Result<bool, TemplatePropertyError> {
+        let Some(commit_id) = self.target.as_normal() else {
+            return Ok(false);
+        };
+        if jj_lib::git::get_git_backend(repo.store()).is_err() {
+            return Ok(false);
+        }
+        let name = RefName::new(self.name());
+        let remote = self.remote_name().map(RemoteName::new);
+        let verification = jj_lib::git::verify_tag_signature(repo, name, remote, commit_id)?;
+        Ok(verification.is_some_and(|v| v.status == SigStatus::Good))
+    }
+
+    #[cfg(not(feature = "git"))]
+    fn verify_git_tag_signature(&self, _repo: &dyn Repo) -> Result<bool, TemplatePropertyError> {
+        Ok(false)
+    }
+
+    /// Computes whether this local ref is ahead of or behind the Git-tracking
+    /// ref. The results will be included in the serialized output.
+    pub fn populate_git_divergence(&self, repo: &dyn Repo) -> Result<(), RevsetEvaluationError> {
+        let Some(git_tracking) = &self.git_tracking_ref else {
+            return Ok(());
+        };
+        let self_ids = self.target.added_ids().cloned().collect_vec();
+        let git_ids = git_tracking.target.added_ids().cloned().collect_vec();
+        git_tracking.ahead.get_or_try_init(|| {
+            revset::walk_revs(repo, &self_ids, &git_ids).map(|revs| !revs.is_empty())
+        })?;
+        git_tracking.behind.get_or_try_init(|| {
+            revset::walk_revs(repo, &git_ids, &self_ids).map(|revs| !revs.is_empty())
+        })?;
+        Ok(())
+    }
 }
 
 // If wrapping with Rc<T> becomes common, add generic impl for Rc<T>.
@@ -1717,6 +1902,8 @@ impl Template for Rc<CommitRef> {
         // be pushed.
         if self.has_conflict() {
             write!(formatter, "??")?;
+        } else if self.is_local() && self.unexported {
+            write!(formatter, "^")?;
         } else if self.is_local() && !self.synced {
             write!(formatter, "*")?;
         }
@@ -1832,6 +2019,18 @@ where
     target.serialize(serializer)
 }
 
+fn is_uninit<T>(cell: &OnceCell<T>) -> bool {
+    cell.get().is_none()
+}
+
+fn serialize_once_cell<T, S>(cell: &OnceCell<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    cell.get().serialize(serializer)
+}
+
 fn builtin_commit_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Rc<CommitRef>> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -1950,6 +2149,16 @@ fn builtin_commit_ref_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo,
             Ok(out_property.into_dyn_wrapped())
         },
     );
+    map.insert(
+        "signature_verified",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property =
+                self_property.and_then(|commit_ref| commit_ref.signature_verified(repo));
+            Ok(out_property.into_dyn_wrapped())
+        },
+    );
     map
 }
 
@@ -1974,21 +2183,36 @@ impl CommitRefsIndex {
 
 fn build_local_remote_refs_index<'a>(
     local_remote_refs: impl IntoIterator<Item = (&'a RefName, LocalRemoteRefTarget<'a>)>,
+    git_remote: Option<&RemoteName>,
+    is_tag: bool,
 ) -> CommitRefsIndex {
+    let into_kind = |commit_ref: Rc<CommitRef>| {
+        if is_tag {
+            commit_ref.into_tag()
+        } else {
+            commit_ref
+        }
+    };
     let mut index = CommitRefsIndex::default();
     for (name, target) in local_remote_refs {
         let local_target = target.local_target;
         let remote_refs = target.remote_refs;
         if local_target.is_present() {
-            let commit_ref = CommitRef::local(
+            let commit_ref = into_kind(CommitRef::local(
                 name,
                 local_target.clone(),
-                remote_refs.iter().map(|&(_, remote_ref)| remote_ref),
-            );
+                remote_refs.iter().copied(),
+                git_remote,
+            ));
             index.insert(local_target.added_ids(), commit_ref);
         }
         for &(remote_name, remote_ref) in &remote_refs {
-            let commit_ref = CommitRef::remote(name, remote_name, remote_ref.clone(), local_target);
+            let commit_ref = into_kind(CommitRef::remote(
+                name,
+                remote_name,
+                remote_ref.clone(),
+                local_target,
+            ));
             index.insert(remote_ref.target.added_ids(), commit_ref);
         }
     }
@@ -100,7 +100,7 @@ use crate::ui::Ui;
 
 #[derive(clap::Args, Clone, Debug)]
 #[command(next_help_heading = "Diff Formatting Options")]
-#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only"])))]
+#[command(group(clap::ArgGroup::new("short-format").args(&["summary", "stat", "types", "name_only", "name_status"])))]
 #[command(group(clap::ArgGroup::new("long-format").args(&["git", "color_words"])))]
 pub struct DiffFormatArgs {
     /// For each path, show only whether it was modified, added, or deleted
@@ -128,6 +128,15 @@ pub struct DiffFormatArgs {
     #[arg(long)]
     pub name_only: bool,
 
+    /// For each path, show its path prefixed by a Git-style status letter
+    ///
+    /// The status is one of 'A' (added), 'D' (deleted), 'M' (modified), 'R'
+    /// (renamed), 'C' (copied), or 'U' (conflicted), separated from the path
+    /// by a tab. Renames and copies are shown with both source and target
+    /// paths.
+    #[arg(long)]
+    pub name_status: bool,
+
     /// Show a Git-format diff
     #[arg(long)]
     pub git: bool,
@@ -165,6 +174,7 @@ pub enum DiffFormat {
     Stat(Box<DiffStatOptions>),
     Types,
     NameOnly,
+    NameStatus,
     Git(Box<UnifiedDiffOptions>),
     ColorWords(Box<ColorWordsDiffOptions>),
     Tool(Box<ExternalMergeTool>),
@@ -176,6 +186,7 @@ enum BuiltinFormatKind {
     Stat,
     Types,
     NameOnly,
+    NameStatus,
     Git,
     ColorWords,
 }
@@ -189,6 +200,7 @@ impl BuiltinFormatKind {
         Self::Stat,
         Self::Types,
         Self::NameOnly,
+        Self::NameStatus,
         Self::Git,
         Self::ColorWords,
     ];
@@ -199,6 +211,7 @@ impl BuiltinFormatKind {
             "stat" => Ok(Self::Stat),
             "types" => Ok(Self::Types),
             "name-only" => Ok(Self::NameOnly),
+            "name-status" => Ok(Self::NameStatus),
             "git" => Ok(Self::Git),
             "color-words" => Ok(Self::ColorWords),
             _ => Err(format!("Invalid builtin diff format: {name}")),
@@ -214,6 +227,8 @@ impl BuiltinFormatKind {
             Some(Self::Types)
         } else if args.name_only {
             Some(Self::NameOnly)
+        } else if args.name_status {
+            Some(Self::NameStatus)
         } else {
             None
         }
@@ -231,7 +246,7 @@ impl BuiltinFormatKind {
 
     fn is_short(self) -> bool {
         match self {
-            Self::Summary | Self::Stat | Self::Types | Self::NameOnly => true,
+            Self::Summary | Self::Stat | Self::Types | Self::NameOnly | Self::NameStatus => true,
             Self::Git | Self::ColorWords => false,
         }
     }
@@ -242,6 +257,7 @@ impl BuiltinFormatKind {
             Self::Stat => "stat",
             Self::Types => "types",
             Self::NameOnly => "name-only",
+            Self::NameStatus => "name-status",
             Self::Git => "git",
             Self::ColorWords => "color-words",
         }
@@ -261,6 +277,7 @@ impl BuiltinFormatKind {
             }
             Self::Types => Ok(DiffFormat::Types),
             Self::NameOnly => Ok(DiffFormat::NameOnly),
+            Self::NameStatus => Ok(DiffFormat::NameStatus),
             Self::Git => {
                 let mut options = UnifiedDiffOptions::from_settings(settings)?;
                 options.merge_args(args);
@@ -499,6 +516,11 @@ impl<'a> DiffRenderer<'a> {
                     let tree_diff = diff_stream();
                     show_names(*formatter.labeled("name_only"), tree_diff, path_converter).await?;
                 }
+                DiffFormat::NameStatus => {
+                    let tree_diff = diff_stream();
+                    show_name_status(*formatter.labeled("name_status"), tree_diff, path_converter)
+                        .await?;
+                }
                 DiffFormat::Git(options) => {
                     let tree_diff = diff_stream();
                     show_git_diff(
@@ -583,7 +605,8 @@ impl<'a> DiffRenderer<'a> {
                 DiffFormat::Summary
                 | DiffFormat::Stat(_)
                 | DiffFormat::Types
-                | DiffFormat::NameOnly => {}
+                | DiffFormat::NameOnly
+                | DiffFormat::NameStatus => {}
                 DiffFormat::Git(options) => {
                     // Git format must be parsable, so use dummy file path.
                     show_git_diff_texts(
@@ -2232,6 +2255,34 @@ pub async fn show_names(
     Ok(())
 }
 
+pub async fn show_name_status(
+    formatter: &mut dyn Formatter,
+    mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+) -> Result<(), DiffRenderError> {
+    while let Some(CopiesTreeDiffEntry { path, values }) = tree_diff.next().await {
+        let values = values?;
+        let status = diff_status(&path, &values);
+        let (label, sigil) = if values.after.is_resolved() {
+            (status.label(), status.char())
+        } else {
+            ("conflict", 'U')
+        };
+        if let Some(paths) = path.to_diff() {
+            let source_path = path_converter.format_file_path(paths.before);
+            let target_path = path_converter.format_file_path(paths.after);
+            writeln!(
+                formatter.labeled(label),
+                "{sigil}\t{source_path}\t{target_path}"
+            )?;
+        } else {
+            let target_path = path_converter.format_file_path(path.target());
+            writeln!(formatter.labeled(label), "{sigil}\t{target_path}")?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn show_templated(
     formatter: &mut dyn Formatter,
     mut tree_diff: BoxStream<'_, CopiesTreeDiffEntry>,
@@ -14,12 +14,16 @@
 
 //! Git utilities shared by various commands.
 
+use std::collections::BTreeSet;
 use std::error;
 use std::io;
 use std::io::Write as _;
 use std::iter;
 use std::mem;
 use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -40,18 +44,32 @@ use jj_lib::git::GitRefKind;
 use jj_lib::git::GitSettings;
 use jj_lib::git::GitSidebandLineTerminator;
 use jj_lib::git::GitSubprocessCallback;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::ref_name::RefName;
+use jj_lib::ref_name::RefNameBuf;
+use jj_lib::ref_name::RemoteNameBuf;
+use jj_lib::ref_name::RemoteRefSymbol;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::settings::RemoteSettingsMap;
+use jj_lib::str_util::StringMatcher;
+use jj_lib::view::View;
 use jj_lib::workspace::Workspace;
 use unicode_width::UnicodeWidthStr as _;
 
 use crate::cleanup_guard::CleanupGuard;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::cli_util::print_updated_commits;
+use crate::cli_util::short_commit_hash;
 use crate::command_error::CommandError;
 use crate::command_error::cli_error;
+use crate::command_error::internal_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::formatter::Formatter;
 use crate::formatter::FormatterExt as _;
 use crate::revset_util::parse_remote_auto_track_bookmarks_map;
@@ -76,6 +94,102 @@ pub fn is_colocated_git_workspace(workspace: &Workspace, repo: &ReadonlyRepo) ->
     dunce::canonicalize(git_workdir).ok().as_deref() == dot_git_path.parent()
 }
 
+/// Runs the Git `pre-commit` hook against `tree` if `git.run-hooks` is enabled
+/// and the workspace is colocated.
+///
+/// The hook sees a temporary Git index matching `tree`, as if the tree were
+/// staged by `git commit`. Returns an error if the hook fails.
+pub fn run_git_pre_commit_hook(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    tree: &MergedTree,
+) -> Result<(), CommandError> {
+    let workspace_command = tx.base_workspace_helper();
+    if !workspace_command.working_copy_shared_with_git()
+        || !tx.settings().get_bool("git.run-hooks")?
+    {
+        return Ok(());
+    }
+    let git_repo = git::get_git_backend(tx.repo().store())?.git_repo();
+    let Some(hook_path) = find_git_hook(&git_repo, "pre-commit")? else {
+        return Ok(());
+    };
+    let Some(tree_id) = tree.tree_ids().as_resolved() else {
+        writeln!(
+            ui.warning_default(),
+            "Not running the Git pre-commit hook because the commit has conflicts"
+        )?;
+        return Ok(());
+    };
+    let mut index = if tree_id == tx.repo().store().empty_tree_id() {
+        // Git doesn't require the empty tree to be present in the object database.
+        gix::index::File::from_state(
+            gix::index::State::new(git_repo.object_hash()),
+            git_repo.index_path(),
+        )
+    } else {
+        git_repo
+            .index_from_tree(&gix::ObjectId::from_bytes_or_panic(tree_id.as_bytes()))
+            .map_err(internal_error)?
+    };
+    let index_file = tempfile::Builder::new()
+        .prefix("jj-hook-index-")
+        .tempfile_in(git_repo.path())?;
+    index.set_path(index_file.path());
+    index
+        .write(gix::index::write::Options::default())
+        .map_err(internal_error)?;
+
+    let mut child = Command::new(&hook_path)
+        .current_dir(workspace_command.workspace_root())
+        .env("GIT_INDEX_FILE", index_file.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(ui.stderr_for_child()?)
+        .spawn()
+        .map_err(|err| {
+            user_error_with_message(
+                format!("Failed to run Git hook {}", hook_path.display()),
+                err,
+            )
+        })?;
+    // Like Git, redirect the hook's stdout to stderr.
+    io::copy(&mut child.stdout.take().unwrap(), &mut ui.stderr())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(
+            user_error(format!("The Git pre-commit hook failed with {status}"))
+                .hinted("Use `--no-verify` to skip the hook."),
+        );
+    }
+    Ok(())
+}
+
+/// Returns the path to the executable Git hook of the given `name`, looking
+/// in `core.hooksPath` if set.
+fn find_git_hook(git_repo: &gix::Repository, name: &str) -> Result<Option<PathBuf>, CommandError> {
+    let hooks_dir = match git_repo.config_snapshot().trusted_path("core.hooksPath") {
+        // Relative paths are resolved from the working tree, as Git does.
+        Some(path) => git_repo
+            .workdir()
+            .unwrap_or_else(|| git_repo.path())
+            .join(path.map_err(user_error)?),
+        None => git_repo.common_dir().join("hooks"),
+    };
+    let path = hooks_dir.join(name);
+    let Ok(metadata) = path.metadata() else {
+        return Ok(None);
+    };
+    #[cfg(unix)]
+    let is_executable = {
+        use std::os::unix::fs::PermissionsExt as _;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let is_executable = metadata.is_file();
+    Ok(is_executable.then_some(path))
+}
+
 /// Parses user-specified remote URL or path to absolute form.
 pub fn absolute_git_url(cwd: &Path, source: &str) -> Result<String, CommandError> {
     // Git appears to turn URL-like source to absolute path if local git directory
@@ -122,6 +236,52 @@ pub fn get_remote_web_url(repo: &ReadonlyRepo, remote_name: &str) -> Option<Stri
     git_remote_url_to_web(url)
 }
 
+/// Returns the URL Git would push to for the given remote.
+///
+/// Unlike [`gix::Remote::url()`], this also applies `url.<base>.pushInsteadOf`
+/// rules to the fetch URL if no push URL is configured, as Git does.
+pub fn get_remote_push_url(remote: &gix::Remote<'_>) -> Option<gix::Url> {
+    let default_url = || remote.url(gix::remote::Direction::Push).cloned();
+    let Some(name) = remote.name() else {
+        return default_url();
+    };
+    let config = remote.repo().config_snapshot();
+    let name = name.as_bstr();
+    if config.string(&format!("remote.{name}.pushurl")).is_some() {
+        return default_url();
+    }
+    let Some(raw_url) = config.string(&format!("remote.{name}.url")) else {
+        return default_url();
+    };
+    let raw_url = raw_url.as_ref();
+    remote
+        .repo()
+        .remote_at_without_url_rewrite(raw_url)
+        .and_then(|anonymous| anonymous.with_push_url(raw_url))
+        .ok()
+        .and_then(|anonymous| {
+            anonymous
+                .url(gix::remote::Direction::Push)
+                .filter(|url| url.to_bstring() != raw_url)
+                .cloned()
+        })
+        .or_else(default_url)
+}
+
+/// How to render the progress of Git operations.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum GitProgressMode {
+    /// Show a progress bar if stderr is a terminal
+    #[default]
+    Auto,
+    /// Always show a progress bar
+    Bar,
+    /// Print periodic progress updates as separate lines
+    Lines,
+    /// Don't show progress
+    None,
+}
+
 /// [`Ui`] adapter to forward Git command outputs.
 pub struct GitSubprocessUi<'a> {
     // Don't hold locked ui.status() which could block tracing output in
@@ -129,18 +289,30 @@ pub struct GitSubprocessUi<'a> {
     ui: &'a Ui,
     progress_output: Option<ProgressOutput<io::Stderr>>,
     progress: Progress,
+    progress_lines: Option<ProgressLines>,
     // Sequence to erase line towards end.
     erase_end: &'static [u8],
 }
 
 impl<'a> GitSubprocessUi<'a> {
     pub fn new(ui: &'a Ui) -> Self {
-        let progress_output = ui.progress_output();
-        let is_terminal = progress_output.is_some();
+        Self::with_progress_mode(ui, GitProgressMode::Auto)
+    }
+
+    pub fn with_progress_mode(ui: &'a Ui, mode: GitProgressMode) -> Self {
+        let is_terminal = ui.use_progress_indicator();
+        let now = Instant::now();
+        let (progress_output, progress_lines) = match mode {
+            GitProgressMode::Auto => (ui.progress_output(), None),
+            GitProgressMode::Bar if !ui.quiet() => (Some(ProgressOutput::for_stderr()), None),
+            GitProgressMode::Lines if !ui.quiet() => (None, Some(ProgressLines::new(now))),
+            GitProgressMode::Bar | GitProgressMode::Lines | GitProgressMode::None => (None, None),
+        };
         Self {
             ui,
             progress_output,
-            progress: Progress::new(Instant::now()),
+            progress: Progress::new(now),
+            progress_lines,
             erase_end: if is_terminal { b"\x1B[K" } else { b"        " },
         }
     }
@@ -173,12 +345,14 @@ impl<'a> GitSubprocessUi<'a> {
 
 impl GitSubprocessCallback for GitSubprocessUi<'_> {
     fn needs_progress(&self) -> bool {
-        self.progress_output.is_some()
+        self.progress_output.is_some() || self.progress_lines.is_some()
     }
 
     fn progress(&mut self, progress: &GitProgress) -> io::Result<()> {
         if let Some(output) = &mut self.progress_output {
             self.progress.update(Instant::now(), progress, output)
+        } else if let Some(lines) = &mut self.progress_lines {
+            lines.update(Instant::now(), progress, &mut *self.ui.status())
         } else {
             Ok(())
         }
@@ -208,18 +382,118 @@ pub fn load_git_import_options(
 ) -> Result<GitImportOptions, CommandError> {
     Ok(GitImportOptions {
         abandon_unreachable_commits: git_settings.abandon_unreachable_commits,
+        import_deleted_bookmark: git_settings.import_deleted_bookmark,
         record_synthetic_predecessors: git_settings.record_synthetic_predecessors,
         remote_auto_track_bookmarks: parse_remote_auto_track_bookmarks_map(ui, remote_settings)?,
+        skip_clashing_bookmarks: false,
+        keep_existing_tags: false,
     })
 }
 
+/// Renames the local bookmark, and untracks the remote bookmarks of the old
+/// name so they won't be deleted by the next push.
+pub fn rename_local_bookmark(mut_repo: &mut MutableRepo, old_name: &RefName, new_name: &RefName) {
+    let tracked_remotes: Vec<RemoteNameBuf> = mut_repo
+        .view()
+        .remote_bookmarks_matching(&StringMatcher::exact(old_name), &StringMatcher::all())
+        .filter(|(symbol, remote_ref)| {
+            symbol.remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO && remote_ref.is_tracked()
+        })
+        .map(|(symbol, _)| symbol.remote.to_owned())
+        .collect();
+    for remote in &tracked_remotes {
+        mut_repo.untrack_remote_bookmark(old_name.to_remote_symbol(remote));
+    }
+    let target = mut_repo.get_local_bookmark(old_name);
+    mut_repo.set_local_bookmark_target(new_name, target);
+    mut_repo.set_local_bookmark_target(old_name, RefTarget::absent());
+}
+
+/// Finds a new name for the local bookmark `name` which clashes with
+/// `other_name` in Git. The colliding path component of `name` gets a `-{n}`
+/// suffix, so `foo` clashing with `foo/bar` becomes `foo-1`, and `foo/bar`
+/// clashing with `foo` becomes `foo-1/bar`.
+pub fn non_clashing_bookmark_name(
+    view: &View,
+    taken: &BTreeSet<RefNameBuf>,
+    name: &RefName,
+    other_name: &RefName,
+) -> RefNameBuf {
+    (1..)
+        .map(|n| suffix_colliding_component(name, other_name, n))
+        .find(|new_name| is_available_bookmark_name(view, taken, new_name))
+        .unwrap()
+}
+
+fn suffix_colliding_component(name: &RefName, other_name: &RefName, n: usize) -> RefNameBuf {
+    let depth = name
+        .as_str()
+        .split('/')
+        .count()
+        .min(other_name.as_str().split('/').count());
+    let components: Vec<_> = name
+        .as_str()
+        .split('/')
+        .enumerate()
+        .map(|(i, component)| {
+            if i + 1 == depth {
+                format!("{component}-{n}")
+            } else {
+                component.to_owned()
+            }
+        })
+        .collect();
+    components.join("/").into()
+}
+
+fn is_available_bookmark_name(view: &View, taken: &BTreeSet<RefNameBuf>, name: &RefName) -> bool {
+    !taken
+        .iter()
+        .any(|other| &**other == name || git::bookmark_names_clash(other, name))
+        && view.git_refs().keys().all(|git_ref| {
+            let Some(other) = git_ref.as_str().strip_prefix("refs/heads/") else {
+                return true;
+            };
+            let other = RefName::new(other);
+            other != name && !git::bookmark_names_clash(other, name)
+        })
+}
+
 pub fn print_git_import_stats(
     ui: &Ui,
     tx: &WorkspaceCommandTransaction<'_>,
     stats: &GitImportStats,
 ) -> Result<(), CommandError> {
     if let Some(mut formatter) = ui.status_formatter() {
-        print_imported_changes(formatter.as_mut(), tx, stats)?;
+        print_imported_changes(formatter.as_mut(), tx, stats, false, false)?;
+    }
+    print_failed_git_import(ui, stats)?;
+    Ok(())
+}
+
+/// Like [`print_git_import_stats()`], but describes the changes as ones that
+/// would be made. Use this when the transaction will not be committed.
+pub fn print_git_import_preview(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction<'_>,
+    stats: &GitImportStats,
+) -> Result<(), CommandError> {
+    if let Some(mut formatter) = ui.status_formatter() {
+        print_imported_changes(formatter.as_mut(), tx, stats, false, true)?;
+    }
+    print_failed_git_import(ui, stats)?;
+    Ok(())
+}
+
+/// Like [`print_git_import_stats()`], but also prints the old and new targets
+/// of the updated refs.
+pub fn print_git_import_stats_with_targets(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction<'_>,
+    stats: &GitImportStats,
+) -> Result<(), CommandError> {
+    if let Some(mut formatter) = ui.status_formatter() {
+        print_imported_changes(formatter.as_mut(), tx, stats, true, false)?;
     }
     print_failed_git_import(ui, stats)?;
     Ok(())
@@ -229,6 +503,8 @@ fn print_imported_changes(
     formatter: &mut dyn Formatter,
     tx: &WorkspaceCommandTransaction<'_>,
     stats: &GitImportStats,
+    show_targets: bool,
+    dry_run: bool,
 ) -> Result<(), CommandError> {
     for (kind, changes) in [
         (GitRefKind::Bookmark, &stats.changed_remote_bookmarks),
@@ -236,7 +512,7 @@ fn print_imported_changes(
     ] {
         let refs_stats = changes
             .iter()
-            .map(|update| RefStatus::new(kind, update, tx.repo()))
+            .map(|update| RefStatus::new(kind, update, tx.repo(), show_targets))
             .collect_vec();
         let Some(max_width) = refs_stats.iter().map(|x| x.symbol.width()).max() else {
             continue;
@@ -249,8 +525,13 @@ fn print_imported_changes(
     if !stats.abandoned_commits.is_empty() {
         writeln!(
             formatter,
-            "Abandoned {} commits that are no longer reachable:",
-            stats.abandoned_commits.len()
+            "{verb} {count} commits that are no longer reachable:",
+            verb = if dry_run {
+                "Would abandon"
+            } else {
+                "Abandoned"
+            },
+            count = stats.abandoned_commits.len()
         )?;
         let template = tx.commit_summary_template();
         print_updated_commits(formatter, &template, &stats.abandoned_commits)?;
@@ -258,8 +539,9 @@ fn print_imported_changes(
     if !stats.rewritten_commit_ids.is_empty() {
         writeln!(
             formatter,
-            "Updated {} rewritten commits.",
-            stats.rewritten_commit_ids.len()
+            "{verb} {count} rewritten commits.",
+            verb = if dry_run { "Would update" } else { "Updated" },
+            count = stats.rewritten_commit_ids.len()
         )?;
     }
 
@@ -290,6 +572,27 @@ fn print_failed_git_import(ui: &Ui, stats: &GitImportStats) -> Result<(), Comman
             name = git::REMOTE_NAME_FOR_LOCAL_GIT_REPO.as_symbol(),
         )?;
     }
+    if !stats.clashing_ref_names.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "Failed to import some Git refs clashing with other bookmarks:"
+        )?;
+        let mut formatter = ui.stderr_formatter();
+        for name in &stats.clashing_ref_names {
+            write!(formatter, "  ")?;
+            write!(formatter.labeled("git_ref"), "{}", name.as_str())?;
+            writeln!(formatter)?;
+        }
+        drop(formatter);
+        writedoc!(
+            ui.hint_default(),
+            "
+            Git doesn't allow a branch name that looks like a parent directory of
+            another (e.g. `foo` and `foo/bar`). Try to rename the Git refs that failed
+            to import or the clashing bookmarks.
+            ",
+        )?;
+    }
     Ok(())
 }
 
@@ -383,6 +686,49 @@ impl Progress {
     }
 }
 
+/// Renders progress as separate lines, suitable for logs.
+pub struct ProgressLines {
+    next_print: Instant,
+    printed: bool,
+    done: bool,
+}
+
+impl ProgressLines {
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new(now: Instant) -> Self {
+        Self {
+            next_print: now + crate::progress::INITIAL_DELAY,
+            printed: false,
+            done: false,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        now: Instant,
+        progress: &GitProgress,
+        output: &mut dyn std::io::Write,
+    ) -> io::Result<()> {
+        if self.done {
+            return Ok(());
+        }
+        let overall = progress.overall();
+        if overall == 1.0 {
+            self.done = true;
+            // Don't report completion of quick operations.
+            if !self.printed {
+                return Ok(());
+            }
+        } else if now < self.next_print {
+            return Ok(());
+        }
+        self.next_print = now + Self::INTERVAL;
+        self.printed = true;
+        writeln!(output, "Progress: {: >3.0}%", 100.0 * overall)
+    }
+}
+
 fn draw_progress(progress: f32, buffer: &mut String, width: usize) {
     const CHARS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
     const RESOLUTION: usize = CHARS.len() - 1;
@@ -405,10 +751,17 @@ struct RefStatus {
     symbol: String,
     tracking_status: TrackingStatus,
     import_status: ImportStatus,
+    /// Old and new targets of the updated ref, if requested.
+    target_change: Option<(String, String)>,
 }
 
 impl RefStatus {
-    fn new(ref_kind: GitRefKind, update: &GitImportRefUpdate, repo: &dyn Repo) -> Self {
+    fn new(
+        ref_kind: GitRefKind,
+        update: &GitImportRefUpdate,
+        repo: &dyn Repo,
+        show_targets: bool,
+    ) -> Self {
         let tracking_status = match ref_kind {
             GitRefKind::Bookmark => {
                 if repo
@@ -433,10 +786,19 @@ impl RefStatus {
             _ => ImportStatus::Updated,
         };
 
+        let target_change =
+            (show_targets && matches!(import_status, ImportStatus::Updated)).then(|| {
+                (
+                    format_ref_target(&update.old_remote_ref.target),
+                    format_ref_target(&update.new_target),
+                )
+            });
+
         Self {
             symbol: update.symbol.to_string(),
             tracking_status,
             import_status,
+            target_change,
             ref_kind,
         }
     }
@@ -465,7 +827,21 @@ impl RefStatus {
 
         write!(out, "{label}: ")?;
         write!(out.labeled(label), "{padded_symbol}")?;
-        writeln!(out, " [{import_status}] {tracking_status}")
+        write!(out, " [{import_status}] ")?;
+        if let Some((old, new)) = &self.target_change {
+            write!(out.labeled("commit_id"), "{old}")?;
+            write!(out, " -> ")?;
+            write!(out.labeled("commit_id"), "{new}")?;
+            write!(out, " ")?;
+        }
+        writeln!(out, "{tracking_status}")
+    }
+}
+
+fn format_ref_target(target: &RefTarget) -> String {
+    match target.as_normal() {
+        Some(id) => short_commit_hash(id),
+        None => "(conflicted)".to_owned(),
     }
 }
 
@@ -481,6 +857,53 @@ enum ImportStatus {
     Updated,
 }
 
+/// Adds `Change-Id` trailers to the commits to be exported if
+/// `git.write-change-id-trailer` is enabled.
+///
+/// The trailer is rendered by the `format_gerrit_change_id_trailer()` template
+/// function. Immutable commits are left alone.
+pub async fn add_change_id_trailers_for_export(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    mut_repo: &mut MutableRepo,
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+) -> Result<(), CommandError> {
+    if !GitSettings::from_settings(mut_repo.base_repo().settings())?.write_change_id_trailer {
+        return Ok(());
+    }
+    let template =
+        workspace_command.parse_commit_template(ui, "format_gerrit_change_id_trailer(self)")?;
+    let immutable = workspace_command
+        .env()
+        .resolve_immutable_expression(mut_repo)?;
+    let stats = git::add_change_id_trailers(mut_repo, git_ref_filter, &immutable, |commit| {
+        String::from_utf8_lossy(&template.format_plain_text(commit)).into_owned()
+    })
+    .await?;
+    if !stats.skipped_immutable.is_empty() {
+        writeln!(
+            ui.warning_default(),
+            "Didn't add Change-Id trailers to {} immutable commits",
+            stats.skipped_immutable.len()
+        )?;
+    }
+    if stats.num_rewritten > 0 {
+        writeln!(
+            ui.status(),
+            "Added Change-Id trailers to {} commits",
+            stats.num_rewritten
+        )?;
+    }
+    if stats.num_rebased > 0 {
+        writeln!(
+            ui.status(),
+            "Rebased {} descendant commits",
+            stats.num_rebased
+        )?;
+    }
+    Ok(())
+}
+
 pub fn print_git_export_stats(ui: &Ui, stats: &GitExportStats) -> Result<(), std::io::Error> {
     if !stats.failed_bookmarks.is_empty() {
         writeln!(ui.warning_default(), "Failed to export some bookmarks:")?;
@@ -732,4 +1155,37 @@ mod tests {
         // previous output, so we don't get an update here
         assert_snapshot!(update(Duration::from_millis(30), 40), @"");
     }
+
+    #[test]
+    fn test_update_lines() {
+        let start = Instant::now();
+        let mut progress = ProgressLines::new(start);
+        let mut current_time = start;
+        let mut update = |duration, overall: u64| -> String {
+            current_time += duration;
+            let mut buf = vec![];
+            progress
+                .update(
+                    current_time,
+                    &GitProgress {
+                        deltas: (overall, 100),
+                        objects: (0, 0),
+                        counted_objects: (0, 0),
+                        compressed_objects: (0, 0),
+                    },
+                    &mut buf,
+                )
+                .unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+        // First output is after the initial delay
+        assert_snapshot!(update(crate::progress::INITIAL_DELAY - Duration::from_millis(1), 1), @"");
+        assert_snapshot!(update(Duration::from_millis(1), 10), @"Progress:  10%");
+        // Next update is a second later
+        assert_snapshot!(update(Duration::from_millis(500), 20), @"");
+        assert_snapshot!(update(Duration::from_millis(500), 30), @"Progress:  30%");
+        // Completion is reported immediately, but only once
+        assert_snapshot!(update(Duration::from_millis(10), 100), @"Progress: 100%");
+        assert_snapshot!(update(Duration::from_secs(2), 100), @"");
+    }
 }
@@ -42,6 +42,14 @@ pub mod git_util {
     pub fn get_remote_web_url(_repo: &ReadonlyRepo, _remote_name: &str) -> Option<String> {
         None
     }
+
+    pub fn run_git_pre_commit_hook(
+        _ui: &crate::ui::Ui,
+        _tx: &crate::cli_util::WorkspaceCommandTransaction,
+        _tree: &jj_lib::merged_tree::MergedTree,
+    ) -> Result<(), crate::command_error::CommandError> {
+        Ok(())
+    }
 }
 pub mod graphlog;
 pub mod merge_tools;
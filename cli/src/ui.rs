@@ -418,6 +418,11 @@ impl Ui {
         self.formatter_factory.maybe_color()
     }
 
+    /// Whether `--quiet` was requested.
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
     pub fn new_formatter<'output, W: Write + 'output>(
         &self,
         output: W,
@@ -52,6 +52,8 @@ mod test_git_private_commits;
 mod test_git_push;
 mod test_git_remotes;
 mod test_git_root;
+mod test_git_submodule;
+mod test_git_verify;
 mod test_gitignores;
 mod test_global_opts;
 mod test_help_command;
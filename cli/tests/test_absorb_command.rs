@@ -804,6 +804,82 @@ fn test_absorb_from_into() {
     ");
 }
 
+#[test]
+fn test_absorb_into_bookmark_ancestors() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["new", "-m1"]).success();
+    work_dir.write_file("file1", "1a\n");
+    work_dir.run_jj(["new", "-m2"]).success();
+    work_dir.write_file("file2", "2a\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["new", "-m3"]).success();
+    work_dir.write_file("file3", "3a\n");
+
+    // Only the ancestors of the bookmark receive hunks. The change to the line
+    // last modified outside the range stays in the source revision.
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "1A\n");
+    work_dir.write_file("file2", "2A\n");
+    work_dir.write_file("file3", "3A\n");
+    let output = work_dir.run_jj(["absorb", "--into=::main & mine()"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Absorbed changes into 2 revisions:
+      kkmpptxz 11e5ef1c main | 2
+      rlvkpnrz 7f15b6e6 1
+    Rebased 2 descendant commits.
+    Working copy  (@) now at: royxmykx 60db0c68 (no description set)
+    Parent commit (@-)      : mzvwutvl e2a0ff67 3
+    Remaining changes:
+    M file3
+    [EOF]
+    ");
+
+    insta::assert_snapshot!(get_diffs(&work_dir, "mutable()"), @"
+    @  royxmykx 60db0c68 (no description set)
+    │  diff --git a/file3 b/file3
+    │  index 31cd755d20..44442d2d7b 100644
+    │  --- a/file3
+    │  +++ b/file3
+    │  @@ -1,1 +1,1 @@
+    │  -3a
+    │  +3A
+    ○  mzvwutvl e2a0ff67 3
+    │  diff --git a/file3 b/file3
+    │  new file mode 100644
+    │  index 0000000000..31cd755d20
+    │  --- /dev/null
+    │  +++ b/file3
+    │  @@ -0,0 +1,1 @@
+    │  +3a
+    ○  kkmpptxz 11e5ef1c 2
+    │  diff --git a/file2 b/file2
+    │  new file mode 100644
+    │  index 0000000000..8676c24ef8
+    │  --- /dev/null
+    │  +++ b/file2
+    │  @@ -0,0 +1,1 @@
+    │  +2A
+    ○  rlvkpnrz 7f15b6e6 1
+    │  diff --git a/file1 b/file1
+    │  new file mode 100644
+    │  index 0000000000..268de3f3ec
+    │  --- /dev/null
+    │  +++ b/file1
+    │  @@ -0,0 +1,1 @@
+    │  +1A
+    ○  qpvuntsm e8849ae1 (empty) (no description set)
+    │
+    ~
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_absorb_paths() {
     let test_env = TestEnvironment::default();
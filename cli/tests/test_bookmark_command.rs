@@ -2490,13 +2490,13 @@ fn test_bookmark_list() -> TestResult {
 
     let output = local_dir.run_jj(["bookmark", "list", r#"-Tjson(self) ++ "\n""#]);
     insta::assert_snapshot!(output, @r#"
-    {"name":"absent-tracked","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"]}
+    {"name":"absent-tracked","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"],"git_target":[null],"ahead_of_git":true,"behind_git":false}
     {"name":"absent-tracked","remote":"origin","target":[null],"tracking_target":["0353dd35c56156971ce5f023a1db7a6196160a8a"]}
-    {"name":"local-only","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"]}
-    {"name":"remote-delete","target":[null]}
+    {"name":"local-only","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"],"git_target":[null],"ahead_of_git":true,"behind_git":false}
+    {"name":"remote-delete","target":[null],"git_target":[null],"ahead_of_git":false,"behind_git":false}
     {"name":"remote-delete","remote":"origin","target":["b32031cf329fbb90d042635c295b4e3fa2ca2651"],"tracking_target":[null]}
-    {"name":"remote-sync","target":["7a07dbeef135886b7ba7adb27d05190c39cd92ab"]}
-    {"name":"remote-unsync","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"]}
+    {"name":"remote-sync","target":["7a07dbeef135886b7ba7adb27d05190c39cd92ab"],"git_target":[null],"ahead_of_git":true,"behind_git":false}
+    {"name":"remote-unsync","target":["0353dd35c56156971ce5f023a1db7a6196160a8a"],"git_target":[null],"ahead_of_git":true,"behind_git":false}
     {"name":"remote-unsync","remote":"origin","target":["553203baa52803406124962dbc0bcdc0227b20b2"],"tracking_target":["0353dd35c56156971ce5f023a1db7a6196160a8a"]}
     [EOF]
     ------- stderr -------
@@ -2506,6 +2506,46 @@ fn test_bookmark_list() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_bookmark_list_json_git_divergence() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m=one"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "exported", "moved"])
+        .success();
+    work_dir.run_jj(["new", "-m=two"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "rewound"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+
+    // Move bookmarks without exporting them
+    work_dir
+        .run_jj(["bookmark", "set", "-r@", "moved"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r@-", "--allow-backwards", "rewound"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "unexported"])
+        .success();
+
+    let output = work_dir.run_jj(["bookmark", "list", r#"-Tjson(self) ++ "\n""#]);
+    insta::assert_snapshot!(output, @r#"
+    {"name":"exported","target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"],"git_target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"],"ahead_of_git":false,"behind_git":false}
+    {"name":"moved","target":["d472c8ab5688bda299b5231d005bb35a1e6c939b"],"git_target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"],"ahead_of_git":true,"behind_git":false}
+    {"name":"moved","remote":"git","target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"],"tracking_target":["d472c8ab5688bda299b5231d005bb35a1e6c939b"]}
+    {"name":"rewound","target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"],"git_target":["d472c8ab5688bda299b5231d005bb35a1e6c939b"],"ahead_of_git":false,"behind_git":true}
+    {"name":"rewound","remote":"git","target":["d472c8ab5688bda299b5231d005bb35a1e6c939b"],"tracking_target":["401ea16fc3fe9927b7c6e535480a94015dfe6206"]}
+    {"name":"unexported","target":["d472c8ab5688bda299b5231d005bb35a1e6c939b"],"git_target":[null],"ahead_of_git":true,"behind_git":false}
+    [EOF]
+    "#);
+    Ok(())
+}
+
 #[test]
 fn test_bookmark_list_filtered() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -3089,6 +3129,90 @@ fn test_bookmark_list_conflicted() {
     ");
 }
 
+#[test]
+fn test_bookmark_list_preview() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo_path = test_env.env_root().join("git-repo");
+    let git_repo = git::init(git_repo_path);
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../git-repo"])
+        .success();
+    create_commit_with_refs(
+        &git_repo,
+        "base",
+        b"content",
+        &["refs/heads/feature", "refs/heads/synced"],
+    );
+    work_dir.run_jj(["git", "fetch"]).success();
+    work_dir
+        .run_jj(["bookmark", "track", "feature", "synced", "--remote=origin"])
+        .success();
+
+    // Move the local bookmark two commits ahead of the remote
+    work_dir.run_jj(["new", "feature", "-m", "first"]).success();
+    work_dir.run_jj(["new", "-m", "second\n\nbody"]).success();
+    work_dir
+        .run_jj(["bookmark", "move", "feature", "--to=@"])
+        .success();
+
+    let output = work_dir.run_jj(["bookmark", "list", "--preview"]);
+    insta::assert_snapshot!(output, @"
+    feature: royxmykx df6d261f (empty) second
+      @origin (behind by 2 commits): ktnymyyy 1744e9b6 base
+      2 commits ahead of @origin: second
+    synced: ktnymyyy 1744e9b6 base
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_bookmark_list_git() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "exported1", "exported2"])
+        .success();
+    // A bookmark pointing to the root commit can't be exported
+    work_dir
+        .run_jj(["bookmark", "create", "-rroot()", "jj-only1"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    // Bookmarks created after the export aren't exported
+    work_dir.run_jj(["new", "-m", "b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "jj-only2"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "move", "exported2", "--to=@"])
+        .success();
+
+    let output = work_dir.run_jj(["bookmark", "list", "--git"]);
+    insta::assert_snapshot!(output, @"
+    exported1: qpvuntsm b86e28cd (empty) a
+      @git: qpvuntsm b86e28cd (empty) a
+    exported2: royxmykx 198b129b (empty) b
+      @git (behind by 1 commits): qpvuntsm b86e28cd (empty) a
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["bookmark", "list", "--git", "--tracked"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the argument '--git' cannot be used with '--tracked'
+
+    Usage: jj bookmark list --git [NAMES]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_bookmark_list_sort_unknown_key_error() {
     let test_env = TestEnvironment::default();
@@ -3509,6 +3633,20 @@ fn test_bookmark_advance_default() -> TestResult {
     ");
     work_dir.run_jj(["op", "restore", &setup_opid]).success();
 
+    // To the parent of the empty working-copy commit, as configured.
+    work_dir.run_jj(["new"]).success();
+    let output = work_dir.run_jj([
+        "--config=revsets.bookmark-advance-to='coalesce(@ ~ empty(), @-)'",
+        "bookmark",
+        "advance",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Advanced 1 bookmarks to vruxwmqv 7753a73e B | e
+    [EOF]
+    ");
+    work_dir.run_jj(["op", "restore", &setup_opid]).success();
+
     // Multiple bookmarks by name.
     let output = work_dir.run_jj(["bookmark", "advance", "A|B"]);
     insta::assert_snapshot!(output, @"
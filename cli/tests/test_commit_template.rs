@@ -742,6 +742,148 @@ fn test_log_bookmarks() {
     ");
 }
 
+#[test]
+fn test_log_bookmarks_unexported() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--no-colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "foo", "bar"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    work_dir.run_jj(["bookmark", "set", "-r@", "foo"]).success();
+
+    // foo was moved, but the change hasn't been exported to Git
+    let template = r#"commit_id.short() ++ " " ++ bookmarks ++ "\n""#;
+    let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  bbc749308d7f foo^
+    ○  b876c5f49546 bar foo@git
+    ◆  000000000000
+    [EOF]
+    ");
+
+    work_dir.run_jj(["git", "export"]).success();
+    let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  bbc749308d7f foo
+    ○  b876c5f49546 bar
+    ◆  000000000000
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_conflict_sides() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"separate(" ", description.first_line(), conflict_sides) ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-mbase"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "base"])
+        .success();
+    work_dir.write_file("file", "base\n");
+    for name in ["a", "b", "c"] {
+        work_dir.run_jj(["new", "-m", name, "base"]).success();
+        work_dir
+            .run_jj(["bookmark", "create", "-r@", name])
+            .success();
+        work_dir.write_file("file", format!("{name}\n"));
+    }
+    work_dir.run_jj(["new", "-mmerge", "a", "b", "c"]).success();
+
+    let output = work_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @      merge 3
+    ├─┬─╮
+    │ │ ○  c 1
+    │ ○ │  b 1
+    │ ├─╯
+    ○ │  a 1
+    ├─╯
+    ○  base 1
+    ◆  1
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r@", "-T", "if(conflict, conflict_sides)"]);
+    insta::assert_snapshot!(output, @"
+    @  3
+    │
+    ~
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_log_git_head_ref() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.export.attach-head = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"
+    separate(" ",
+      commit_id.short(),
+      bookmarks,
+      if(git_head_ref, "(HEAD -> " ++ git_head_ref ++ ")"),
+    ) ++ "\n"
+    "#;
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+
+    // Git HEAD is detached at an anonymous commit
+    work_dir.run_jj(["commit", "-mcommit2"]).success();
+    let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  5454fd721754
+    ○  65f240c15bac
+    ○  b876c5f49546 main
+    ◆  000000000000
+    [EOF]
+    ");
+
+    // Git HEAD points to the bookmark
+    work_dir.run_jj(["new", "main"]).success();
+    let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  9683f4367feb
+    │ ○  65f240c15bac
+    ├─╯
+    ○  b876c5f49546 main (HEAD -> main)
+    ◆  000000000000
+    [EOF]
+    ");
+
+    // Non-colocated repo has no Git HEAD managed by jj
+    test_env
+        .run_jj_in(".", ["git", "init", "--no-colocate", "repo2"])
+        .success();
+    let work_dir = test_env.work_dir("repo2");
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    work_dir.run_jj(["git", "import"]).success();
+    let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  4f2ac68446f2
+    ○  ca757e9800be main
+    ◆  000000000000
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_tags() {
     let test_env = TestEnvironment::default();
@@ -761,7 +903,7 @@ fn test_log_tags() {
     let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
     insta::assert_snapshot!(output, @"
     @  510df2613fc8 (no tags)
-    ◆  3f672e728535 bar baz foo*
+    ◆  3f672e728535 bar baz foo^
     ◆  b876c5f49546 foo@git
     ◆  000000000000 (no tags)
     [EOF]
@@ -771,7 +913,7 @@ fn test_log_tags() {
     let output = work_dir.run_jj(["log", "-rall()", "-T", template]);
     insta::assert_snapshot!(output, @"
     @  L: R:
-    ◆  L: bar baz foo* R: bar@git
+    ◆  L: bar baz foo^ R: bar@git
     ◆  L: R: foo@git
     ◆  L: R:
     [EOF]
@@ -1610,6 +1610,7 @@ fn test_merge_tools() {
     :stat
     :types
     :name-only
+    :name-status
     :git
     :color-words
     diffedit3
@@ -363,6 +363,7 @@ fn test_diff_basic() {
       --stat
       --types
       --name-only
+      --name-status
 
     Usage: jj diff --template <TEMPLATE> [FILESETS]...
 
@@ -699,6 +700,71 @@ fn test_diff_name_only() {
     ");
 }
 
+#[test]
+fn test_diff_name_status() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("deleted", "d\n");
+    work_dir.write_file("modified", "m\n");
+    work_dir.write_file("renamed", "r\n");
+    work_dir.write_file("copied", "1\n2\n3\n4\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("deleted");
+    work_dir.write_file("modified", "mod\n");
+    work_dir.write_file("added", "add\n");
+    work_dir.remove_file("renamed");
+    work_dir.write_file("renamed-new", "r\n");
+    work_dir.write_file("copied", "1\n5\n3\n");
+    work_dir.write_file("copied-new", "1\n2\n3\n4\n");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-status"]), @"
+    A	added
+    M	copied
+    C	copied	copied-new
+    D	deleted
+    M	modified
+    R	renamed	renamed-new
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-only"]), @"
+    added
+    copied
+    copied-new
+    deleted
+    modified
+    renamed-new
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["diff", "--name-status", "--color=debug"]), @"
+    [38;5;2m<<diff name_status added::A	added>>[39m
+    [38;5;6m<<diff name_status modified::M	copied>>[39m
+    [38;5;2m<<diff name_status copied::C	copied	copied-new>>[39m
+    [38;5;1m<<diff name_status removed::D	deleted>>[39m
+    [38;5;6m<<diff name_status modified::M	modified>>[39m
+    [38;5;6m<<diff name_status renamed::R	renamed	renamed-new>>[39m
+    [EOF]
+    ");
+
+    // Conflicted files are shown as 'U'
+    work_dir.run_jj(["new", "root()"]).success();
+    work_dir.write_file("modified", "left\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "left"])
+        .success();
+    work_dir.run_jj(["new", "root()"]).success();
+    work_dir.write_file("modified", "right\n");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "right"])
+        .success();
+    work_dir.run_jj(["new", "left", "right"]).success();
+    let output = work_dir.run_jj(["diff", "--name-status", "--from=root()"]);
+    insta::assert_snapshot!(output, @"
+    U	modified
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_diff_renamed_file_and_dir() {
     let test_env = TestEnvironment::default();
@@ -735,6 +801,88 @@ fn test_diff_renamed_file_and_dir() {
     ");
 }
 
+#[test]
+fn test_diff_reverse() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1", "foo\n");
+    work_dir.write_file("file2", "1\n2\n3\n");
+    work_dir.run_jj(["new"]).success();
+    work_dir.remove_file("file1");
+    work_dir.write_file("file2", "1\n5\n3\n");
+    work_dir.write_file("file3", "foo\n");
+
+    let output = work_dir.run_jj(["diff", "--git", "--reverse"]);
+    insta::assert_snapshot!(output, @"
+    diff --git a/file3 b/file1
+    rename from file3
+    rename to file1
+    diff --git a/file2 b/file2
+    index 1ffc51b472..01e79c32a8 100644
+    --- a/file2
+    +++ b/file2
+    @@ -1,3 +1,3 @@
+     1
+    -5
+    +2
+     3
+    [EOF]
+    ");
+
+    // The reversed patch should undo the change
+    let inverse_output = work_dir.run_jj(["diff", "--git", "--from=@", "--to=@-"]);
+    assert_eq!(output, inverse_output);
+    let output = work_dir.run_jj(["diff", "--git", "--reverse", "--from=@-", "--to=@"]);
+    assert_eq!(output, inverse_output);
+
+    // Reversed diff from conflicted to resolved commit
+    test_env.run_jj_in(".", ["git", "init", "repo2"]).success();
+    let work_dir = test_env.work_dir("repo2");
+    create_commit_with_files(&work_dir, "base", &[], &[("file", "a\n")]);
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", "b\n")]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "c\n")]);
+    create_commit_with_files(&work_dir, "merge", &["left", "right"], &[]);
+    create_commit_with_files(&work_dir, "resolved", &["merge"], &[("file", "d\n")]);
+    let output = work_dir.run_jj(["diff", "--git", "-rresolved"]);
+    insta::assert_snapshot!(output, @r#"
+    diff --git a/file b/file
+    index 0000000000..4bcfe98e64 100644
+    --- a/file
+    +++ b/file
+    @@ -1,8 +1,1 @@
+    -<<<<<<< conflict 1 of 1
+    -%%%%%%% diff from: yqosqzyt 41e914b6 "base"
+    -\\\\\\\        to: yostqsxw 72f22112 "left"
+    --a
+    -+b
+    -+++++++ kpqxywon 55d29f12 "right"
+    -c
+    ->>>>>>> conflict 1 of 1 ends
+    +d
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["diff", "--git", "--reverse", "-rresolved"]);
+    insta::assert_snapshot!(output, @r#"
+    diff --git a/file b/file
+    index 4bcfe98e64..0000000000 100644
+    --- a/file
+    +++ b/file
+    @@ -1,1 +1,8 @@
+    -d
+    +<<<<<<< conflict 1 of 1
+    +%%%%%%% diff from: yqosqzyt 41e914b6 "base"
+    +\\\\\\\        to: yostqsxw 72f22112 "left"
+    +-a
+    ++b
+    ++++++++ kpqxywon 55d29f12 "right"
+    +c
+    +>>>>>>> conflict 1 of 1 ends
+    [EOF]
+    "#);
+}
+
 #[test]
 fn test_diff_bad_args() {
     let test_env = TestEnvironment::default();
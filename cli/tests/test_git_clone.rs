@@ -1055,6 +1055,50 @@ fn test_git_clone_with_depth() {
     ");
 }
 
+#[test]
+fn test_git_clone_sha256() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    let clone_dir = test_env.work_dir("clone");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git::init_with_object_hash(git_repo_path, gix::hash::Kind::Sha256);
+    set_up_non_empty_git_repo(&git_repo);
+
+    // The repo is recreated with the object hash algorithm of the remote
+    let output = root_dir.run_jj(["git", "clone", "source", "clone"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] tracked
+    Setting the revset alias `trunk()` to `main@origin`.
+    Working copy  (@) now at: zutuyxnq a5baa198 (empty) (no description set)
+    Parent commit (@-)      : tppoysxx 3964b98d main | message
+    Added 1 files, modified 0 files, removed 0 files
+    [EOF]
+    "#);
+    let clone_git_repo = git::open(clone_dir.root().join(".jj/repo/store/git"));
+    assert_eq!(clone_git_repo.object_hash(), gix::hash::Kind::Sha256);
+
+    // Objects round-trip with the same ids
+    let main_id = git_repo.find_reference("main")?.peel_to_commit()?.id;
+    let output = clone_dir.run_jj(["log", "--no-graph", "-rmain", "-Tcommit_id"]);
+    assert_eq!(output.stdout.normalized(), main_id.to_string());
+    let output = clone_dir.run_jj(["file", "show", "-rmain", "file"]);
+    insta::assert_snapshot!(output, @"content[EOF]");
+
+    // Mismatched object hash algorithm is reported
+    let output = root_dir.run_jj(["git", "clone", "--object-hash=sha1", "source", "clone2"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Fetching into new repo in "$TEST_ENV/clone2"
+    Error: The remote repository uses sha256 object hashes, but the local repository uses sha1
+    [EOF]
+    [exit status: 1]
+    "#);
+    Ok(())
+}
+
 #[test]
 fn test_git_clone_invalid_immutable_heads() {
     let test_env = TestEnvironment::default();
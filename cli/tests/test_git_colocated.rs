@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fmt::Write as _;
+use std::io::Write as _;
 use std::path::Path;
 
 use testutils::TestResult;
@@ -172,6 +173,263 @@ fn test_git_colocated_intent_to_add() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_debug_git_index() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file1.txt", "contents");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file2.txt", "contents");
+    work_dir.run_jj(["status"]).success();
+    let output = work_dir.run_jj(["debug", "git-index"]);
+    insta::assert_snapshot!(output, @"
+    Unconflicted Mode(FILE) 0839b2e9412b314cb8bb9a20f587aa13752ae310 ctime=0:0 mtime=0:0 size=0 flags=0 file1.txt
+    Unconflicted Mode(FILE) e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 ctime=0:0 mtime=0:0 size=0 flags=20004000 file2.txt
+    [EOF]
+    ");
+
+    // Non-colocated workspaces have no Git index to show
+    test_env
+        .run_jj_in(".", ["git", "init", "non-colocated"])
+        .success();
+    let output = test_env
+        .work_dir("non-colocated")
+        .run_jj(["debug", "git-index"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: This command requires a workspace colocated with Git
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_colocated_debug_git_index_check() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = testutils::git::open(work_dir.root());
+
+    work_dir.write_file("file1", "1");
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file1", "modified");
+    work_dir.write_file("file2", "2");
+
+    // The index written by jj matches
+    let output = work_dir.run_jj(["debug", "git-index", "--check"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    The Git index matches the working-copy commit.
+    [EOF]
+    ");
+
+    // Staging a file with Git makes the index diverge
+    let mut index_manager = testutils::git::IndexManager::new(&git_repo);
+    index_manager.add_file("file3", b"3");
+    index_manager.sync_index();
+    let output = work_dir.run_jj(["debug", "git-index", "--check"]);
+    insta::assert_snapshot!(output, @"
+    M file3
+    [EOF]
+    ------- stderr -------
+    Error: The Git index doesn't match the working-copy commit
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Resetting the index through jj fixes it
+    work_dir.run_jj(["new", "@-"]).success();
+    let output = work_dir.run_jj(["debug", "git-index", "--check"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    The Git index matches the working-copy commit.
+    [EOF]
+    ");
+
+    // Not supported in non-colocated workspaces
+    test_env
+        .run_jj_in(".", ["git", "init", "--no-colocate", "repo2"])
+        .success();
+    let output = test_env
+        .work_dir("repo2")
+        .run_jj(["debug", "git-index", "--check"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: This command requires a workspace colocated with Git
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_colocated_intent_to_add_disabled() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.write-intent-to-add = false");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    // A file added directly on top of the root commit should be absent from
+    // the index
+    work_dir.write_file("file1.txt", "contents");
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @"
+    Working copy changes:
+    A file1.txt
+    Working copy  (@) : qpvuntsm 303618e3 (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_index_state(work_dir.root()), @"");
+
+    // Committed files are still written to the index as usual
+    work_dir.run_jj(["new"]).success();
+    work_dir.write_file("file2.txt", "contents");
+    work_dir.run_jj(["status"]).success();
+    insta::assert_snapshot!(get_index_state(work_dir.root()), @"Unconflicted Mode(FILE) 0839b2e9412b ctime=0:0 mtime=0:0 size=0 flags=0 file1.txt");
+
+    // If we edit an existing commit, new files are still omitted
+    work_dir.run_jj(["edit", "@-"]).success();
+    insta::assert_snapshot!(get_index_state(work_dir.root()), @"");
+}
+
+#[test]
+fn test_git_colocated_orig_head() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root());
+
+    // Moving HEAD from unborn state shouldn't create ORIG_HEAD
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["new"]).success();
+    assert!(git_repo.try_find_reference("ORIG_HEAD")?.is_none());
+    insta::assert_snapshot!(
+        git_repo.head_id()?.to_string(),
+        @"6b57e33cc56babbeaa6bcd6e2a296236b52ad93c"
+    );
+
+    // ORIG_HEAD should point to the previous HEAD
+    work_dir.write_file("file", "b\n");
+    work_dir.run_jj(["new"]).success();
+    insta::assert_snapshot!(
+        git_repo.find_reference("ORIG_HEAD")?.id().to_string(),
+        @"6b57e33cc56babbeaa6bcd6e2a296236b52ad93c"
+    );
+
+    // ORIG_HEAD should also be updated when HEAD moves backwards
+    let head_id = git_repo.head_id()?.detach();
+    work_dir.run_jj(["new", "root()"]).success();
+    assert!(git_repo.head()?.is_unborn());
+    assert_eq!(git_repo.find_reference("ORIG_HEAD")?.id(), head_id);
+
+    Ok(())
+}
+
+#[test]
+fn test_git_colocated_advance_head_bookmark() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.advance-head-bookmark = true");
+    test_env.add_config("git.export.attach-head = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root());
+    let get_head_name = || -> TestResult<String> {
+        let head = git_repo.head()?;
+        Ok(match head.referent_name() {
+            Some(name) => name.as_bstr().to_string(),
+            None => format!("detached at {}", head.id().unwrap().shorten()?),
+        })
+    };
+
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["new", "-m=second"]).success();
+    insta::assert_snapshot!(get_head_name()?, @"refs/heads/main");
+
+    // The bookmark is advanced to the commit we're leaving, and HEAD stays
+    // attached to it
+    work_dir.write_file("file", "b\n");
+    let output = work_dir.run_jj(["new", "-m=third"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Working copy  (@) now at: mzvwutvl e9fde517 (empty) third
+    Parent commit (@-)      : zsuskuln cdd8fe60 main | second
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  e9fde51739bb4e4ecadd82a0a96e49e78ad96dda third
+    ○  cdd8fe60722ecda1924eb537e22d705b1383bc7d main second
+    ○  d89b4dad6db6f83b49b24e667aa2d125e139a0a8 first
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_head_name()?, @"refs/heads/main");
+
+    // Explicit multiple parents don't advance the bookmark
+    work_dir.write_file("file", "c\n");
+    work_dir.run_jj(["new", "@", "main", "-m=merge"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @    51b635a7eec0ab0b5082e35a0321dd749893300a merge
+    ├─╮
+    ○ │  b5d4cc0dfcbdbd76817ab4c38e7b55ebbb06f25c third
+    ├─╯
+    ○  cdd8fe60722ecda1924eb537e22d705b1383bc7d main second
+    ○  d89b4dad6db6f83b49b24e667aa2d125e139a0a8 first
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    work_dir.run_jj(["undo"]).success();
+
+    // Multiple bookmarks on the parent disable the advance
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "other"])
+        .success();
+    work_dir.run_jj(["new", "-m=fourth"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  729bdfe1eeb6b87c03e9ae46ac86d5a8ecea6a59 fourth
+    ○  b5d4cc0dfcbdbd76817ab4c38e7b55ebbb06f25c third
+    ○  cdd8fe60722ecda1924eb537e22d705b1383bc7d main other second
+    ○  d89b4dad6db6f83b49b24e667aa2d125e139a0a8 first
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_head_name()?, @"detached at b5d4cc0");
+
+    // The bookmark isn't advanced in non-colocated workspace
+    test_env.run_jj_in(".", ["git", "init", "plain"]).success();
+    let plain_dir = test_env.work_dir("plain");
+    plain_dir.run_jj(["describe", "-m=first"]).success();
+    plain_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    plain_dir.run_jj(["new", "-m=second"]).success();
+    plain_dir.write_file("file", "a\n");
+    plain_dir.run_jj(["new", "-m=third"]).success();
+    insta::assert_snapshot!(get_log_output(&plain_dir), @"
+    @  01097a29afde6c9a7574e6c3b99a9bc8d2ccb7ea third
+    ○  45af8d2db45bc670178a0456212fdce2b74f85e0 second
+    ○  ebe67d4ee46b4a00f71c597d97c42d899e4e3dc6 main first
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_git_colocated_new_wc_commit_when_wc_immutable() {
     let test_env = TestEnvironment::default();
@@ -435,6 +693,57 @@ fn test_git_colocated_unborn_bookmark() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_export_reflog() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::init(work_dir.root());
+    work_dir
+        .run_jj(["git", "init", "--git-repo", "."])
+        .success();
+    let get_reflog = |name: &str| -> TestResult<String> {
+        let git_ref = git_repo.find_reference(name)?;
+        let mut platform = git_ref.log_iter();
+        let mut output = String::new();
+        for line in platform.rev()?.into_iter().flatten() {
+            let line = line?;
+            writeln!(output, "{} {}", line.new_oid, line.message)?;
+        }
+        Ok(output)
+    };
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    work_dir.run_jj(["new", "-mnew"]).success();
+    work_dir
+        .run_jj(["bookmark", "move", "foo", "--to=@"])
+        .success();
+    insta::assert_snapshot!(get_reflog("refs/heads/foo")?, @"
+    7410bd2ac3188b286fd099a22999e0990a734155 export bookmark foo from jj
+    e8849ae12c709f2321908879bc724fdb2ab8a781 export bookmark foo from jj
+    ");
+
+    // Reflog isn't written if disabled by Git config
+    work_dir.run_jj(["new"]).success();
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".git/config"))?;
+    config_file.write_all(b"[core]\nlogAllRefUpdates = false\n")?;
+    work_dir
+        .run_jj(["bookmark", "move", "foo", "--to=@"])
+        .success();
+    insta::assert_snapshot!(git_repo
+        .find_reference("refs/heads/foo")?
+        .id()
+        .to_string(), @"3bf302b3e4da6e141e93d0a2fc005d60c63510d1");
+    insta::assert_snapshot!(get_reflog("refs/heads/foo")?, @"
+    7410bd2ac3188b286fd099a22999e0990a734155 export bookmark foo from jj
+    e8849ae12c709f2321908879bc724fdb2ab8a781 export bookmark foo from jj
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_git_colocated_export_bookmarks_on_snapshot() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -471,6 +780,45 @@ fn test_git_colocated_export_bookmarks_on_snapshot() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_rewrite_bookmarked_commit() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::init(work_dir.root());
+    work_dir
+        .run_jj(["git", "init", "--git-repo", "."])
+        .success();
+
+    work_dir.write_file("file", "initial");
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    work_dir.run_jj(["new"]).success();
+
+    // Rewriting the bookmarked commit moves the bookmark, and the Git ref and
+    // the @git remote bookmark follow it
+    work_dir.run_jj(["describe", "-r@-", "-mamended"]).success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  f548c8ae5abcb05e10fa163865afd62453768d59
+    ○  89b3a36f5325e35a89d9595cb7e557c9cc6d0af1 foo amended
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["bookmark", "list", "--all-remotes"]), @"
+    foo: qpvuntsm 89b3a36f amended
+      @git: qpvuntsm 89b3a36f amended
+    [EOF]
+    ");
+    let output = work_dir
+        .run_jj(["log", "--no-graph", "-Tcommit_id", "-rfoo"])
+        .success();
+    assert_eq!(
+        git_repo.find_reference("refs/heads/foo")?.id().to_string(),
+        output.stdout.raw()
+    );
+    Ok(())
+}
+
 #[test]
 fn test_git_colocated_rebase_on_import() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -664,7 +1012,7 @@ fn test_git_colocated_bookmark_at_root() -> TestResult {
     insta::assert_snapshot!(output, @"
     ------- stderr -------
     Warning: Target revision is empty.
-    Moved 1 bookmarks to zzzzzzzz 00000000 foo* | (empty) (no description set)
+    Moved 1 bookmarks to zzzzzzzz 00000000 foo^ | (empty) (no description set)
     Warning: Failed to export some bookmarks:
       foo@git: Ref cannot point to the root commit in Git
     [EOF]
@@ -829,6 +1177,73 @@ fn test_git_colocated_fetch_deleted_or_moved_bookmark() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_fetch_dry_run() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    let origin_dir = test_env.work_dir("origin");
+    git::init(origin_dir.root());
+    origin_dir.run_jj(["git", "init", "--git-repo=."]).success();
+    origin_dir.run_jj(["describe", "-m=A"]).success();
+    origin_dir
+        .run_jj(["bookmark", "create", "-r@", "A"])
+        .success();
+    origin_dir.run_jj(["new", "-m=B_to_delete"]).success();
+    origin_dir
+        .run_jj(["bookmark", "create", "-r@", "B_to_delete"])
+        .success();
+    origin_dir.run_jj(["new", "-m=original C", "@-"]).success();
+    origin_dir
+        .run_jj(["bookmark", "create", "-r@", "C_to_move"])
+        .success();
+
+    let clone_dir = test_env.work_dir("clone");
+    git::clone(clone_dir.root(), origin_dir.root().to_str().unwrap(), None);
+    clone_dir.run_jj(["git", "init", "--git-repo=."]).success();
+    clone_dir.run_jj(["new", "A"]).success();
+    let log_before = get_log_output(&clone_dir);
+    let op_log_before = clone_dir.run_jj(["op", "log", "-Tdescription"]);
+
+    origin_dir
+        .run_jj(["bookmark", "delete", "B_to_delete"])
+        .success();
+    origin_dir
+        .run_jj(["describe", "C_to_move", "-m", "moved C"])
+        .success();
+    let output = clone_dir.run_jj(["git", "fetch", "--dry-run"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: B_to_delete@origin [deleted] untracked
+    bookmark: C_to_move@origin   [updated] tracked
+    Would abandon 1 commits that are no longer reachable:
+      zsuskuln b2ea51c0 B_to_delete@git | (empty) B_to_delete
+    Would update 1 rewritten commits.
+    Dry-run requested, not importing.
+    [EOF]
+    ");
+    // Neither the view nor the operation log has changed
+    assert_eq!(get_log_output(&clone_dir), log_before);
+    assert_eq!(
+        clone_dir.run_jj(["op", "log", "-Tdescription"]),
+        op_log_before
+    );
+
+    // The remote-tracking Git refs were restored, so the actual fetch imports
+    // the same changes.
+    let output = clone_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @r"
+    ------- stderr -------
+    bookmark: B_to_delete@origin [deleted] untracked
+    bookmark: C_to_move@origin   [updated] tracked
+    Abandoned 1 commits that are no longer reachable:
+      zsuskuln b2ea51c0 B_to_delete@git | (empty) B_to_delete
+    Updated 1 rewritten commits.
+    [EOF]
+    ");
+
+    Ok(())
+}
+
 #[test]
 fn test_git_colocated_rebase_dirty_working_copy() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -878,6 +1293,87 @@ fn test_git_colocated_rebase_dirty_working_copy() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_rebase_dirty_working_copy_import_deleted_bookmark() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let set_up = |setting: &str| -> TestResult<TestWorkDir<'_>> {
+        let work_dir = test_env.work_dir(setting);
+        let git_repo = git::init(work_dir.root());
+        work_dir.run_jj(["git", "init", "--git-repo=."]).success();
+        work_dir
+            .run_jj([
+                "config",
+                "set",
+                "--repo",
+                "git.import-deleted-bookmark",
+                setting,
+            ])
+            .success();
+        work_dir.write_file("file", "base");
+        work_dir.run_jj(["new"]).success();
+        work_dir.write_file("file", "old");
+        work_dir
+            .run_jj(["bookmark", "create", "-r@", "feature"])
+            .success();
+
+        // Make the working-copy dirty, delete the checked out bookmark.
+        work_dir.write_file("file", "new");
+        git_repo.find_reference("refs/heads/feature")?.delete()?;
+        Ok(work_dir)
+    };
+
+    // The local bookmark is kept, and exported back to Git.
+    let work_dir = set_up("keep-local")?;
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @"
+    Working copy changes:
+    M file
+    Working copy  (@) : kkmpptxz 9192abb0 feature | (no description set)
+    Parent commit (@-): qpvuntsm 1c7912fe (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to export some bookmarks:
+      feature@git: Modified ref had been deleted in Git
+    Done importing changes from the underlying Git repo.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  9192abb08b8bbeb109304eb75f8f5a1b3b10c192 feature
+    ○  1c7912fe209e6ecbd54e938fc07e351fac1e2214
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"new");
+    work_dir.run_jj(["status"]).success();
+    let git_repo = git::open(work_dir.root());
+    assert!(git_repo.try_find_reference("refs/heads/feature")?.is_some());
+
+    // The local bookmark is deleted, but the commits are kept.
+    let work_dir = set_up("delete")?;
+    let output = work_dir.run_jj(["status"]);
+    insta::assert_snapshot!(output, @"
+    Working copy changes:
+    M file
+    Working copy  (@) : znkkpsqq 9e7a0ffb (no description set)
+    Parent commit (@-): vruxwmqv af980af2 (no description set)
+    [EOF]
+    ------- stderr -------
+    Warning: Failed to export some bookmarks:
+      feature@git: Modified ref had been deleted in Git
+    Done importing changes from the underlying Git repo.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  9e7a0ffba36f1f1d832779bff478be71e0c66b83
+    ○  af980af2d0c8a83ffb9c123611c81d2ba3126ddf
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"new");
+
+    Ok(())
+}
+
 #[test]
 fn test_git_colocated_external_checkout() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -969,7 +1465,7 @@ fn test_git_colocated_external_checkout() -> TestResult {
     [EOF]
     ------- stderr -------
     Reset the working copy parent to the new Git HEAD.
-    Operation left uncommitted because --no-integrate-operation was requested: 4dd0ee71039d
+    Operation left uncommitted because --no-integrate-operation was requested: 7fe1b9440d72
     [EOF]
     ");
     let output = work_dir.run_jj(["status", "--no-integrate-operation"]);
@@ -980,10 +1476,152 @@ fn test_git_colocated_external_checkout() -> TestResult {
     [EOF]
     ------- stderr -------
     Reset the working copy parent to the new Git HEAD.
-    Operation left uncommitted because --no-integrate-operation was requested: 1c501b6939b3
+    Operation left uncommitted because --no-integrate-operation was requested: 43203387d082
+    [EOF]
+    ");
+
+    Ok(())
+}
+
+#[test]
+fn test_git_colocated_external_checkout_op_description() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::init(work_dir.root());
+    let get_last_op_description = || {
+        work_dir.run_jj([
+            "op",
+            "log",
+            "--no-graph",
+            "--limit=1",
+            "--ignore-working-copy",
+            "-T=description ++ \"\\n\"",
+        ])
+    };
+
+    work_dir.run_jj(["git", "init", "--git-repo=."]).success();
+    work_dir.run_jj(["ci", "-m=A"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "master"])
+        .success();
+    work_dir.run_jj(["new", "-m=B", "root()"]).success();
+    work_dir.run_jj(["new"]).success();
+
+    // Check out another bookmark by external command
+    let target = git_repo
+        .find_reference("refs/heads/master")?
+        .into_fully_peeled_id()?
+        .detach();
+    git::set_head_to_id(&git_repo, target);
+    work_dir.run_jj(["status"]).success();
+    insta::assert_snapshot!(get_last_op_description(), @"
+    import git head: 319eaafc8fd0 -> 8777db25171c
+    [EOF]
+    ");
+
+    // Make HEAD unborn by external command
+    git::set_symbolic_reference(&git_repo, "HEAD", "refs/heads/unborn");
+    work_dir.run_jj(["status"]).success();
+    insta::assert_snapshot!(get_last_op_description(), @"
+    import git head: 8777db25171c -> unborn
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(windows, ignore = "uses POSIX sh")]
+fn test_git_colocated_pre_commit_hook() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let hooks_dir = test_env.env_root().join("hooks");
+    std::fs::create_dir(&hooks_dir)?;
+    let hook_path = hooks_dir.join("pre-commit");
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\necho \"staged: $(git diff --cached --name-only)\"\nexit 1\n",
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".git/config"))?;
+    writeln!(
+        config_file,
+        "[core]\n\thooksPath = {}",
+        hooks_dir.to_str().unwrap().replace('\\', "/")
+    )?;
+    drop(config_file);
+    work_dir.write_file("file", "contents");
+
+    // Hooks are not run unless enabled
+    work_dir.run_jj(["commit", "-mfirst"]).success();
+
+    // The failing hook blocks the commit, and sees the staged changes
+    test_env.add_config("git.run-hooks = true");
+    work_dir.write_file("file", "new contents");
+    work_dir.write_file("file2", "");
+    let output = work_dir.run_jj(["commit", "-msecond"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    staged: file
+    file2
+    Error: The Git pre-commit hook failed with exit status: 1
+    Hint: Use `--no-verify` to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["describe", "-mthird"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    staged: file
+    file2
+    Error: The Git pre-commit hook failed with exit status: 1
+    Hint: Use `--no-verify` to skip the hook.
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  d81fb5ecf129a8a239c2e707ea645e8c7a64e753
+    ○  cdf2536022bed704032288efd7fcc8ca5d7f3168 first
+    ◆  0000000000000000000000000000000000000000
     [EOF]
     ");
 
+    // --no-verify skips the hook
+    work_dir
+        .run_jj(["commit", "-msecond", "--no-verify"])
+        .success();
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  1c11b940ec55e63595c465d3ca885d598994d8be
+    ○  d52f07e33a469b09ad48e9aa06e022a8a6f88762 second
+    ○  cdf2536022bed704032288efd7fcc8ca5d7f3168 first
+    ◆  0000000000000000000000000000000000000000
+    [EOF]
+    ");
+
+    // Hooks are not run in non-colocated workspaces
+    test_env
+        .run_jj_in(".", ["git", "init", "--no-colocate", "non-colocated"])
+        .success();
+    let work_dir = test_env.work_dir("non-colocated");
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))?;
+    writeln!(
+        config_file,
+        "[core]\n\thooksPath = {}",
+        hooks_dir.to_str().unwrap().replace('\\', "/")
+    )?;
+    drop(config_file);
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["commit", "-mfirst"]).success();
     Ok(())
 }
 
@@ -1592,6 +2230,43 @@ fn test_git_colocated_update_index_3_sided_conflict() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_colocated_update_index_chmod() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "contents\n");
+    work_dir.run_jj(["new"]).success();
+
+    // Index should be updated when the parent commit is rewritten
+    work_dir
+        .run_jj(["file", "chmod", "+x", "-r@-", "file"])
+        .success();
+    let output = work_dir.run_jj([
+        "file",
+        "list",
+        "-r@-",
+        "-Tpath ++ ' ' ++ executable ++ '\n'",
+    ]);
+    insta::assert_snapshot!(output, @"
+    file true
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_index_state(work_dir.root()), @"
+    Unconflicted Mode(FILE | FILE_EXECUTABLE) 12f00e90b6ef ctime=0:0 mtime=0:0 size=0 flags=0 file
+    ");
+
+    work_dir
+        .run_jj(["file", "chmod", "n", "-r@-", "file"])
+        .success();
+    insta::assert_snapshot!(get_index_state(work_dir.root()), @"
+    Unconflicted Mode(FILE) 12f00e90b6ef ctime=0:0 mtime=0:0 size=0 flags=0 file
+    ");
+}
+
 #[must_use]
 fn get_log_output_divergence(work_dir: &TestWorkDir) -> CommandOutput {
     let template = r#"
@@ -13,10 +13,16 @@
 // limitations under the License.
 
 use std::io::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
 
+use gix::objs::Write as _;
 use indoc::indoc;
 use testutils::TestResult;
+use testutils::ensure_running_outside_ci;
 use testutils::git;
+use testutils::is_external_tool_installed;
 
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
@@ -171,6 +177,67 @@ fn test_git_fetch_single_remote() {
     ");
 }
 
+#[test]
+fn test_git_fetch_progress_none() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.rem1.auto-track-bookmarks = '*'");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    add_git_remote(&test_env, &work_dir, "rem1");
+
+    // No progress is shown, but the refs are still imported
+    let output = work_dir.run_jj(["git", "fetch", "--progress=none"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hint: Fetching from the only existing remote: rem1
+    bookmark: rem1@rem1 [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    rem1: ppspxspk 4acd0343 message
+      @rem1: ppspxspk 4acd0343 message
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_fetch_with_url_rewrite() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.rem1.auto-track-bookmarks = '*'");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    init_git_remote(&test_env, "rem1");
+    work_dir
+        .run_jj(["git", "remote", "add", "rem1", "example:rem1"])
+        .success();
+
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))
+        .unwrap();
+    writeln!(
+        config_file,
+        "[url \"{}/\"]\n\tinsteadOf = example:",
+        test_env.env_root().to_str().unwrap().replace('\\', "/")
+    )
+    .unwrap();
+    drop(config_file);
+
+    // Git resolves the remote URL through the rewrite rule
+    let output = work_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hint: Fetching from the only existing remote: rem1
+    bookmark: rem1@rem1 [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    rem1: ppspxspk 4acd0343 message
+      @rem1: ppspxspk 4acd0343 message
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_fetch_single_remote_all_remotes_flag() {
     let test_env = TestEnvironment::default();
@@ -498,6 +565,133 @@ fn test_git_fetch_all_remotes() {
     ");
 }
 
+#[test]
+fn test_git_fetch_all_remotes_with_failing_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    add_git_remote(&test_env, &work_dir, "rem1");
+    add_git_remote(&test_env, &work_dir, "rem2");
+    work_dir
+        .run_jj(["git", "remote", "add", "bogus", "nonexistent"])
+        .success();
+
+    // The failing remote is reported, but the others are still fetched
+    let output = work_dir.run_jj(["git", "fetch", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Failed to fetch from remote bogus: Could not find repository at '$TEST_ENV/repo/nonexistent'
+    bookmark: rem1@rem1 [new] untracked
+    bookmark: rem2@rem2 [new] untracked
+    Error: Failed to fetch from some remotes: bogus
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    rem1@rem1: ppspxspk 4acd0343 message
+    rem2@rem2: pzqqpnpo 44c57802 message
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_fetch_pull_requests() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = add_git_remote(&test_env, &work_dir, "origin");
+    git::add_commit(&git_repo, "refs/pull/1/head", "file", b"", "pr 1", &[]);
+
+    // The forge can't be detected from a local path
+    let output = work_dir.run_jj(["git", "fetch", "--prs"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot detect the forge of remote origin
+    Hint: Use `--prs=github` or `--prs=gitlab` to specify it.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    let output = work_dir.run_jj(["git", "fetch", "--prs=github"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: origin@origin [new] untracked
+    bookmark: pr/1@origin   [new] untracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    origin@origin: qmyrypzk ab8b299e message
+    pr/1@origin: olzxwqyl e28baaae pr 1
+    [EOF]
+    ");
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-r=pr/1@origin",
+        r#"-T=description.first_line() ++ "\n""#,
+    ]);
+    insta::assert_snapshot!(output, @"
+    pr 1
+    [EOF]
+    ");
+
+    // The heads are fetched into a separate namespace, so they aren't
+    // remote-tracking branches in Git
+    let local_git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    assert!(
+        local_git_repo
+            .try_find_reference("refs/jj/pull/origin/pr/1")
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        local_git_repo
+            .try_find_reference("refs/remotes/origin/pr/1")
+            .unwrap()
+            .is_none()
+    );
+
+    // Fetching without --prs keeps the pull request bookmarks
+    let output = work_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    origin@origin: qmyrypzk ab8b299e message
+    pr/1@origin: olzxwqyl e28baaae pr 1
+    [EOF]
+    ");
+
+    // A branch of the same name on the remote takes precedence over the pull
+    // request head
+    git::add_commit(&git_repo, "refs/heads/pr/1", "file", b"", "branch", &[]);
+    let output = work_dir.run_jj(["git", "fetch", "--prs=github"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: pr/1@origin [updated] untracked
+    Abandoned 1 commits that are no longer reachable:
+      olzxwqyl e28baaae pr 1
+    Warning: Failed to import some Git refs:
+      refs/jj/pull/origin/pr/1
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "fetch", "--prs=github"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Failed to import some Git refs:
+      refs/jj/pull/origin/pr/1
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    origin@origin: qmyrypzk ab8b299e message
+    pr/1@origin: wwmkrzoy 9611d35b branch
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_fetch_multiple_remotes_from_config() {
     let test_env = TestEnvironment::default();
@@ -711,6 +905,295 @@ fn test_git_fetch_prune_before_updating_tips() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_fetch_mirror_into() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let origin_repo = add_git_remote(&test_env, &work_dir, "origin");
+    add_commit_to_branch(&origin_repo, "feature", "feature");
+    let backup_repo = add_git_remote(&test_env, &work_dir, "backup");
+    work_dir
+        .run_jj(["git", "fetch", "--remote", "backup"])
+        .success();
+    let list_backup_branches = || -> TestResult<Vec<String>> {
+        let names = backup_repo
+            .references()?
+            .local_branches()?
+            .map(|git_ref| git_ref.unwrap().name().shorten().to_string())
+            .collect();
+        Ok(names)
+    };
+
+    // Bookmarks missing on the source remote are kept by default
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "backup",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin [new] untracked
+    bookmark: origin@origin  [new] untracked
+    Changes to push to backup:
+      bookmark: feature [add to fa6b3f486a24]
+      bookmark: origin [add to ab8b299ea075]
+    [EOF]
+    ");
+    assert_eq!(list_backup_branches()?, ["backup", "feature", "origin"]);
+
+    // Nothing to push if the mirror is up to date
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "backup",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Mirror remote backup is already up to date.
+    Nothing changed.
+    [EOF]
+    ");
+
+    // Deleted bookmarks are removed from the mirror with --prune
+    origin_repo.find_reference("refs/heads/feature")?.delete()?;
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "backup",
+        "--prune",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      wuosmmql fa6b3f48 feature@backup | feature
+    Changes to push to backup:
+      bookmark: backup [delete from 0e4421cbfe5e]
+      bookmark: feature [delete from fa6b3f486a24]
+    [EOF]
+    ");
+    assert_eq!(list_backup_branches()?, ["origin"]);
+
+    // Can't mirror into the source remote
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "origin",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot mirror remote origin into itself
+    [EOF]
+    [exit status: 1]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_mirror_into_prune_config() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let origin_repo = add_git_remote(&test_env, &work_dir, "origin");
+    add_commit_to_branch(&origin_repo, "feature", "feature");
+    let backup_repo = add_git_remote(&test_env, &work_dir, "backup");
+    work_dir
+        .run_jj(["git", "fetch", "--remote", "backup"])
+        .success();
+    work_dir
+        .run_jj([
+            "git",
+            "fetch",
+            "--remote",
+            "origin",
+            "--mirror-into",
+            "backup",
+        ])
+        .success();
+    let list_backup_branches = || -> TestResult<Vec<String>> {
+        let names = backup_repo
+            .references()?
+            .local_branches()?
+            .map(|git_ref| git_ref.unwrap().name().shorten().to_string())
+            .collect();
+        Ok(names)
+    };
+    assert_eq!(list_backup_branches()?, ["backup", "feature", "origin"]);
+
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))?;
+    writeln!(config_file, "[fetch]\n\tprune = true")?;
+    drop(config_file);
+    origin_repo.find_reference("refs/heads/feature")?.delete()?;
+
+    // --no-prune overrides fetch.prune
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "backup",
+        "--no-prune",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Mirror remote backup is already up to date.
+    Nothing changed.
+    [EOF]
+    ");
+    assert_eq!(list_backup_branches()?, ["backup", "feature", "origin"]);
+
+    // Deleted bookmarks are removed from the mirror without --prune
+    let output = work_dir.run_jj([
+        "git",
+        "fetch",
+        "--remote",
+        "origin",
+        "--mirror-into",
+        "backup",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      wuosmmql fa6b3f48 feature@backup | feature
+    Changes to push to backup:
+      bookmark: backup [delete from 0e4421cbfe5e]
+      bookmark: feature [delete from fa6b3f486a24]
+    [EOF]
+    ");
+    assert_eq!(list_backup_branches()?, ["origin"]);
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_mirror_into_annotated_tag() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let origin_repo = add_git_remote(&test_env, &work_dir, "origin");
+    let commit_id = add_commit_to_branch(&origin_repo, "feature", "feature");
+    let tag_id = origin_repo
+        .tag(
+            "v1",
+            commit_id,
+            gix::object::Kind::Commit,
+            None,
+            "annotated",
+            gix::refs::transaction::PreviousValue::MustNotExist,
+        )?
+        .id()
+        .detach();
+    let backup_repo = add_git_remote(&test_env, &work_dir, "backup");
+
+    // The annotated tag object is pushed to the mirror as is
+    work_dir
+        .run_jj([
+            "git",
+            "fetch",
+            "--remote",
+            "origin",
+            "--mirror-into",
+            "backup",
+        ])
+        .success();
+    let backup_tag = backup_repo.find_reference("refs/tags/v1")?;
+    assert_eq!(backup_tag.target().try_id(), Some(tag_id.as_ref()));
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_prune_config() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let origin_repo = add_git_remote(&test_env, &work_dir, "origin");
+    add_commit_to_branch(&origin_repo, "feature", "feature");
+    work_dir.run_jj(["git", "fetch"]).success();
+
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))?;
+    writeln!(config_file, "[fetch]\n\tprune = false")?;
+    drop(config_file);
+    origin_repo.find_reference("refs/heads/feature")?.delete()?;
+
+    // Deleted bookmarks are kept if fetch.prune is false
+    let output = work_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    feature@origin: wuosmmql fa6b3f48 feature
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // --prune overrides fetch.prune
+    let output = work_dir.run_jj(["git", "fetch", "--prune"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      wuosmmql fa6b3f48 feature
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Tags aren't pruned unless fetch.pruneTags is also set
+    add_commit_to_branch(&origin_repo, "feature", "feature");
+    let commit_oid = origin_repo
+        .find_reference("refs/heads/origin")?
+        .id()
+        .detach();
+    let constraint = gix::refs::transaction::PreviousValue::MustNotExist;
+    origin_repo.tag_reference("v1", commit_oid, constraint)?;
+    work_dir
+        .run_jj(["git", "fetch", "--branch=*", "--tag=*"])
+        .success();
+    let mut config_file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))?;
+    writeln!(config_file, "[fetch]\n\tprune = true")?;
+    drop(config_file);
+    origin_repo.find_reference("refs/heads/feature")?.delete()?;
+    origin_repo.find_reference("refs/tags/v1")?.delete()?;
+    let output = work_dir.run_jj(["git", "fetch", "--branch=*", "--tag=*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      wuosmmql fa6b3f48 feature
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    v1: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_git_fetch_conflicting_bookmarks() {
     let test_env = TestEnvironment::default();
@@ -816,53 +1299,190 @@ fn test_git_fetch_tags_by_name() -> TestResult {
     [EOF]
     ");
 
-    // Fetch tag1
-    let output = work_dir.run_jj(["git", "fetch", "--tag=tag1"]);
-    insta::assert_snapshot!(output, @"
-    ------- stderr -------
-    tag: tag1@origin [new] 
-    [EOF]
-    ");
+    // Fetch tag1
+    let output = work_dir.run_jj(["git", "fetch", "--tag=tag1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: tag1@origin [new] 
+    [EOF]
+    ");
+
+    // Fetch other tags
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: tag2@origin [new] 
+    tag: tag3@origin [new] 
+    [EOF]
+    ");
+
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    tag1: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    tag2: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    tag3: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Move and delete tags at remote
+    let commit2_oid = add_commit_to_branch(&origin_git_repo, "origin", "commit 2");
+    let constraint = gix::refs::transaction::PreviousValue::MustExistAndMatch(commit1_oid.into());
+    origin_git_repo.tag_reference("tag1", commit2_oid, constraint)?;
+    origin_git_repo.find_reference("refs/tags/tag2")?.delete()?;
+
+    // Fetch tag changes
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*", "--force-tags"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: tag1@origin [updated] 
+    tag: tag2@origin [deleted] 
+    [EOF]
+    ");
+
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    tag1: vqswlzks a439bb0e (empty) commit 2
+      @origin: vqswlzks a439bb0e (empty) commit 2
+    tag3: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_import_tags() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let origin_git_repo = add_git_remote(&test_env, &work_dir, "origin");
+    let commit_oid = origin_git_repo
+        .find_reference("refs/heads/origin")?
+        .id()
+        .detach();
+    for name in ["nightly", "v1.0", "v1.1", "v2.0"] {
+        let constraint = gix::refs::transaction::PreviousValue::MustNotExist;
+        origin_git_repo.tag_reference(name, commit_oid, constraint)?;
+    }
+
+    // Empty pattern imports no tags
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*", "--import-tags="]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*", "--import-tags=v1.*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: v1.0@origin [new] 
+    tag: v1.1@origin [new] 
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    v1.0: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    v1.1: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Tags already imported are kept even if they don't match
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*", "--import-tags=v2.*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: v2.0@origin [new] 
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    v1.0: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    v1.1: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    v2.0: qmyrypzk ab8b299e message
+      @origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_only_new_tags() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let origin_git_repo = add_git_remote(&test_env, &work_dir, "origin");
+    let commit1_oid = origin_git_repo
+        .find_reference("refs/heads/origin")?
+        .id()
+        .detach();
+    for name in ["tag1", "tag2"] {
+        let constraint = gix::refs::transaction::PreviousValue::MustNotExist;
+        origin_git_repo.tag_reference(name, commit1_oid, constraint)?;
+    }
+    work_dir.run_jj(["git", "fetch", "--tag=*"]).success();
+
+    // Move and delete tags at remote, and add a new one
+    let commit2_oid = add_commit_to_branch(&origin_git_repo, "origin", "commit 2");
+    let constraint = gix::refs::transaction::PreviousValue::MustExistAndMatch(commit1_oid.into());
+    origin_git_repo.tag_reference("tag1", commit2_oid, constraint)?;
+    origin_git_repo.find_reference("refs/tags/tag2")?.delete()?;
+    let constraint = gix::refs::transaction::PreviousValue::MustNotExist;
+    origin_git_repo.tag_reference("tag3", commit2_oid, constraint)?;
 
-    // Fetch other tags
+    // Existing local tags aren't moved by default
     let output = work_dir.run_jj(["git", "fetch", "--tag=*"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    tag: tag2@origin [new] 
+    tag: tag1@origin [updated] 
+    tag: tag2@origin [deleted] 
     tag: tag3@origin [new] 
+    Warning: Local tags were not updated to match the remote tags: tag1@origin, tag2@origin
+    Hint: Use `jj tag set --allow-move` or `jj tag delete` to update the local tags.
     [EOF]
     ");
-
     insta::assert_snapshot!(get_tag_output(&work_dir), @"
     tag1: qmyrypzk ab8b299e message
-      @origin: qmyrypzk ab8b299e message
+      @origin (ahead by 1 commits): vqswlzks a439bb0e (empty) commit 2
     tag2: qmyrypzk ab8b299e message
-      @origin: qmyrypzk ab8b299e message
-    tag3: qmyrypzk ab8b299e message
-      @origin: qmyrypzk ab8b299e message
+      @origin (not created yet)
+    tag3: vqswlzks a439bb0e (empty) commit 2
+      @origin: vqswlzks a439bb0e (empty) commit 2
     [EOF]
     ");
 
-    // Move and delete tags at remote
-    let commit2_oid = add_commit_to_branch(&origin_git_repo, "origin", "commit 2");
-    let constraint = gix::refs::transaction::PreviousValue::MustExistAndMatch(commit1_oid.into());
-    origin_git_repo.tag_reference("tag1", commit2_oid, constraint)?;
-    origin_git_repo.find_reference("refs/tags/tag2")?.delete()?;
+    // Moved tags can be updated explicitly
+    work_dir
+        .run_jj(["tag", "set", "--allow-move", "-r=tag1@origin", "tag1"])
+        .success();
+    work_dir.run_jj(["tag", "delete", "tag2"]).success();
+    insta::assert_snapshot!(get_tag_output(&work_dir), @"
+    tag1: vqswlzks a439bb0e (empty) commit 2
+      @origin: vqswlzks a439bb0e (empty) commit 2
+    tag3: vqswlzks a439bb0e (empty) commit 2
+      @origin: vqswlzks a439bb0e (empty) commit 2
+    [EOF]
+    ");
 
-    // Fetch tag changes
-    let output = work_dir.run_jj(["git", "fetch", "--tag=*"]);
+    // --force-tags moves the local tags along with the remote tags
+    let commit3_oid = add_commit_to_branch(&origin_git_repo, "origin", "commit 3");
+    let constraint = gix::refs::transaction::PreviousValue::MustExistAndMatch(commit2_oid.into());
+    origin_git_repo.tag_reference("tag3", commit3_oid, constraint)?;
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*", "--force-tags"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    tag: tag1@origin [updated] 
-    tag: tag2@origin [deleted] 
+    tag: tag3@origin [updated] 
     [EOF]
     ");
-
     insta::assert_snapshot!(get_tag_output(&work_dir), @"
     tag1: vqswlzks a439bb0e (empty) commit 2
       @origin: vqswlzks a439bb0e (empty) commit 2
-    tag3: qmyrypzk ab8b299e message
-      @origin: qmyrypzk ab8b299e message
+    tag3: zzpxsqvw 0086a36f (empty) commit 3
+      @origin: zzpxsqvw 0086a36f (empty) commit 3
     [EOF]
     ");
     Ok(())
@@ -2423,7 +3043,9 @@ fn test_git_fetch_tracked() {
 
     // Fetch with --tracked should only update main and tag1 (which are still
     // tracked)
-    work_dir.run_jj(["git", "fetch", "--tracked"]).success();
+    work_dir
+        .run_jj(["git", "fetch", "--tracked", "--force-tags"])
+        .success();
 
     // Main and tag1 should be updated to the new commit, but feature1 should
     // remain unchanged
@@ -2645,3 +3267,413 @@ fn test_git_fetch_auto_track_bookmarks() {
     [EOF]
     ");
 }
+
+#[test]
+fn test_git_fetch_auto_track_bookmarks_patterns() {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    test_env.add_config("remotes.origin.auto-track-bookmarks = 'release/* | main'");
+    root_dir.run_jj(["git", "init", "repo"]).success();
+    let repo_dir = test_env.work_dir("repo");
+    let git_repo = add_git_remote(&test_env, &repo_dir, "origin");
+    for branch in ["main", "release/1.0", "release/2.0", "feature", "mainline"] {
+        add_commit_to_branch(&git_repo, branch, branch);
+    }
+
+    // Only matching bookmarks get local bookmarks, others are imported as
+    // untracked remote bookmarks.
+    let output = repo_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@origin     [new] untracked
+    bookmark: main@origin        [new] tracked
+    bookmark: mainline@origin    [new] untracked
+    bookmark: origin@origin      [new] untracked
+    bookmark: release/1.0@origin [new] tracked
+    bookmark: release/2.0@origin [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    feature@origin: wuosmmql fa6b3f48 feature
+    main: wlltxvop a437242b main
+      @origin: wlltxvop a437242b main
+    mainline@origin: wzytkztl 87366a80 mainline
+    origin@origin: qmyrypzk ab8b299e message
+    release/1.0: qklrlzyq f2427fc4 release/1.0
+      @origin: qklrlzyq f2427fc4 release/1.0
+    release/2.0: pyytnqpt 0652a70a release/2.0
+      @origin: pyytnqpt 0652a70a release/2.0
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_fetch_auto_local_bookmark_flags() {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    root_dir.run_jj(["git", "init", "repo"]).success();
+    let repo_dir = test_env.work_dir("repo");
+    let git_repo = add_git_remote(&test_env, &repo_dir, "origin");
+    add_commit_to_branch(&git_repo, "feature1", "message");
+
+    // The flag overrides the config
+    let output = repo_dir.run_jj(["git", "fetch", "--no-auto-local-bookmark"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature1@origin [new] untracked
+    bookmark: origin@origin   [new] untracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    feature1@origin: txqvqkwm fc8f3f42 message
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Only newly fetched bookmarks are tracked
+    add_commit_to_branch(&git_repo, "feature2", "message");
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '~*'");
+    let output = repo_dir.run_jj(["git", "fetch", "--auto-local-bookmark"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature2@origin [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    feature1@origin: txqvqkwm fc8f3f42 message
+    feature2: wwprwxpm cbd7ca34 message
+      @origin: wwprwxpm cbd7ca34 message
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_fetch_prunes_deleted_remote_bookmarks() {
+    let test_env = TestEnvironment::default();
+    let root_dir = test_env.work_dir("");
+    root_dir.run_jj(["git", "init", "repo"]).success();
+    let repo_dir = test_env.work_dir("repo");
+    let git_repo = add_git_remote(&test_env, &repo_dir, "origin");
+    add_commit_to_branch(&git_repo, "feature1", "message");
+    add_commit_to_branch(&git_repo, "feature2", "message");
+    repo_dir.run_jj(["git", "fetch"]).success();
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    feature1@origin: txqvqkwm fc8f3f42 message
+    feature2@origin: wwprwxpm cbd7ca34 message
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Delete the branches on the remote
+    for name in ["refs/heads/feature1", "refs/heads/feature2"] {
+        git_repo.find_reference(name).unwrap().delete().unwrap();
+    }
+
+    // Only bookmarks matching the given pattern are pruned
+    let output = repo_dir.run_jj(["git", "fetch", "--branch=feature1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature1@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      txqvqkwm fc8f3f42 message
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    feature2@origin: wwprwxpm cbd7ca34 message
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+
+    // Untracked remote bookmarks are pruned regardless of auto-tracking
+    let output = repo_dir.run_jj(["git", "fetch"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature2@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable:
+      wwprwxpm cbd7ca34 message
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&repo_dir), @"
+    origin@origin: qmyrypzk ab8b299e message
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_fetch_import_missing_parents() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_bare(test_env.env_root().join("origin"));
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../origin"])
+        .success();
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    work_dir
+        .run_jj(["bookmark", "track", "b", "--remote=origin"])
+        .success();
+    work_dir.run_jj(["git", "push", "-b", "b"]).success();
+    work_dir.run_jj(["new", "b"]).success();
+
+    let output = work_dir.run_jj(["git", "fetch", "--import-missing-parents"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No missing parent commits found.
+    [EOF]
+    ");
+
+    // Delete the object of the parent commit
+    let commit_id = work_dir
+        .run_jj(["log", "-r", "a", "-T", "commit_id", "--no-graph"])
+        .success()
+        .stdout
+        .into_raw();
+    let (dir_name, file_name) = commit_id.split_at(2);
+    std::fs::remove_file(
+        work_dir
+            .root()
+            .join(".jj/repo/store/git/objects")
+            .join(dir_name)
+            .join(file_name),
+    )?;
+
+    let output = work_dir.run_jj(["git", "fetch", "--import-missing-parents"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Fetched 1 missing parent commits from remote origin
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  dc23b130508d ""
+    ○  c56ecbc62213 "b" b
+    ○  9a0b4679cff3 "a" a
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+    let output = work_dir.run_jj(["git", "fetch", "--import-missing-parents"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    No missing parent commits found.
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_fetch_depth() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    let git_repo = git::init(test_env.env_root().join("origin"));
+    for message in ["first", "second", "third", "fourth"] {
+        add_commit_to_branch(&git_repo, "main", message);
+    }
+    test_env
+        .run_jj_in(".", ["git", "clone", "--depth=1", "origin", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ◆  e9e850f8b6d3 "fourth" main
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+
+    // Deepen the history by one commit
+    let output = work_dir.run_jj(["git", "fetch", "--depth=1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ◆  e9e850f8b6d3 "fourth" main
+    │ ◆  e97e31eab86c "third"
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+
+    // Deepening past the root commit makes the repo full
+    let output = work_dir.run_jj(["git", "fetch", "--depth=10"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    The repository is no longer shallow.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ◆  e9e850f8b6d3 "fourth" main
+    │ ◆  e97e31eab86c "third"
+    │ ◆  2055d9422a3c "second"
+    │ ◆  19d310c31554 "first"
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["git", "fetch", "--depth=1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: --depth requires a shallow repository
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_fetch_unshallow() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    let git_repo = git::init(test_env.env_root().join("origin"));
+    for message in ["first", "second", "third"] {
+        add_commit_to_branch(&git_repo, "main", message);
+    }
+    test_env
+        .run_jj_in(".", ["git", "clone", "--depth=1", "origin", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["git", "fetch", "--unshallow"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    The repository is no longer shallow.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @r#"
+    @  e8849ae12c70 ""
+    │ ◆  e97e31eab86c "third" main
+    │ ◆  2055d9422a3c "second"
+    │ ◆  19d310c31554 "first"
+    ├─╯
+    ◆  000000000000 ""
+    [EOF]
+    "#);
+
+    let output = work_dir.run_jj(["git", "fetch", "--unshallow"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: --unshallow requires a shallow repository
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_fetch_verify_tag_signatures() -> TestResult {
+    if !is_external_tool_installed("gpg") {
+        ensure_running_outside_ci("`gpg` must be in the PATH");
+        eprintln!("Skipping test because gpg is not installed on the system");
+        return Ok(());
+    }
+    let mut test_env = TestEnvironment::default();
+    test_env.add_config("git.verify-tag-signatures = true");
+
+    // The keyring used by jj knows the "known" key, but not the "unknown" one
+    let create_gpg_home = |name: &str| {
+        let dir = test_env.env_root().join(name);
+        std::fs::create_dir(&dir).unwrap();
+        #[cfg(unix)]
+        std::fs::set_permissions(&dir, std::os::unix::fs::PermissionsExt::from_mode(0o700))
+            .unwrap();
+        let status = Command::new("gpg")
+            .arg("--homedir")
+            .arg(&dir)
+            .args(["--batch", "--passphrase", "", "--quick-gen-key"])
+            .arg(format!("{name} <{name}@example.com>"))
+            .args(["ed25519", "sign", "never"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        dir
+    };
+    let known_gpg_home = create_gpg_home("known");
+    let unknown_gpg_home = create_gpg_home("unknown");
+    test_env.add_env_var("GNUPGHOME", &known_gpg_home);
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let origin_git_repo = add_git_remote(&test_env, &work_dir, "origin");
+    let commit_oid = origin_git_repo
+        .find_reference("refs/heads/origin")?
+        .id()
+        .detach();
+    let create_signed_tag = |name: &str, gpg_home: &Path| -> TestResult {
+        let payload = format!(
+            "object {commit_oid}\ntype commit\ntag {name}\ntagger Someone <someone@example.com> \
+             0 +0000\n\nRelease {name}\n"
+        );
+        let mut gpg = Command::new("gpg")
+            .arg("--homedir")
+            .arg(gpg_home)
+            .args(["--batch", "--detach-sign", "--armor"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        gpg.stdin.take().unwrap().write_all(payload.as_bytes())?;
+        let output = gpg.wait_with_output()?;
+        assert!(output.status.success());
+        let data = [payload.as_bytes(), &output.stdout].concat();
+        let tag_oid = origin_git_repo
+            .write_buf(gix::object::Kind::Tag, &data)
+            .unwrap();
+        origin_git_repo.reference(
+            format!("refs/tags/{name}"),
+            tag_oid,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "",
+        )?;
+        Ok(())
+    };
+    create_signed_tag("v1", &known_gpg_home)?;
+    create_signed_tag("v2", &unknown_gpg_home)?;
+    origin_git_repo.tag_reference(
+        "v3",
+        commit_oid,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+    )?;
+
+    // A tag signed by an unknown key isn't reported as a bad signature
+    let output = work_dir.run_jj(["git", "fetch", "--tag=*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: v1@origin [new] 
+    tag: v2@origin [new] 
+    tag: v3@origin [new] 
+    Good signature on tag v1@origin by known <known@example.com>
+    Tag v2@origin is signed by an unknown key
+    [EOF]
+    ");
+
+    let template = r#"separate("@", name, remote) ++ ": " ++ self.signature_verified() ++ "\n""#;
+    let output = work_dir.run_jj(["tag", "list", "--all-remotes", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    v1: true
+    v1@origin: true
+    v2: false
+    v2@origin: false
+    v3: false
+    v3@origin: false
+    [EOF]
+    ");
+
+    // Bookmarks have no tag signature
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    main: <Error: Not a tag>
+    [EOF]
+    ");
+    Ok(())
+}
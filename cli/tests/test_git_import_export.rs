@@ -93,6 +93,823 @@ fn test_git_export_conflicting_git_refs() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_git_export_atomic() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo/bar"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--atomic"]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Warning: Failed to export some bookmarks:
+      foo/bar@git: Failed to set: Conflicts with existing Git ref refs/heads/foo
+    Hint: Git doesn't allow a branch/tag name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks/tags that failed
+    to export or their "parent" bookmarks/tags.
+    Error: Failed to export some refs atomically, no refs were exported
+    [EOF]
+    [exit status: 1]
+    "#);
+    // Neither ref was written
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @"[]");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
+    foo/bar: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Once the conflict is resolved, all refs are exported
+    work_dir
+        .run_jj(["bookmark", "rename", "foo/bar", "foo-bar"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--atomic"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/foo",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+        (
+            "refs/heads/foo-bar",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    foo-bar: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_rename_conflicting() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "foo-1"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo/bar"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--rename-conflicting"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Renamed bookmark foo to foo-2 to avoid conflict with foo/bar
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/foo-1",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+        (
+            "refs/heads/foo-2",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+        (
+            "refs/heads/foo/bar",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo-1: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    foo-2: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    foo/bar: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_rename_conflicting_untracks_remote() -> TestResult {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("origin"));
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../origin"])
+        .success();
+    work_dir.run_jj(["describe", "-mfoo"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "track", "foo", "--remote=origin"])
+        .success();
+    work_dir.run_jj(["git", "push", "--bookmark=foo"]).success();
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo/bar"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--rename-conflicting"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Renamed bookmark foo to foo-1 to avoid conflict with foo/bar
+    [EOF]
+    ");
+
+    // The remote bookmark of the renamed bookmark is untracked, so the next
+    // push won't delete it
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo@origin: qpvuntsm a515d574 (empty) foo
+    foo-1: qpvuntsm a515d574 (empty) foo
+      @git: qpvuntsm a515d574 (empty) foo
+    foo/bar: qpvuntsm a515d574 (empty) foo
+      @git: qpvuntsm a515d574 (empty) foo
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "push", "--deleted", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_only() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main", "release/1", "other"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--only=main", "--only=release/*"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/main",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+        (
+            "refs/heads/release/1",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    main: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    other: qpvuntsm e8849ae1 (empty) (no description set)
+    release/1: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+
+    // Deletion of a matching bookmark is exported, other bookmarks are left
+    // unexported
+    work_dir
+        .run_jj(["bookmark", "delete", "release/1"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--only=release/*", "--only=nothing*"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: No matching bookmarks for patterns: nothing*
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/main",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+    Ok(())
+}
+
+#[test]
+fn test_git_import_export_octopus_merge_with_mergetag() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    let empty_tree_id = git_repo.empty_tree().id;
+    let parent_ids = ["p1", "p2", "p3"].map(|name| {
+        git::write_commit(
+            &git_repo,
+            &format!("refs/heads/{name}"),
+            empty_tree_id,
+            name,
+            &[],
+        )
+    });
+    let mergetags = [(1, "v1"), (2, "v2")].map(|(index, tag)| {
+        format!(
+            "object {}\ntype commit\ntag {tag}\ntagger Someone <someone@example.com> 0 \
+             +0000\n\n{tag}",
+            parent_ids[index]
+        )
+    });
+    let signature = gix::actor::Signature {
+        name: "Someone".into(),
+        email: "someone@example.com".into(),
+        time: gix::date::Time::new(0, 0),
+    };
+    let octopus = gix::objs::Commit {
+        tree: empty_tree_id,
+        parents: parent_ids.into_iter().collect(),
+        author: signature.clone(),
+        committer: signature,
+        encoding: None,
+        message: "octopus\n".into(),
+        extra_headers: mergetags
+            .into_iter()
+            .map(|mergetag| ("mergetag".into(), mergetag.into()))
+            .collect(),
+    };
+    let octopus_id = git_repo.write_object(&octopus).unwrap().detach();
+    git_repo
+        .reference(
+            "refs/heads/octopus",
+            octopus_id,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "",
+        )
+        .unwrap();
+
+    work_dir.run_jj(["git", "import"]).success();
+    let output = work_dir.run_jj([
+        "log",
+        "--no-graph",
+        "-roctopus",
+        r#"-Tself.git_headers('mergetag').join("\n---\n")"#,
+    ]);
+    insta::assert_snapshot!(output, @"
+    object d6d9b1814ad5f95db4352b9455448fc9252ad01f
+    type commit
+    tag v1
+    tagger Someone <someone@example.com> 0 +0000
+
+    v1
+
+    ---
+    object f6230acdbc20d49645058179ad8dd811f0798277
+    type commit
+    tag v2
+    tagger Someone <someone@example.com> 0 +0000
+
+    v2
+    [EOF]
+    ");
+
+    // Re-exporting the imported commit reproduces the same Git object
+    work_dir
+        .run_jj(["bookmark", "rename", "octopus", "merged"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    let merged_id = git_repo.find_reference("refs/heads/merged")?.id().detach();
+    assert_eq!(merged_id, octopus_id);
+    let object = git_repo.find_object(merged_id)?;
+    let commit = object.try_to_commit_ref()?;
+    assert_eq!(commit.parents().count(), 3);
+    assert_eq!(commit.extra_headers().find_all("mergetag").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_git_export_bookmark_forget_keep_git_ref() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "bar"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+
+    let output = work_dir.run_jj(["bookmark", "forget", "--keep-git-ref", "foo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Forgot 1 local bookmarks.
+    [EOF]
+    ");
+    work_dir.run_jj(["bookmark", "forget", "bar"]).success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    bar (deleted)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ------- stderr -------
+    Hint: Bookmarks marked as deleted will be deleted from the underlying Git repo on the next `jj git export`.
+    [EOF]
+    ");
+
+    // Only the Git branch of the bookmark forgotten without --keep-git-ref is
+    // deleted
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/foo",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+
+    // The kept Git branch is imported again as a new bookmark
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: foo@git [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_attach_head() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+
+    // HEAD isn't touched by default
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(get_git_head(&git_repo), @"ref: refs/heads/master");
+
+    let output = work_dir.run_jj(["git", "export", "--config=git.export.attach-head=true"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Attached Git HEAD to bookmark foo.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_git_head(&git_repo), @"ref: refs/heads/foo");
+    // The view's Git HEAD was updated, so nothing is imported
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    // HEAD isn't attached if multiple bookmarks point to @
+    work_dir.run_jj(["new", "-mbar"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "bar", "baz"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--config=git.export.attach-head=true"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(get_git_head(&git_repo), @"ref: refs/heads/foo");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_dry_run() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a", "b"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    work_dir.run_jj(["new"]).success();
+    work_dir.run_jj(["bookmark", "set", "-r@", "a"]).success();
+    work_dir.run_jj(["bookmark", "delete", "b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main", "main/sub"])
+        .success();
+
+    // Nothing is written by dry-run
+    let output = work_dir.run_jj(["git", "export", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    would delete refs/heads/b: e8849ae12c70
+    would update refs/heads/a: e8849ae12c70 -> 0e555a27ac99
+    would create refs/heads/main: 0e555a27ac99
+    would fail: main/sub@git (Failed to set: Conflicts with existing Git ref refs/heads/main)
+    Dry-run requested, not exporting.
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/a",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+        (
+            "refs/heads/b",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+
+    // The real export matches the dry-run output
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::with_settings!({filters => vec![("Failed to set: .*", "Failed to set: ...")]}, {
+        insta::assert_snapshot!(output, @r#"
+        ------- stderr -------
+        Warning: Failed to export some bookmarks:
+          main/sub@git: Failed to set: ...
+        Hint: Git doesn't allow a branch/tag name that looks like a parent directory of
+        another (e.g. `foo` and `foo/bar`). Try to rename the bookmarks/tags that failed
+        to export or their "parent" bookmarks/tags.
+        [EOF]
+        "#);
+    });
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/a",
+            CommitId(
+                "0e555a27ac99122bec2edb664843c1a7c27db5bb",
+            ),
+        ),
+        (
+            "refs/heads/main",
+            CommitId(
+                "0e555a27ac99122bec2edb664843c1a7c27db5bb",
+            ),
+        ),
+    ]
+    "#);
+
+    // Nothing left to export except the failed ref
+    let output = work_dir.run_jj(["git", "export", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    would fail: main/sub@git (Failed to set: Conflicts with existing Git ref refs/heads/main)
+    Dry-run requested, not exporting.
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_export_dry_run_json() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a", "b"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    work_dir.run_jj(["new"]).success();
+    work_dir.run_jj(["bookmark", "set", "-r@", "a"]).success();
+    work_dir.run_jj(["bookmark", "delete", "b"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main", "main/sub"])
+        .success();
+
+    let output = work_dir.run_jj(["git", "export", "--dry-run", "--format=json"]);
+    insta::assert_snapshot!(output, @r#"
+    {
+      "detached_head_refs": [],
+      "ref_changes": [
+        {
+          "name": "refs/heads/b",
+          "action": "delete",
+          "old_target": "e8849ae12c709f2321908879bc724fdb2ab8a781",
+          "new_target": null
+        },
+        {
+          "name": "refs/heads/a",
+          "action": "update",
+          "old_target": "e8849ae12c709f2321908879bc724fdb2ab8a781",
+          "new_target": "0e555a27ac99122bec2edb664843c1a7c27db5bb"
+        },
+        {
+          "name": "refs/heads/main",
+          "action": "create",
+          "old_target": null,
+          "new_target": "0e555a27ac99122bec2edb664843c1a7c27db5bb"
+        }
+      ],
+      "failed_refs": [
+        {
+          "kind": "bookmark",
+          "name": "main/sub",
+          "remote": "git",
+          "reason": "Failed to set: Conflicts with existing Git ref refs/heads/main"
+        }
+      ]
+    }
+    [EOF]
+    ------- stderr -------
+    Dry-run requested, not exporting.
+    [EOF]
+    "#);
+
+    // The plan format requires --dry-run
+    let output = work_dir.run_jj(["git", "export", "--format=json"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      --dry-run
+
+    Usage: jj git export --dry-run --format <FORMAT>
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
+#[test]
+fn test_git_export_to_repo() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    let target_repo = git::init_bare(test_env.env_root().join("target"));
+
+    work_dir.write_file("file", "contents\n");
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main", "other"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--to-repo=../target", "--only=main"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&target_repo), @r#"
+    [
+        (
+            "refs/heads/main",
+            CommitId(
+                "bf3e295481bdc6e88d780608eac291c007add696",
+            ),
+        ),
+    ]
+    "#);
+    let main_commit = target_repo.find_reference("main")?.peel_to_commit()?;
+    assert_eq!(read_git_file(&main_commit, "file"), b"contents\n");
+
+    // The underlying Git repo isn't touched
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @"[]");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    main: qpvuntsm bf3e2954 first
+    other: qpvuntsm bf3e2954 first
+    [EOF]
+    ");
+
+    // Existing branches are overwritten, and only the new objects are copied
+    work_dir.write_file("file", "updated\n");
+    work_dir.run_jj(["commit", "-m", "second"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r@-", "main"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--to-repo=../target"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&target_repo), @r#"
+    [
+        (
+            "refs/heads/main",
+            CommitId(
+                "93959aa37cc6f72c248c0b4b2587a571baa1f53a",
+            ),
+        ),
+        (
+            "refs/heads/other",
+            CommitId(
+                "bf3e295481bdc6e88d780608eac291c007add696",
+            ),
+        ),
+    ]
+    "#);
+    let main_commit = target_repo.find_reference("main")?.peel_to_commit()?;
+    assert_eq!(read_git_file(&main_commit, "file"), b"updated\n");
+    let parent_id = main_commit.parent_ids().next().unwrap();
+    let parent_commit = target_repo.find_commit(parent_id)?;
+    assert_eq!(read_git_file(&parent_commit, "file"), b"contents\n");
+
+    // Conflicted bookmarks are reported
+    work_dir.run_jj(["new", "root()", "-m", "side"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "conflicted"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-rmain", "conflicted", "--at-op=@-"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--to-repo=../target"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Concurrent modification detected, resolving automatically.
+    Warning: Failed to export some bookmarks:
+      conflicted@git: Ref is conflicted
+    [EOF]
+    ");
+    assert!(target_repo.try_find_reference("conflicted")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_git_export_to_repo_object_hash_mismatch() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    git::init_with_object_hash(test_env.env_root().join("target"), gix::hash::Kind::Sha256);
+
+    work_dir.run_jj(["commit", "-m", "first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    let output = work_dir.run_jj(["git", "export", "--to-repo=../target"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Failed to export refs to underlying Git repo
+    Caused by: The Git repository at $TEST_ENV/repo/../target uses sha256 object hashes, but the source repository uses sha1
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_export_change_id_trailer() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.write-change-id-trailer = true");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    let get_git_message = |name: &str| -> TestResult<String> {
+        let commit = git_repo.find_reference(name)?.peel_to_commit()?;
+        Ok(commit.message_raw()?.to_string())
+    };
+
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["describe", "-m=first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    insta::assert_snapshot!(get_git_message("main")?, @"
+    first
+
+    Change-Id: I9a45c67d3e96a7e5007c110ede34dec56a6a6964
+    ");
+
+    // Rewriting and re-exporting the commit doesn't duplicate the trailer
+    work_dir.write_file("file", "b\n");
+    work_dir.run_jj(["git", "export"]).success();
+    let message = get_git_message("main")?;
+    assert_eq!(message.matches("Change-Id:").count(), 1);
+
+    // The change id is preserved when the commit is imported
+    test_env
+        .run_jj_in(
+            ".",
+            [
+                "git",
+                "init",
+                "--git-repo=repo/.jj/repo/store/git",
+                "imported",
+            ],
+        )
+        .success();
+    let log_template = r#"change_id ++ "\n" ++ description"#;
+    let output = work_dir.run_jj(["log", "--no-graph", "-rmain", "-T", log_template]);
+    insta::assert_snapshot!(output, @"
+    qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu
+    first
+
+    Change-Id: I9a45c67d3e96a7e5007c110ede34dec56a6a6964
+    [EOF]
+    ");
+    let imported_dir = test_env.work_dir("imported");
+    let imported_output = imported_dir.run_jj(["log", "--no-graph", "-rmain", "-T", log_template]);
+    assert_eq!(imported_output, output);
+
+    // No trailer is added to conflicted commits
+    work_dir.run_jj(["new", "root()", "-m=other"]).success();
+    work_dir.write_file("file", "c\n");
+    work_dir.run_jj(["new", "main", "@", "-m=merge"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "conflicted"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    insta::assert_snapshot!(get_git_message("conflicted")?, @"merge");
+
+    // A trailer copied to another change is replaced
+    work_dir
+        .run_jj(["duplicate", "main", "--onto=root()"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-rsubject(first) ~ main", "copied"])
+        .success();
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Added Change-Id trailers to 1 commits
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_git_message("copied")?, @"
+    first
+
+    Change-Id: I39c3fb0af576c21accc6c1f3898f8a446a6a6964
+    ");
+
+    // A trailer set by the user is kept
+    work_dir
+        .run_jj([
+            "new",
+            "root()",
+            "-m=custom\n\nChange-Id: I0123456789012345678901234567890123456789",
+        ])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "custom"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    insta::assert_snapshot!(get_git_message("custom")?, @"
+    custom
+
+    Change-Id: I0123456789012345678901234567890123456789
+    ");
+
+    // Descendants of the rewritten commits are rebased
+    work_dir.run_jj(["new", "root()", "-m=base"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "base"])
+        .success();
+    work_dir.run_jj(["new", "-m=child"]).success();
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Added Change-Id trailers to 1 commits
+    Rebased 1 descendant commits
+    Working copy  (@) now at: tlkvzzqu 82e9e9ed (empty) child
+    Parent commit (@-)      : xtnwkqum 1974254f base | (empty) base
+    [EOF]
+    ");
+
+    // Immutable commits are left alone
+    work_dir.run_jj(["new", "root()", "-m=frozen"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "frozen"])
+        .success();
+    let output = work_dir.run_jj([
+        "git",
+        "export",
+        "--config=revset-aliases.'immutable_heads()'='frozen'",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Didn't add Change-Id trailers to 1 immutable commits
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_git_message("frozen")?, @"frozen");
+    Ok(())
+}
+
 #[test]
 fn test_git_export_undo() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -100,121 +917,550 @@ fn test_git_export_undo() -> TestResult {
     let work_dir = test_env.work_dir("repo");
     let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
 
-    work_dir
-        .run_jj(["bookmark", "create", "-r@", "a"])
-        .success();
-    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
-    a: qpvuntsm e8849ae1 (empty) (no description set)
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "a"])
+        .success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    a: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 a e8849ae1
+    │  (empty) (no description set)
+    ~
+    [EOF]
+    ");
+
+    // Exported refs won't be removed by undoing the export, but the git-tracking
+    // bookmark is. This is the same as remote-tracking bookmarks.
+    let output = work_dir.run_jj(["undo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Undid operation: 563b0274c404 (2001-02-03 08:05:10) export git refs
+    Restored to operation: a8cc177d3fa6 (2001-02-03 08:05:08) create bookmark a pointing to commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    [EOF]
+    ");
+    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
+    [
+        (
+            "refs/heads/a",
+            CommitId(
+                "e8849ae12c709f2321908879bc724fdb2ab8a781",
+            ),
+        ),
+    ]
+    "#);
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
+    ------- stderr -------
+    Error: Revision `a@git` doesn't exist
+    Hint: Did you mean `a`?
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // This would re-export bookmark "a" and create git-tracking bookmark.
+    let output = work_dir.run_jj(["git", "export"]);
+    insta::assert_snapshot!(output, @"");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 a e8849ae1
+    │  (empty) (no description set)
+    ~
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_import_undo() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    // Create bookmark "a" in git repo
+    let commit_id = work_dir
+        .run_jj(&["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .success()
+        .stdout
+        .into_raw();
+    let commit_id = gix::ObjectId::from_hex(commit_id.as_bytes())?;
+    git_repo.reference(
+        "refs/heads/a",
+        commit_id,
+        gix::refs::transaction::PreviousValue::Any,
+        "",
+    )?;
+
+    // Initial state we will return to after `undo`. There are no bookmarks.
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
+    let base_operation_id = work_dir.current_operation_id();
+
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: a@git [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    a: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+
+    // "git import" can be undone by default.
+    let output = work_dir.run_jj(["op", "restore", &base_operation_id]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Restored to operation: 90267f31f904 (2001-02-03 08:05:07) add workspace 'default'
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
+    // Try "git import" again, which should re-import the bookmark "a".
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: a@git [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    a: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_import_changed_refs_summary() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir.run_jj(["describe", "-mfirst"]).success();
+    work_dir.run_jj(["new", "-msecond"]).success();
+    let get_commit_id = |rev: &str| -> TestResult<gix::ObjectId> {
+        let output = work_dir
+            .run_jj(&["log", "-Tcommit_id", "--no-graph", "-r", rev])
+            .success();
+        Ok(gix::ObjectId::from_hex(output.stdout.raw().as_bytes())?)
+    };
+    let first_id = get_commit_id("@-")?;
+    let second_id = get_commit_id("@")?;
+    let set_ref = |name: &str, id: gix::ObjectId| -> TestResult {
+        git_repo.reference(name, id, gix::refs::transaction::PreviousValue::Any, "")?;
+        Ok(())
+    };
+
+    for name in ["refs/heads/main", "refs/heads/stale", "refs/tags/v0.9"] {
+        set_ref(name, first_id)?;
+    }
+    work_dir.run_jj(["git", "import"]).success();
+
+    // Unchanged refs (v0.9) aren't printed
+    set_ref("refs/heads/main", second_id)?;
+    set_ref("refs/heads/feature", second_id)?;
+    set_ref("refs/tags/v1.0", second_id)?;
+    git_repo.find_reference("refs/heads/stale")?.delete()?;
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: feature@git [new] tracked
+    bookmark: main@git    [updated] 68a505386f93 -> b1cb6b2f9141 tracked
+    bookmark: stale@git   [deleted] untracked
+    tag: v1.0@git [new] 
+    [EOF]
+    ");
+
+    // Nothing is printed with --quiet
+    set_ref("refs/heads/main", first_id)?;
+    let output = work_dir.run_jj(["git", "import", "--quiet"]);
+    insta::assert_snapshot!(output, @"");
+    Ok(())
+}
+
+#[test]
+fn test_git_import_invalid_ref_names() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo_path = work_dir.root().join(".jj/repo/store/git");
+    let git_repo = git::open(&git_repo_path);
+
+    let commit_id = work_dir
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .success()
+        .stdout
+        .into_raw();
+    git_repo.reference(
+        "refs/heads/main",
+        gix::ObjectId::from_hex(commit_id.as_bytes())?,
+        gix::refs::transaction::PreviousValue::Any,
+        "",
+    )?;
+    // Write loose refs that Git considers invalid directly
+    for name in ["refs/heads/double..dot", "refs/tags/a@{b"] {
+        std::fs::write(git_repo_path.join(name), format!("{commit_id}\n"))?;
+    }
+
+    // Invalid refs are reported, and the valid ref is still imported
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: main@git [new] tracked
+    Warning: Failed to import some Git refs:
+      refs/heads/double..dot
+      refs/tags/a@{b
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    main: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_import_keep_colliding_bookmarks() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    let commit_id = work_dir
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .success()
+        .stdout
+        .into_raw();
+    let commit_id = gix::ObjectId::from_hex(commit_id.as_bytes())?;
+    for name in ["refs/heads/foo", "refs/remotes/origin/foo/bar"] {
+        git_repo.reference(
+            name,
+            commit_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "",
+        )?;
+    }
+
+    // Colliding bookmarks are imported as-is by default
+    let output = work_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: foo@git        [new] tracked
+    bookmark: foo/bar@origin [new] tracked
     [EOF]
     ");
-    let output = work_dir.run_jj(["git", "export"]);
-    insta::assert_snapshot!(output, @"");
-    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
-    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 a e8849ae1
-    │  (empty) (no description set)
-    ~
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    foo/bar: qpvuntsm e8849ae1 (empty) (no description set)
+      @origin: qpvuntsm e8849ae1 (empty) (no description set)
     [EOF]
     ");
+    Ok(())
+}
 
-    // Exported refs won't be removed by undoing the export, but the git-tracking
-    // bookmark is. This is the same as remote-tracking bookmarks.
-    let output = work_dir.run_jj(["undo"]);
+#[test]
+fn test_git_import_rename_colliding_bookmarks() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "bar/baz"])
+        .success();
+    let commit_id = work_dir
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .success()
+        .stdout
+        .into_raw();
+    let commit_id = gix::ObjectId::from_hex(commit_id.as_bytes())?;
+    for name in ["refs/heads/foo/bar", "refs/heads/bar", "refs/heads/qux"] {
+        git_repo.reference(
+            name,
+            commit_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "",
+        )?;
+    }
+
+    // Bookmarks colliding with existing bookmarks are renamed
+    let output = work_dir.run_jj(["git", "import", "--on-collision=rename"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    Undid operation: 563b0274c404 (2001-02-03 08:05:10) export git refs
-    Restored to operation: a8cc177d3fa6 (2001-02-03 08:05:08) create bookmark a pointing to commit e8849ae12c709f2321908879bc724fdb2ab8a781
+    bookmark: bar@git     [new] tracked
+    bookmark: foo/bar@git [new] tracked
+    bookmark: qux@git     [new] tracked
+    Renamed imported bookmark bar to bar-1 to avoid collision with bar/baz
+    Renamed imported bookmark foo/bar to foo-1/bar to avoid collision with foo
     [EOF]
     ");
-    insta::assert_debug_snapshot!(get_git_repo_refs(&git_repo), @r#"
-    [
-        (
-            "refs/heads/a",
-            CommitId(
-                "e8849ae12c709f2321908879bc724fdb2ab8a781",
-            ),
-        ),
-    ]
-    "#);
-    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    bar (deleted)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    bar-1: qpvuntsm e8849ae1 (empty) (no description set)
+    bar/baz: qpvuntsm e8849ae1 (empty) (no description set)
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
+    foo-1/bar: qpvuntsm e8849ae1 (empty) (no description set)
+    foo/bar (deleted)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    qux: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    [EOF]
     ------- stderr -------
-    Error: Revision `a@git` doesn't exist
-    Hint: Did you mean `a`?
+    Hint: Bookmarks marked as deleted will be deleted from the underlying Git repo on the next `jj git export`.
     [EOF]
-    [exit status: 1]
     ");
 
-    // This would re-export bookmark "a" and create git-tracking bookmark.
+    // The renamed bookmarks can be exported
     let output = work_dir.run_jj(["git", "export"]);
     insta::assert_snapshot!(output, @"");
-    insta::assert_snapshot!(work_dir.run_jj(["log", "-ra@git"]), @"
-    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 a e8849ae1
-    │  (empty) (no description set)
-    ~
-    [EOF]
-    ");
     Ok(())
 }
 
 #[test]
-fn test_git_import_undo() -> TestResult {
+fn test_git_import_skip_clashing_ref_names() -> TestResult {
     let test_env = TestEnvironment::default();
+    test_env.add_config("remotes.origin.auto-track-bookmarks = '*'");
     test_env.run_jj_in(".", ["git", "init", "repo"]).success();
     let work_dir = test_env.work_dir("repo");
     let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
 
-    // Create bookmark "a" in git repo
     let commit_id = work_dir
-        .run_jj(&["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-r@"])
         .success()
         .stdout
         .into_raw();
     let commit_id = gix::ObjectId::from_hex(commit_id.as_bytes())?;
-    git_repo.reference(
-        "refs/heads/a",
-        commit_id,
-        gix::refs::transaction::PreviousValue::Any,
-        "",
-    )?;
-
-    // Initial state we will return to after `undo`. There are no bookmarks.
-    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
-    let base_operation_id = work_dir.current_operation_id();
+    let set_ref = |name: &str| -> TestResult {
+        git_repo.reference(
+            name,
+            commit_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "",
+        )?;
+        Ok(())
+    };
+    set_ref("refs/heads/main")?;
+    work_dir.run_jj(["git", "import"]).success();
 
-    let output = work_dir.run_jj(["git", "import"]);
+    // With --on-collision=skip, tracked bookmarks which clash with the existing
+    // or other incoming bookmarks aren't imported
+    for name in [
+        "refs/heads/foo",
+        "refs/remotes/origin/foo/bar",
+        "refs/remotes/origin/main/sub",
+        "refs/remotes/origin/qux",
+    ] {
+        set_ref(name)?;
+    }
+    let output = work_dir.run_jj(["git", "import", "--on-collision=skip"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    bookmark: a@git [new] tracked
+    bookmark: foo@git    [new] tracked
+    bookmark: qux@origin [new] tracked
+    Warning: Failed to import some Git refs clashing with other bookmarks:
+      refs/remotes/origin/foo/bar
+      refs/remotes/origin/main/sub
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the Git refs that failed
+    to import or the clashing bookmarks.
     [EOF]
     ");
     insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
-    a: qpvuntsm e8849ae1 (empty) (no description set)
+    foo: qpvuntsm e8849ae1 (empty) (no description set)
       @git: qpvuntsm e8849ae1 (empty) (no description set)
+    main: qpvuntsm e8849ae1 (empty) (no description set)
+      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    qux: qpvuntsm e8849ae1 (empty) (no description set)
+      @origin: qpvuntsm e8849ae1 (empty) (no description set)
     [EOF]
     ");
 
-    // "git import" can be undone by default.
-    let output = work_dir.run_jj(["op", "restore", &base_operation_id]);
+    // The clash is reported again until it gets resolved
+    let output = work_dir.run_jj(["git", "import", "--on-collision=skip"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    Restored to operation: 90267f31f904 (2001-02-03 08:05:07) add workspace 'default'
+    Warning: Failed to import some Git refs clashing with other bookmarks:
+      refs/remotes/origin/foo/bar
+      refs/remotes/origin/main/sub
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the Git refs that failed
+    to import or the clashing bookmarks.
+    Nothing changed.
     [EOF]
     ");
-    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"");
-    // Try "git import" again, which should re-import the bookmark "a".
-    let output = work_dir.run_jj(["git", "import"]);
+    git_repo.find_reference("refs/heads/foo")?.delete()?;
+    let output = work_dir.run_jj(["git", "import", "--on-collision=skip"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
-    bookmark: a@git [new] tracked
+    bookmark: foo@git        [deleted] untracked
+    bookmark: foo/bar@origin [new] tracked
+    Warning: Failed to import some Git refs clashing with other bookmarks:
+      refs/remotes/origin/main/sub
+    Hint: Git doesn't allow a branch name that looks like a parent directory of
+    another (e.g. `foo` and `foo/bar`). Try to rename the Git refs that failed
+    to import or the clashing bookmarks.
     [EOF]
     ");
-    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
-    a: qpvuntsm e8849ae1 (empty) (no description set)
-      @git: qpvuntsm e8849ae1 (empty) (no description set)
+    Ok(())
+}
+
+#[test]
+fn test_git_import_onto_workspace() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "main"])
+        .success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.write_file("file", "contents");
+    main_dir.run_jj(["commit", "-m=first"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+    main_dir.write_file("file", "modified");
+    main_dir.run_jj(["commit", "-m=second"]).success();
+
+    // Register the secondary workspace as a Git worktree checked out at the
+    // "second" commit
+    let head_id = main_dir.run_jj(["log", "-r@-", "-T=commit_id", "--no-graph"]);
+    register_git_worktree(&main_dir, &secondary_dir, head_id.stdout.raw())?;
+
+    let template = r#"separate(" ", description.first_line(), working_copies)"#;
+    let output = main_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  default@
+    ○  second
+    │ ○  secondary@
+    ├─╯
+    ○  first
+    ◆
+    [EOF]
+    ");
+
+    // Only the working-copy commit of the targeted workspace is moved
+    let output = main_dir.run_jj(["git", "import", "--onto", "secondary"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Reset the working copy parent of workspace secondary to the new Git HEAD.
+    [EOF]
+    ");
+    let output = main_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  default@
+    │ ○  secondary@
+    ├─╯
+    ○  second
+    ○  first
+    ◆
+    [EOF]
+    ");
+
+    // Importing again is a no-op
+    let output = main_dir.run_jj(["git", "import", "--onto", "secondary"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    [EOF]
+    ");
+
+    let output = main_dir.run_jj(["git", "import", "--onto", "unknown"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No such workspace: unknown
+    [EOF]
+    [exit status: 1]
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_git_import_in_linked_worktree() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "main"])
+        .success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.write_file("file", "contents");
+    main_dir.run_jj(["commit", "-m=first"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+    main_dir.write_file("file", "modified");
+    main_dir.run_jj(["commit", "-m=second"]).success();
+
+    // Register the secondary workspace as a Git worktree checked out at the
+    // "second" commit, with a per-worktree ref
+    let head_id = main_dir.run_jj(["log", "-r@-", "-T=commit_id", "--no-graph"]);
+    let worktree_git_dir = register_git_worktree(&main_dir, &secondary_dir, head_id.stdout.raw())?;
+    std::fs::create_dir_all(worktree_git_dir.join("refs/worktree"))?;
+    std::fs::write(
+        worktree_git_dir.join("refs/worktree/foo"),
+        format!("{}\n", head_id.stdout.raw()),
+    )?;
+
+    // Importing in the linked worktree moves the working-copy commit of the
+    // workspace, not the one of the main worktree. The per-worktree ref isn't
+    // imported as a bookmark.
+    let output = secondary_dir.run_jj(["git", "import"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Reset the working copy parent of workspace secondary to the new Git HEAD.
+    Working copy  (@) now at: royxmykx a40364fc (empty) (no description set)
+    Parent commit (@-)      : rlvkpnrz 45c36ee6 second
+    Added 0 files, modified 1 files, removed 0 files
+    [EOF]
+    ");
+    let template = r#"separate(" ", description.first_line(), working_copies, bookmarks)"#;
+    let output = main_dir.run_jj(["log", "-T", template]);
+    insta::assert_snapshot!(output, @"
+    @  default@
+    │ ○  secondary@
+    ├─╯
+    ○  second
+    ○  first
+    ◆
     [EOF]
     ");
     Ok(())
 }
 
+/// Registers `worktree_dir` as a linked Git worktree of the colocated
+/// `main_dir`, checked out at `head`. Returns the Git dir of the worktree.
+fn register_git_worktree(
+    main_dir: &TestWorkDir,
+    worktree_dir: &TestWorkDir,
+    head: &str,
+) -> TestResult<std::path::PathBuf> {
+    let worktree_name = worktree_dir.root().file_name().unwrap();
+    let worktree_git_dir = main_dir
+        .root()
+        .join(".git")
+        .join("worktrees")
+        .join(worktree_name);
+    std::fs::create_dir_all(&worktree_git_dir)?;
+    std::fs::write(worktree_git_dir.join("HEAD"), head)?;
+    std::fs::write(worktree_git_dir.join("commondir"), "../..\n")?;
+    let dot_git_path = worktree_dir.root().join(".git");
+    std::fs::write(
+        worktree_git_dir.join("gitdir"),
+        format!("{}\n", dot_git_path.display()),
+    )?;
+    std::fs::write(
+        &dot_git_path,
+        format!("gitdir: {}\n", worktree_git_dir.display()),
+    )?;
+    Ok(worktree_git_dir)
+}
+
 #[test]
 fn test_git_import_move_export_with_default_undo() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -357,18 +1603,105 @@ fn test_git_import_export_stats_color() -> TestResult {
     insta::assert_snapshot!(output, @"
     ------- stderr -------
     bookmark: [38;5;5mbar@git[39m [new] tracked
-    bookmark: [38;5;5mfoo@git[39m [updated] tracked
+    bookmark: [38;5;5mfoo@git[39m [updated] [38;5;4me8849ae12c70[39m -> [38;5;4mc2934cfbfb19[39m tracked
     tag: [38;5;5mbaz@git[39m [new] 
     [EOF]
     ");
     Ok(())
 }
 
+#[test]
+fn test_git_import_ref_kind_filter() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+
+    work_dir.run_jj(["describe", "-m=foo"]).success();
+    let commit_id = work_dir
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-r@"])
+        .success()
+        .stdout
+        .into_raw();
+    let commit_id = gix::ObjectId::from_hex(commit_id.as_bytes())?;
+    for name in ["refs/heads/foo", "refs/tags/v1"] {
+        git_repo.reference(
+            name,
+            commit_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "",
+        )?;
+    }
+
+    // Only the branch is imported
+    let output = work_dir.run_jj(["git", "import", "--branches-only"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: foo@git [new] tracked
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm 92d03254 (empty) foo
+      @git: qpvuntsm 92d03254 (empty) foo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @"");
+
+    // Deleted branch isn't removed from the view when importing only tags
+    git_repo.find_reference("refs/heads/foo")?.delete()?;
+    let output = work_dir.run_jj(["git", "import", "--tags-only"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    tag: v1@git [new] 
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    foo: qpvuntsm 92d03254 (empty) foo
+      @git: qpvuntsm 92d03254 (empty) foo
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["tag", "list"]), @"
+    v1: qpvuntsm 92d03254 (empty) foo
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "import", "--branches-only", "--tags-only"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the argument '--branches-only' cannot be used with '--tags-only'
+
+    Usage: jj git import --branches-only
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    Ok(())
+}
+
 #[must_use]
 fn get_bookmark_output(work_dir: &TestWorkDir) -> CommandOutput {
     work_dir.run_jj(["bookmark", "list", "--all-remotes"])
 }
 
+fn get_git_head(git_repo: &gix::Repository) -> String {
+    let head = git_repo.find_reference("HEAD").unwrap();
+    match head.target() {
+        gix::refs::TargetRef::Object(id) => id.to_string(),
+        gix::refs::TargetRef::Symbolic(name) => format!("ref: {name}"),
+    }
+}
+
+fn read_git_file(commit: &gix::Commit, path: &str) -> Vec<u8> {
+    let entry = commit
+        .tree()
+        .unwrap()
+        .lookup_entry_by_path(path)
+        .unwrap()
+        .unwrap();
+    entry.object().unwrap().detach().data
+}
+
 fn get_git_repo_refs(git_repo: &gix::Repository) -> Vec<(bstr::BString, CommitId)> {
     let mut refs: Vec<_> = git_repo
         .references()
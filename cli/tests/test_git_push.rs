@@ -145,6 +145,162 @@ fn test_git_push_default_remote_selection() {
     ");
 }
 
+#[test]
+fn test_git_push_tracked_remote_selection() {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("origin"));
+    git::init_bare(test_env.env_root().join("other"));
+    git::init_bare(test_env.env_root().join("upstream"));
+    test_env.run_jj_in(".", ["git", "init", "local"]).success();
+    let work_dir = test_env.work_dir("local");
+    for remote in ["origin", "other", "upstream"] {
+        work_dir
+            .run_jj(["git", "remote", "add", remote, &format!("../{remote}")])
+            .success();
+    }
+    work_dir.run_jj(["describe", "-m=foo"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo", "bar", "baz"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "track", "foo", "--remote=other"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "track", "bar", "--remote=origin"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "track", "bar", "--remote=other"])
+        .success();
+    work_dir
+        .run_jj(["bookmark", "track", "baz", "--remote=upstream"])
+        .success();
+
+    // select remote tracked by the bookmark
+    let output = work_dir.run_jj(["git", "push", "-b", "foo", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hint: Pushing to the tracked remote: other
+    Changes to push to other:
+      bookmark: foo [add to b786fe89321f]
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // git.push config takes precedence
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--config=git.push=origin",
+        "-b",
+        "foo",
+        "--dry-run",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Refusing to create new remote bookmark foo@origin
+    Hint: Run `jj bookmark track foo --remote=origin` and try again.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // bookmark tracking multiple remotes including the default remote
+    let output = work_dir.run_jj(["git", "push", "-b", "bar", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: bar [add to b786fe89321f]
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+
+    // bookmarks tracking different remotes other than the default remote
+    let output = work_dir.run_jj(["git", "push", "-b", "foo", "-b", "baz", "--dry-run"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Bookmarks to push are tracking multiple remotes: other, upstream
+    Hint: Use `--remote` to select the remote to push to.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
+#[test]
+fn test_git_push_no_thin() -> TestResult {
+    let test_env = TestEnvironment::default();
+    let remote_repo = git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "local"]).success();
+    let work_dir = test_env.work_dir("local");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir.write_file("file", "contents");
+    work_dir.run_jj(["commit", "-m=first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+    work_dir
+        .run_jj(["git", "push", "--no-thin", "--bookmark=main"])
+        .success();
+
+    // Push a descendant, whose pack would be thin by default
+    work_dir.write_file("file", "modified contents");
+    work_dir.run_jj(["commit", "-m=second"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r@-", "main"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--no-thin", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: main [move forward from 72e90a4d9f40 to a508a06008f8]
+    [EOF]
+    ");
+    let local_id = work_dir.run_jj(["log", "-r@-", "-T=commit_id", "--no-graph"]);
+    let remote_id = remote_repo
+        .find_reference("refs/heads/main")?
+        .id()
+        .to_string();
+    assert_eq!(local_id.stdout.raw(), remote_id);
+    Ok(())
+}
+
+#[test]
+fn test_git_push_signed() {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "local"]).success();
+    let work_dir = test_env.work_dir("local");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    work_dir.run_jj(["commit", "-m=first"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main"])
+        .success();
+
+    // The remote doesn't advertise push certificate support
+    let output = work_dir.run_jj(["git", "push", "--signed", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: main [add to 059d86266fd1]
+    Error: The remote doesn't support signed pushes
+    Hint: Use `--signed=if-asked` to sign only if the remote supports it.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Signing is skipped if the remote doesn't ask for it
+    let output = work_dir.run_jj(["git", "push", "--signed=if-asked", "--bookmark=main"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: main [add to 059d86266fd1]
+    git: warning: not sending a push certificate since the receiving end does not support --signed push        
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_push_current_bookmark() {
     let test_env = TestEnvironment::default();
@@ -255,6 +411,109 @@ fn test_git_push_parent_bookmark() {
     ");
 }
 
+#[test]
+fn test_git_push_stack() {
+    let test_env = TestEnvironment::default();
+    git::init_bare(test_env.env_root().join("remote"));
+    test_env.run_jj_in(".", ["git", "init", "local"]).success();
+    let work_dir = test_env.work_dir("local");
+    work_dir
+        .run_jj(["git", "remote", "add", "origin", "../remote"])
+        .success();
+    for name in ["first", "second", "third"] {
+        work_dir.write_file("file", name);
+        work_dir.run_jj(["commit", "-m", name]).success();
+        work_dir
+            .run_jj(["bookmark", "create", "-r@-", name])
+            .success();
+    }
+
+    // Bookmarks on the ancestors are pushed parents first
+    let output = work_dir.run_jj(["git", "push", "--stack", "-bthird"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: first [add to 661c22a4d931]
+      bookmark: second [add to ea2c3aa03c78]
+      bookmark: third [add to 640b4ce15c38]
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    first: qpvuntsm 661c22a4 first
+      @origin: qpvuntsm 661c22a4 first
+    second: kkmpptxz ea2c3aa0 second
+      @origin: kkmpptxz ea2c3aa0 second
+    third: mzvwutvl 640b4ce1 third
+      @origin: mzvwutvl 640b4ce1 third
+    [EOF]
+    ");
+
+    // The push is aborted with --atomic if a stacked bookmark can't be pushed
+    work_dir
+        .run_jj(["describe", "-rfirst", "-m=first (rewritten)"])
+        .success();
+    let setup_op_id = work_dir.current_operation_id();
+    work_dir
+        .run_jj(["bookmark", "set", "-rfirst", "second", "--allow-backwards"])
+        .success();
+    work_dir
+        .run_jj([
+            "bookmark",
+            "set",
+            "-rthird",
+            "second",
+            "--at-op",
+            &setup_op_id,
+        ])
+        .success();
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    first: qpvuntsm 414a59c2 first (rewritten)
+      @origin (ahead by 1 commits, behind by 1 commits): qpvuntsm/1 661c22a4 (hidden) first
+    second (conflicted):
+      - kkmpptxz eb844a82 second
+      + qpvuntsm 414a59c2 first (rewritten)
+      + mzvwutvl fa52a00c third
+      @origin (ahead by 2 commits, behind by 3 commits): kkmpptxz/1 ea2c3aa0 (hidden) second
+    third: mzvwutvl fa52a00c third
+      @origin (ahead by 3 commits, behind by 3 commits): mzvwutvl/1 640b4ce1 (hidden) third
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["git", "push", "--stack", "-bthird", "--atomic"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Bookmark second is conflicted
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    [EOF]
+    [exit status: 1]
+    ");
+
+    // Without --atomic, the other bookmarks are pushed
+    let output = work_dir.run_jj(["git", "push", "--stack", "-bthird"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Bookmark second is conflicted
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    Changes to push to origin:
+      bookmark: first [move sideways from 661c22a4d931 to 414a59c2b86a]
+      bookmark: third [move sideways from 640b4ce15c38 to fa52a00c3331]
+    [EOF]
+    ");
+
+    // --stack requires --bookmark
+    let output = work_dir.run_jj(["git", "push", "--stack"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the following required arguments were not provided:
+      --bookmark <BOOKMARK>
+
+    Usage: jj git push --bookmark <BOOKMARK> --stack
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_git_push_tag_in_default_target() {
     let test_env = TestEnvironment::default();
@@ -429,6 +688,163 @@ fn test_git_push_forward_unexpectedly_moved() {
     ");
 }
 
+#[test]
+fn test_git_push_fetch_first() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+
+    // Move bookmark1 forward on the remote
+    let origin_dir = test_env.work_dir("origin");
+    origin_dir
+        .run_jj(["new", "bookmark1", "-m=remote"])
+        .success();
+    origin_dir.write_file("remote", "remote");
+    origin_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+    origin_dir.run_jj(["git", "export"]).success();
+
+    // Move bookmark1 forward to another commit locally
+    work_dir.run_jj(["new", "bookmark1", "-m=local"]).success();
+    work_dir.write_file("local", "local");
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+
+    // Nothing is fetched with --dry-run
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--fetch-first",
+        "--dry-run",
+        "-b",
+        "bookmark1",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Warning: Not fetching from origin because of --dry-run
+    Changes to push to origin:
+      bookmark: bookmark1 [move forward from 9b2e76de3920 to 624f94a35f00]
+    Dry-run requested, not pushing.
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["op", "log", "-n1", "--no-graph", "-Tdescription"]);
+    insta::assert_snapshot!(output, @"
+    point bookmark bookmark1 to commit 624f94a35f00d6144f2f218c6d98aba9be1cd9b7[EOF]
+    ");
+
+    // The divergence is detected by fetching, and nothing is pushed
+    let output = work_dir.run_jj(["git", "push", "--fetch-first", "-b", "bookmark1"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    bookmark: bookmark1@origin [updated] tracked
+    Error: Bookmark bookmark1 is conflicted
+    Hint: Run `jj bookmark list` to inspect, and use `jj bookmark set` to fix it up.
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    bookmark1 (conflicted):
+      - qpvuntsm 9b2e76de (empty) description 1
+      + kpqxywon 624f94a3 local
+      + vruxwmqv 7ce4029e remote
+      @origin (behind by 1 commits): vruxwmqv 7ce4029e remote
+    bookmark2: zsuskuln 38a20473 (empty) description 2
+      @origin: zsuskuln 38a20473 (empty) description 2
+    [EOF]
+    ");
+
+    // Bookmarks which haven't diverged can still be pushed
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark2", "--allow-backwards", "-r@"])
+        .success();
+    let output = work_dir.run_jj(["git", "push", "--fetch-first", "-b", "bookmark2"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Nothing changed.
+    Changes to push to origin:
+      bookmark: bookmark2 [move sideways from 38a204733702 to 624f94a35f00]
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_git_push_expect_remote_head() {
+    let test_env = TestEnvironment::default();
+    set_up(&test_env);
+    let work_dir = test_env.work_dir("local");
+    let origin_dir = test_env.work_dir("origin");
+    let origin_git_repo_path = git_repo_dir_for_jj_repo(&origin_dir);
+    let get_commit_id = |rev: &str| {
+        work_dir
+            .run_jj(["log", "--no-graph", "-T=commit_id", "-r", rev])
+            .success()
+            .stdout
+            .into_raw()
+    };
+    let bookmark2_id = get_commit_id("bookmark2@origin");
+
+    // The remote default branch changed from bookmark1 to bookmark2
+    std::fs::write(
+        origin_git_repo_path.join("HEAD"),
+        "ref: refs/heads/bookmark2\n",
+    )
+    .unwrap();
+
+    work_dir.run_jj(["new", "bookmark1", "-m=local"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "bookmark1", "-r@"])
+        .success();
+
+    // The guard aborts the push, reporting the observed remote HEAD
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "-b=bookmark1",
+        "--expect-remote-head=bookmark1@origin",
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: bookmark1 [move forward from 9b2e76de3920 to a1c1db2cd4dc]
+    Error: The remote HEAD is at 38a2047337025526e8b7e79321e47168ea4996f3, but expected 9b2e76de39209592527956784ca134072f70850f
+    [EOF]
+    [exit status: 1]
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&work_dir), @"
+    bookmark1: yostqsxw a1c1db2c (empty) local
+      @origin (behind by 1 commits): qpvuntsm 9b2e76de (empty) description 1
+    bookmark2: zsuskuln 38a20473 (empty) description 2
+      @origin: zsuskuln 38a20473 (empty) description 2
+    [EOF]
+    ");
+
+    // The push proceeds if the remote HEAD matches. The expected commit can
+    // be specified by a short commit id.
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "-b=bookmark1",
+        &format!("--expect-remote-head={}", &bookmark2_id[..12]),
+    ]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Changes to push to origin:
+      bookmark: bookmark1 [move forward from 9b2e76de3920 to a1c1db2cd4dc]
+    [EOF]
+    ");
+
+    // Unknown revision
+    let output = work_dir.run_jj(["git", "push", "--expect-remote-head=foo"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Revision `foo` doesn't exist
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_git_push_sideways_unexpectedly_moved() {
     let test_env = TestEnvironment::default();
@@ -1103,6 +1519,30 @@ fn test_git_push_changes() {
     [EOF]
     [exit status: 1]
     ");
+
+    // Generated bookmark names must be valid Git ref names
+    let output = work_dir.run_jj([
+        "git",
+        "push",
+        "--config=templates.git_push_bookmark=\"'bad..name'\"",
+        "--change=@",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    ------- stderr -------
+    Error: Invalid Git ref name generated: "bad..name"
+    Caused by: Reference name cannot contain repeated dots
+    [EOF]
+    [exit status: 1]
+    "#);
+
+    // The root commit can't be pushed
+    let output = work_dir.run_jj(["git", "push", "--change=root()"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Cannot push the root commit
+    [EOF]
+    [exit status: 1]
+    ");
 }
 
 #[test]
@@ -1758,16 +2198,27 @@ fn test_git_push_allow_new_heuristics() {
         ])
         .success();
 
-    // ...unless the ref is already tracked with a different remote, in
-    // which case the user may have simply forgotten to specify --remote.
+    // The bookmark is pushed to the remote it's tracked with by default.
     let output = work_dir.run_jj(["git", "push", "--bookmark=untracked"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
+    Hint: Pushing to the tracked remote: upstream
+    Bookmark untracked@upstream already matches untracked
+    Nothing changed.
+    [EOF]
+    ");
+    // ...unless the remote is specified explicitly, in which case the user may
+    // have simply specified the wrong remote.
+    let output = work_dir.run_jj(["git", "push", "--bookmark=untracked", "--remote=origin"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
     Error: Refusing to create new remote bookmark untracked@origin
     Hint: Run `jj bookmark track untracked --remote=origin` and try again.
     [EOF]
     [exit status: 1]
     ");
+    // Tags are pushed to the default remote, which may have simply been
+    // forgotten to be specified.
     let output = work_dir.run_jj(["git", "push", "--tag=untracked"]);
     insta::assert_snapshot!(output, @"
     ------- stderr -------
@@ -224,6 +224,58 @@ fn test_git_remote_add_duplicate_url_warning_with_url_rewrite() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_git_remote_list_with_url_rewrite() {
+    let test_env = TestEnvironment::default();
+
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let mut config_file = fs::OpenOptions::new()
+        .append(true)
+        .open(work_dir.root().join(".jj/repo/store/git/config"))
+        .unwrap();
+    writeln!(
+        config_file,
+        r#"[url "https://example.com/"]
+	insteadOf = gh:
+[url "ssh://git@example.com/"]
+	pushInsteadOf = gh:"#
+    )
+    .unwrap();
+    drop(config_file);
+
+    work_dir
+        .run_jj(["git", "remote", "add", "foo", "gh:org/repo"])
+        .success();
+    work_dir
+        .run_jj(["git", "remote", "add", "bar", "https://example.org/repo"])
+        .success();
+    // The rewritten URLs are the ones used for fetching and pushing
+    let output = work_dir.run_jj(["git", "remote", "list"]);
+    insta::assert_snapshot!(output, @"
+    bar https://example.org/repo
+    foo https://example.com/org/repo (push: ssh://git@example.com/org/repo)
+    [EOF]
+    ");
+    // The configured URL is kept as is
+    insta::assert_snapshot!(read_git_config(work_dir.root()), @r#"
+    [core]
+    	bare = true
+    	logallrefupdates = false
+    	repositoryformatversion = 0
+    [url "https://example.com/"]
+    	insteadOf = gh:
+    [url "ssh://git@example.com/"]
+    	pushInsteadOf = gh:
+    [remote "foo"]
+    	url = gh:org/repo
+    	fetch = +refs/heads/*:refs/remotes/foo/*
+    [remote "bar"]
+    	url = https://example.org/repo
+    	fetch = +refs/heads/*:refs/remotes/bar/*
+    "#);
+}
+
 #[test]
 fn test_git_remote_add_duplicate_url_warning_omits_url() {
     let test_env = TestEnvironment::default();
@@ -587,6 +639,68 @@ fn test_git_remote_rename() {
     "#);
 }
 
+#[test]
+fn test_git_remote_rename_tracking_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir
+        .run_jj(["git", "remote", "add", "foo", "http://example.com/repo/foo"])
+        .success();
+    work_dir
+        .run_jj(["git", "remote", "add", "baz", "http://example.com/repo/baz"])
+        .success();
+
+    // Set up remote-tracking refs as if they were fetched
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    for remote in ["foo", "baz"] {
+        git::add_commit(
+            &git_repo,
+            &format!("refs/remotes/{remote}/main"),
+            "file",
+            remote.as_bytes(),
+            remote,
+            &[],
+        );
+        git::set_symbolic_reference(
+            &git_repo,
+            &format!("refs/remotes/{remote}/HEAD"),
+            &format!("refs/remotes/{remote}/main"),
+        );
+    }
+    work_dir.run_jj(["git", "import"]).success();
+    work_dir
+        .run_jj(["bookmark", "track", "main", "--remote=foo"])
+        .success();
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    main: mqxkromu e94b2362 foo
+      @foo: mqxkromu e94b2362 foo
+    main@baz: tqpnplmn 744d7c51 baz
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["git", "remote", "rename", "foo", "bar"]);
+    insta::assert_snapshot!(output, @"");
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    main: mqxkromu e94b2362 foo
+      @bar: mqxkromu e94b2362 foo
+    main@baz: tqpnplmn 744d7c51 baz
+    [EOF]
+    ");
+    let git_repo = git::open(work_dir.root().join(".jj/repo/store/git"));
+    let head_ref = git_repo.find_reference("refs/remotes/bar/HEAD").unwrap();
+    insta::assert_snapshot!(head_ref.target().try_name().unwrap(), @"refs/remotes/bar/main");
+
+    // The remote-tracking bookmarks are renamed in a single operation
+    let output = work_dir.run_jj(["op", "log", "-n1", "-T", "description"]);
+    insta::assert_snapshot!(output, @"
+    @  rename git remote foo to bar
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_remote_rename_updates_trunk() {
     // Verify trunk() resolves correctly after renaming the remote it references.
@@ -0,0 +1,82 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::indoc;
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_git_submodule_list() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["git", "submodule", "list"]);
+    insta::assert_snapshot!(output, @"");
+
+    // "sub2" has no gitlink, and "sub3" has no .gitmodules entry
+    work_dir.write_file(
+        ".gitmodules",
+        indoc! {r#"
+            [submodule "sub1"]
+                path = sub1
+                url = https://example.com/sub1.git
+            [submodule "sub2"]
+                path = sub2
+                url = ../sub2.git
+        "#},
+    );
+    let git = |args: &[&str]| {
+        work_dir
+            .run_jj(["util", "exec", "--", "git"].iter().chain(args))
+            .success();
+    };
+    git(&["add", ".gitmodules"]);
+    git(&[
+        "update-index",
+        "--add",
+        "--cacheinfo",
+        "160000,1111111111111111111111111111111111111111,sub1",
+    ]);
+    git(&[
+        "update-index",
+        "--add",
+        "--cacheinfo",
+        "160000,3333333333333333333333333333333333333333,sub3",
+    ]);
+    git(&[
+        "-c",
+        "user.email=test@example.com",
+        "-c",
+        "user.name=Test user",
+        "commit",
+        "-m",
+        "Add submodules",
+    ]);
+
+    let output = work_dir.run_jj(["git", "submodule", "list"]);
+    insta::assert_snapshot!(output, @r#"
+    sub1 https://example.com/sub1.git 1111111111111111111111111111111111111111
+    sub2 ../sub2.git <no gitlink>
+    sub3 <not in .gitmodules> 3333333333333333333333333333333333333333
+    [EOF]
+    ------- stderr -------
+    ignoring git submodule at "sub1"
+    ignoring git submodule at "sub3"
+    Done importing changes from the underlying Git repo.
+    [EOF]
+    "#);
+}
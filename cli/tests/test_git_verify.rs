@@ -0,0 +1,87 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use testutils::TestResult;
+use testutils::git;
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_git_verify() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("signing.backend = 'test'");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file", "a\n");
+    work_dir.run_jj(["commit", "-m", "unsigned"]).success();
+    work_dir.write_file("file", "b\n");
+    work_dir
+        .run_jj(["describe", "-m", "signed", "--config=signing.behavior=own"])
+        .success();
+    work_dir
+        .run_jj(["new", "root()", "-m", "conflicting"])
+        .success();
+    work_dir.write_file("file", "c\n");
+    work_dir
+        .run_jj([
+            "new",
+            "subject(signed)",
+            "subject(conflicting)",
+            "-m",
+            "merge",
+        ])
+        .success();
+
+    // Copy the signed commit with a tampered message, and with a signature that
+    // no backend can read
+    let output = work_dir.run_jj(["log", "--no-graph", "-r=subject(signed)", "-Tcommit_id"]);
+    let signed_id = gix::ObjectId::from_hex(output.stdout.raw().as_bytes())?;
+    let git_repo = git::open(work_dir.root());
+    let signed_commit: gix::objs::Commit = git_repo.find_commit(signed_id)?.decode()?.try_into()?;
+    for (name, signature) in [("tampered", None), ("unreadable", Some("unreadable"))] {
+        let mut commit = signed_commit.clone();
+        commit.message = format!("{name}\n").into();
+        commit.extra_headers.retain(|(key, _)| key != "change-id");
+        if let Some(signature) = signature {
+            for (key, value) in &mut commit.extra_headers {
+                if key == "gpgsig" {
+                    *value = signature.into();
+                }
+            }
+        }
+        let id = git_repo.write_object(&commit)?.detach();
+        git_repo.reference(
+            format!("refs/heads/{name}"),
+            id,
+            gix::refs::transaction::PreviousValue::Any,
+            "test",
+        )?;
+    }
+    work_dir.run_jj(["git", "import"]).success();
+
+    let output = work_dir.run_jj(["git", "verify", "-r", "all() ~ root()"]);
+    insta::assert_snapshot!(output, @"
+    bad signature: qmknnmnx eac404cf tampered | tampered
+    unknown key: wrxpnqts c4ab8cae unreadable | unreadable
+    n/a: mzvwutvl 54751317 (conflict) (empty) merge
+    unsigned: zsuskuln 2278aecc conflicting
+    good: rlvkpnrz 36d98fff signed
+    unsigned: qpvuntsm 1bc4eb11 unsigned
+    [EOF]
+    ");
+    Ok(())
+}
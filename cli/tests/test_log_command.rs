@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use testutils::TestResult;
+use testutils::git;
+
 use crate::common::TestEnvironment;
 use crate::common::to_toml_value;
 
@@ -1092,6 +1095,78 @@ fn test_log_limit() {
     ");
 }
 
+#[test]
+fn test_log_order_by_date() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["describe", "-m", "a"]).success();
+    work_dir.run_jj(["new", "-m", "b"]).success();
+    work_dir.run_jj(["new", "-m", "c", "root()"]).success();
+    // Author dates in the order of b, c, a. Committer dates follow the order of
+    // the rewrites.
+    for (subject, timestamp) in [
+        ("a", "2001-02-03T04:05:30+07:00"),
+        ("c", "2001-02-03T04:05:20+07:00"),
+        ("b", "2001-02-03T04:05:10+07:00"),
+    ] {
+        work_dir
+            .run_jj([
+                "metaedit",
+                "--author-timestamp",
+                timestamp,
+                &format!("subject({subject})"),
+            ])
+            .success();
+    }
+
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      author.timestamp(),
+      committer.timestamp(),
+    ) ++ "\n"
+    "#;
+    let output = work_dir.run_jj(["log", "-r~root()", "-T", template, "--no-graph"]);
+    insta::assert_snapshot!(output, @"
+    b 2001-02-03 04:05:10.000 +07:00 2001-02-03 04:05:13.000 +07:00
+    c 2001-02-03 04:05:20.000 +07:00 2001-02-03 04:05:12.000 +07:00
+    a 2001-02-03 04:05:30.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r~root()", "-T", template, "--order=author-date"]);
+    insta::assert_snapshot!(output, @"
+    a 2001-02-03 04:05:30.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    c 2001-02-03 04:05:20.000 +07:00 2001-02-03 04:05:12.000 +07:00
+    b 2001-02-03 04:05:10.000 +07:00 2001-02-03 04:05:13.000 +07:00
+    [EOF]
+    ");
+    let output = work_dir.run_jj(["log", "-r~root()", "-T", template, "--order=committer-date"]);
+    insta::assert_snapshot!(output, @"
+    b 2001-02-03 04:05:10.000 +07:00 2001-02-03 04:05:13.000 +07:00
+    c 2001-02-03 04:05:20.000 +07:00 2001-02-03 04:05:12.000 +07:00
+    a 2001-02-03 04:05:30.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    [EOF]
+    ");
+
+    // Limit is applied before reversing
+    let output = work_dir.run_jj([
+        "log",
+        "-r~root()",
+        "-T",
+        template,
+        "--order=author-date",
+        "--limit=2",
+        "--reversed",
+    ]);
+    insta::assert_snapshot!(output, @"
+    c 2001-02-03 04:05:20.000 +07:00 2001-02-03 04:05:12.000 +07:00
+    a 2001-02-03 04:05:30.000 +07:00 2001-02-03 04:05:11.000 +07:00
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_log_warn_path_might_be_revset() {
     let test_env = TestEnvironment::default();
@@ -1827,6 +1902,63 @@ fn test_log_anonymize() {
     ");
 }
 
+#[test]
+fn test_log_decorate_git_style() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.export.attach-head = true");
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+    let git_repo = git::open(work_dir.root());
+
+    work_dir.run_jj(["commit", "-mfirst"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@-", "main", "feature"])
+        .success();
+    work_dir.run_jj(["tag", "set", "-r@-", "v1"]).success();
+    work_dir.run_jj(["commit", "-msecond"]).success();
+    work_dir
+        .run_jj(["bookmark", "set", "-r@-", "main"])
+        .success();
+    let commit_id = work_dir
+        .run_jj(["log", "-Tcommit_id", "--no-graph", "-rmain"])
+        .success()
+        .stdout
+        .into_raw();
+    git_repo.reference(
+        "refs/remotes/origin/main",
+        gix::ObjectId::from_hex(commit_id.as_bytes())?,
+        gix::refs::transaction::PreviousValue::Any,
+        "",
+    )?;
+    // Check out the bookmark so the Git HEAD points to it
+    work_dir.run_jj(["new", "feature"]).success();
+    work_dir.run_jj(["new", "main"]).success();
+
+    let output = work_dir.run_jj(["log", "--decorate-git-style", "-r::@"]);
+    insta::assert_snapshot!(output, @"
+    @  c9619cc7 (no description set)
+    ◆  c7b64ae4 (HEAD -> main, origin/main) second
+    ◆  68a50538 (feature, tag: v1) first
+    ◆  zzzzzzzz root() 00000000
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["log", "--decorate-git-style", "-T=builtin_log_oneline"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the argument '--decorate-git-style' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj log --decorate-git-style [FILESETS]...
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_log_count() {
     let test_env = TestEnvironment::default();
@@ -84,6 +84,59 @@ fn test_revert_rewrite_with_child() {
     ");
 }
 
+#[test]
+fn test_revert_commits_only() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let log_template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-m", "old"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    // This operation rewrites the commit and moves the bookmark
+    work_dir.run_jj(["describe", "-m", "new"]).success();
+    let describe_op_id = work_dir.current_operation_id();
+    work_dir.run_jj(["new", "root()", "-m", "other"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  other
+    │ ○  new foo
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // Only the commit rewrite is reverted, the bookmark stays on the new commit
+    work_dir
+        .run_jj(["op", "revert", &describe_op_id, "--what=commits"])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  other
+    │ ○  new foo
+    ├─╯
+    │ ○  old
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // Only the bookmark move is reverted
+    work_dir.run_jj(["undo"]).success();
+    work_dir
+        .run_jj(["op", "revert", &describe_op_id, "--what=local-refs"])
+        .success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  other
+    │ ○  new
+    ├─╯
+    │ ○  old foo
+    ├─╯
+    ◆
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_git_push_revert() {
     let test_env = TestEnvironment::default();
@@ -378,6 +378,43 @@ fn test_op_log_no_graph_null_terminated() {
     insta::assert_debug_snapshot!(output.stdout.normalized(), @r#""20fb\01ea2\09026\00000\0""#);
 }
 
+#[test]
+fn test_op_log_op_id_only() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    work_dir.run_jj(["commit", "-m", "message1"]).success();
+
+    let output = work_dir.run_jj(["op", "log", "--op-id-only"]);
+    insta::assert_snapshot!(output, @"
+    1ea2c9cafd64c6d242b22416457e153d6fece63af41a4c988d68bd954f59d18e432a6f4d245b34a9c6696592f48ac8582f392d5f675ba4533e02133faefd1f87
+    90267f31f90442f630dd8a2b5feaf8cf753dc64324e3d2d46bfd6d93f279a4d7630c2701a06a60ec04ca5c01a1e3f6758c0ab4f1efe6997ae82789328fb77fc9
+    00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+    [EOF]
+    ");
+    let lines = output.stdout.raw().lines().collect_vec();
+    assert_eq!(lines[0], work_dir.current_operation_id());
+
+    let output = work_dir.run_jj(["op", "log", "--op-id-only", "--limit=2", "--reversed"]);
+    insta::assert_snapshot!(output, @"
+    90267f31f90442f630dd8a2b5feaf8cf753dc64324e3d2d46bfd6d93f279a4d7630c2701a06a60ec04ca5c01a1e3f6758c0ab4f1efe6997ae82789328fb77fc9
+    1ea2c9cafd64c6d242b22416457e153d6fece63af41a4c988d68bd954f59d18e432a6f4d245b34a9c6696592f48ac8582f392d5f675ba4533e02133faefd1f87
+    [EOF]
+    ");
+
+    let output = work_dir.run_jj(["op", "log", "--op-id-only", "-Tid"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    error: the argument '--op-id-only' cannot be used with '--template <TEMPLATE>'
+
+    Usage: jj operation log --op-id-only
+
+    For more information, try '--help'.
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_op_log_template() -> TestResult {
     let test_env = TestEnvironment::default();
@@ -257,6 +257,72 @@ fn test_rebase_bookmark() {
     ");
 }
 
+#[test]
+fn test_rebase_stack_moves_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit(&work_dir, "a", &[]);
+    create_commit(&work_dir, "b", &["a"]);
+    work_dir
+        .run_jj(["bookmark", "create", "-rb", "b2"])
+        .success();
+    // This commit will become empty when rebased onto d
+    create_commit_with_files(&work_dir, "dup", &["b"], &[("d", "d\n")]);
+    create_commit(&work_dir, "c", &["dup"]);
+    create_commit(&work_dir, "d", &["a"]);
+    work_dir.run_jj(["git", "export"]).success();
+    // Test the setup
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  d: a
+    │ ○  c: dup
+    │ ○  dup: b b2
+    │ ○  b b2: a
+    ├─╯
+    ○  a
+    ◆
+    [EOF]
+    ");
+
+    // Bookmarks on the rebased commits are moved to the rewritten commits,
+    // and the bookmark on the abandoned commit is moved to its parent
+    let output = work_dir.run_jj(["rebase", "-s=b", "-o=d", "--skip-emptied"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Rebased 2 commits to destination.
+    Abandoned 1 newly emptied commits.
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    ○  c^: b^ b2^ dup^
+    ○  b^ b2^ dup^: d
+    @  d: a
+    ○  a
+    ◆
+    [EOF]
+    ");
+
+    // The moved bookmarks are exported to Git
+    work_dir.run_jj(["git", "export"]).success();
+    let output = work_dir.run_jj(["bookmark", "list", "--all-remotes"]);
+    insta::assert_snapshot!(output, @"
+    a: rlvkpnrz 7d980be7 a
+      @git: rlvkpnrz 7d980be7 a
+    b: zsuskuln 65b1a485 b
+      @git: zsuskuln 65b1a485 b
+    b2: zsuskuln 65b1a485 b
+      @git: zsuskuln 65b1a485 b
+    c: yostqsxw b26abe30 c
+      @git: yostqsxw b26abe30 c
+    d: kpqxywon 20893fd7 d
+      @git: kpqxywon 20893fd7 d
+    dup: zsuskuln 65b1a485 b
+      @git: zsuskuln 65b1a485 b
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_rebase_bookmark_with_merge() {
     let test_env = TestEnvironment::default();
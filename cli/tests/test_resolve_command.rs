@@ -756,6 +756,104 @@ fn test_too_many_parents() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_resolve_print() -> TestResult {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file", "base\n"), ("other", "base\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "a",
+        &["base"],
+        &[("file", "a\n"), ("other", "a\n")],
+    );
+    create_commit_with_files(
+        &work_dir,
+        "b",
+        &["base"],
+        &[("file", "b\n"), ("other", "b\n")],
+    );
+    create_commit_with_files(&work_dir, "c", &["base"], &[("file", "c\n")]);
+    create_commit_with_files(&work_dir, "conflict2", &["a", "b"], &[]);
+    create_commit_with_files(&work_dir, "conflict3", &["a", "b", "c"], &[]);
+
+    // A 2-sided conflict
+    let output = work_dir.run_jj(["resolve", "-r=conflict2", "--print", "file"]);
+    insta::assert_snapshot!(output, @r#"
+    <<<<<<< conflict 1 of 1
+    %%%%%%% diff from: rlvkpnrz ed3e06b2 "base"
+    \\\\\\\        to: zsuskuln 4066fb2d "a"
+    -base
+    +a
+    +++++++ royxmykx 91d14785 "b"
+    b
+    >>>>>>> conflict 1 of 1 ends
+    [EOF]
+    "#);
+
+    // A 3-sided conflict shows all sides
+    let output = work_dir.run_jj(["resolve", "-r=conflict3", "--print", "file"]);
+    insta::assert_snapshot!(output, @r#"
+    <<<<<<< conflict 1 of 1
+    %%%%%%% diff from: rlvkpnrz ed3e06b2 "base"
+    \\\\\\\        to: zsuskuln 4066fb2d "a"
+    -base
+    +a
+    %%%%%%% diff from: rlvkpnrz ed3e06b2 "base"
+    \\\\\\\        to: royxmykx 91d14785 "b"
+    -base
+    +b
+    +++++++ vruxwmqv 41824c57 "c"
+    c
+    >>>>>>> conflict 1 of 1 ends
+    [EOF]
+    "#);
+
+    // The configured marker style is used
+    let output = work_dir.run_jj([
+        "resolve",
+        "-r=conflict2",
+        "--print",
+        "file",
+        "--config=ui.conflict-marker-style=git",
+    ]);
+    insta::assert_snapshot!(output, @r#"
+    <<<<<<< zsuskuln 4066fb2d "a"
+    a
+    ||||||| rlvkpnrz ed3e06b2 "base"
+    base
+    =======
+    b
+    >>>>>>> royxmykx 91d14785 "b"
+    [EOF]
+    "#);
+
+    // Printing doesn't modify the commit
+    let output = work_dir.run_jj(["resolve", "-r=conflict2", "--list"]);
+    insta::assert_snapshot!(output, @"
+    file     2-sided conflict
+    other    2-sided conflict
+    [EOF]
+    ");
+
+    // More than one conflicted path
+    let output = work_dir.run_jj(["resolve", "-r=conflict2", "--print"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: --print requires exactly one conflicted path, but 2 paths are conflicted
+    [EOF]
+    [exit status: 1]
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_simplify_conflict_sides() -> TestResult {
     let mut test_env = TestEnvironment::default();
@@ -593,6 +593,65 @@ fn test_restore_interactive_with_paths() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_restore_from_git_index_stage() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    create_commit_with_files(
+        &work_dir,
+        "base",
+        &[],
+        &[("file", "base\n"), ("other", "a\n")],
+    );
+    create_commit_with_files(&work_dir, "left", &["base"], &[("file", "left\n")]);
+    create_commit_with_files(&work_dir, "right", &["base"], &[("file", "right\n")]);
+    // The parents of the working-copy commit conflict, so the Git index has
+    // conflicted entries for "file"
+    work_dir.run_jj(["new", "left", "right"]).success();
+    work_dir.write_file("other", "b\n");
+
+    let output = work_dir.run_jj(["restore", "--from-git-index", "--stage=theirs"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Hint: Ignored `--stage` for path not conflicted in the Git index: other
+    Working copy  (@) now at: vruxwmqv 9202b66e (no description set)
+    Parent commit (@-)      : zsuskuln 286017f2 left | left
+    Parent commit (@-)      : royxmykx 804d865f right | right
+    Added 0 files, modified 2 files, removed 0 files
+    Existing conflicts were resolved or abandoned from 1 commits.
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.read_file("file"), @"right");
+    insta::assert_snapshot!(work_dir.read_file("other"), @"a");
+    insta::assert_snapshot!(work_dir.run_jj(["status"]), @"
+    Working copy changes:
+    M file
+    Working copy  (@) : vruxwmqv 9202b66e (no description set)
+    Parent commit (@-): zsuskuln 286017f2 left | left
+    Parent commit (@-): royxmykx 804d865f right | right
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_restore_from_git_index_not_colocated() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    let output = work_dir.run_jj(["restore", "--from-git-index"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: `--from-git-index` requires a workspace colocated with Git
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[must_use]
 fn get_log_output(work_dir: &TestWorkDir) -> CommandOutput {
     work_dir.run_jj(["log", "-T", "bookmarks"])
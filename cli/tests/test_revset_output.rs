@@ -511,6 +511,49 @@ fn test_bad_symbol_or_argument_should_not_be_optimized_out() {
     "#);
 }
 
+#[test]
+fn test_at_current_workspace_remote() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let template = r#"commit_id.short() ++ " " ++ bookmarks ++ "\n""#;
+
+    // No bookmark at @
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r@git"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: No bookmark points to the working-copy commit of workspace `default`
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-rpresent(@git)"]);
+    insta::assert_snapshot!(output, @"");
+
+    // A single exported bookmark at @
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "main"])
+        .success();
+    work_dir.run_jj(["git", "export"]).success();
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r@git"]);
+    insta::assert_snapshot!(output, @"
+    e8849ae12c70 main
+    [EOF]
+    ");
+
+    // Multiple bookmarks at @
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "feature"])
+        .success();
+    let output = work_dir.run_jj(["log", "--no-graph", "-T", template, "-r@git"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Multiple bookmarks point to the working-copy commit of workspace `default`
+    Hint: Use one of the remote bookmarks instead: feature@git, main@git
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_default_string_pattern() {
     let test_env = TestEnvironment::default();
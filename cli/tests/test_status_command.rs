@@ -170,6 +170,71 @@ fn test_status_filtered() {
     ");
 }
 
+#[test]
+fn test_status_ignore_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("file_1", "file_1");
+    work_dir.run_jj(["status"]).success();
+    work_dir.write_file("file_2", "file_2");
+
+    // file_2 isn't reported because the working copy isn't snapshotted
+    let output = work_dir.run_jj(["status", "--ignore-working-copy"]);
+    insta::assert_snapshot!(output, @"
+    Working copy changes:
+    A file_1
+    Working copy  (@) : qpvuntsm bfea4d0b (no description set)
+    Parent commit (@-): zzzzzzzz 00000000 (empty) (no description set)
+    [EOF]
+    ");
+
+    // The working-copy commit wasn't updated
+    let output = work_dir.run_jj(["log", "--ignore-working-copy", "-r@", "--summary"]);
+    insta::assert_snapshot!(output, @"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:08 bfea4d0b
+    │  (no description set)
+    ~  A file_1
+    [EOF]
+    ");
+}
+
+#[test]
+fn test_status_git_porcelain() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.write_file("modified", "base\n");
+    work_dir.write_file("deleted", "base\n");
+    work_dir.write_file("conflicted", "base\n");
+    work_dir.run_jj(["new", "-mleft"]).success();
+    work_dir.write_file("conflicted", "left\n");
+    work_dir.run_jj(["new", "-mright", "@-"]).success();
+    work_dir.write_file("conflicted", "right\n");
+    work_dir.run_jj(["new", "@", "@-+ ~ @"]).success();
+
+    work_dir.write_file("modified", "updated\n");
+    work_dir.remove_file("deleted");
+    work_dir.write_file("dir/added", "new\n");
+    let output = work_dir.run_jj(["status", "--git"]);
+    insta::assert_snapshot!(output, @"
+    UU conflicted
+    D  deleted
+    A  dir/added
+    M  modified
+    [EOF]
+    ");
+
+    // Paths can be filtered
+    let output = work_dir.run_jj(["status", "--git", "dir"]);
+    insta::assert_snapshot!(output, @"
+    A  dir/added
+    [EOF]
+    ");
+}
+
 #[test]
 fn test_status_conflicted_bookmarks() {
     // create conflicted local bookmark
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use testutils::git;
+
 use crate::common::CommandOutput;
 use crate::common::TestEnvironment;
 use crate::common::TestWorkDir;
@@ -107,6 +109,50 @@ fn test_tag_set_delete() {
     ");
 }
 
+#[test]
+fn test_tag_create_annotated() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "repo"])
+        .success();
+    let work_dir = test_env.work_dir("repo");
+
+    work_dir.run_jj(["commit", "-mcommit1"]).success();
+    let output = work_dir.run_jj(["tag", "create", "-r@-", "-mRelease 1.0", "v1.0"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Created annotated tag v1.0 pointing to qpvuntsm b876c5f4 (empty) commit1
+    [EOF]
+    ");
+    insta::assert_snapshot!(get_log_output(&work_dir), @"
+    @  bbc749308d7f
+    ◆  b876c5f49546 v1.0
+    ◆  000000000000
+    [EOF]
+    ");
+
+    let git_repo = git::open(work_dir.root());
+    let git_ref = git_repo.find_reference("refs/tags/v1.0").unwrap();
+    let tag = git_ref.id().object().unwrap().try_into_tag().unwrap();
+    let tag = tag.decode().unwrap();
+    assert_eq!(tag.name, "v1.0");
+    assert_eq!(tag.message, "Release 1.0\n");
+    assert_eq!(
+        tag.tagger().unwrap().unwrap().email,
+        "test.user@example.com"
+    );
+
+    // Existing tags can't be re-created
+    let output = work_dir.run_jj(["tag", "create", "-mmessage", "v1.0"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Tag already exists: v1.0
+    Hint: Use `jj tag set --allow-move` to move existing tags.
+    [EOF]
+    [exit status: 1]
+    ");
+}
+
 #[test]
 fn test_tag_at_root() {
     let test_env = TestEnvironment::default();
@@ -201,3 +201,57 @@ fn test_jump_over_old_redo_stack() {
     [exit status: 1]
     ");
 }
+
+#[test]
+fn test_undo_what() {
+    let test_env = TestEnvironment::default();
+    test_env.run_jj_in(".", ["git", "init", "repo"]).success();
+    let work_dir = test_env.work_dir("repo");
+    let log_template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+
+    work_dir.run_jj(["describe", "-m", "old"]).success();
+    work_dir
+        .run_jj(["bookmark", "create", "-r@", "foo"])
+        .success();
+    // This operation rewrites both the working-copy commit and the bookmark
+    work_dir.run_jj(["describe", "-m", "new"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  new foo
+    ◆
+    [EOF]
+    ");
+
+    // Only the bookmark is reverted
+    let output = work_dir.run_jj(["undo", "--what=refs"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Undid operation: 81d22d74d2f5 (2001-02-03 08:05:10) describe commit 1ea04b9f537799e42deb2f9d4ce4f773cb1c8b9e
+    Restored to operation: 9ca7e773f988 (2001-02-03 08:05:09) create bookmark foo pointing to commit 1ea04b9f537799e42deb2f9d4ce4f773cb1c8b9e
+    [EOF]
+    ");
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  new
+    │ ○  old foo
+    ├─╯
+    ◆
+    [EOF]
+    ");
+
+    // The partial undo can be undone
+    work_dir.run_jj(["undo"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  new foo
+    ◆
+    [EOF]
+    ");
+
+    // Only the working-copy commit is reverted
+    work_dir.run_jj(["undo", "--what=working-copy"]).success();
+    insta::assert_snapshot!(work_dir.run_jj(["log", "-T", log_template]), @"
+    @  old
+    │ ○  new foo
+    ├─╯
+    ◆
+    [EOF]
+    ");
+}
@@ -56,6 +56,67 @@ fn test_util_config_schema() {
     });
 }
 
+#[test]
+fn test_util_markdown_help_command() {
+    let test_env = TestEnvironment::default();
+    let output = test_env.run_jj_in(
+        ".",
+        ["util", "markdown-help", "--command", "git.remote.add"],
+    );
+    assert_snapshot!(output, @r#"
+    # Command-Line Help for `jj git remote add`
+
+    This document contains the help content for the `jj git remote add` command-line program.
+
+    **Command Overview:**
+
+    * [`jj git remote add`↴](#jj-git-remote-add)
+
+    ## `jj git remote add`
+
+    Add a Git remote
+
+    **Usage:** `jj git remote add [OPTIONS] <REMOTE> <URL>`
+
+    ###### **Arguments:**
+
+    * `<REMOTE>` — The remote's name
+    * `<URL>` — The remote's URL or path
+
+       Local path will be resolved to absolute form.
+
+    ###### **Options:**
+
+    * `--push-url <PUSH_URL>` — The URL used for push
+
+       Local path will be resolved to absolute form
+
+
+
+    <hr/>
+
+    <small><i>
+        This document was generated automatically by
+        <a href="https://crates.io/crates/clap-markdown"><code>clap-markdown</code></a>.
+    </i></small>
+    [EOF]
+    "#);
+
+    // Aliases are resolved to the canonical command names
+    let output = test_env.run_jj_in(".", ["util", "markdown-help", "--command", "bookmark.s"]);
+    let stdout = output.stdout.raw();
+    assert!(stdout.contains("## `jj bookmark set`"));
+    assert!(!stdout.contains("## `jj bookmark create`"));
+
+    let output = test_env.run_jj_in(".", ["util", "markdown-help", "--command", "git.nope"]);
+    assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: Unknown command: git nope
+    [EOF]
+    [exit status: 2]
+    ");
+}
+
 #[test]
 fn test_gc_args() {
     let test_env = TestEnvironment::default();
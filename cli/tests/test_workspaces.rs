@@ -1535,6 +1535,90 @@ fn test_workspaces_forget() {
     insta::assert_snapshot!(output, @"");
 }
 
+#[test]
+fn test_workspaces_forget_colocate_cleanup() {
+    let test_env = TestEnvironment::default();
+    test_env
+        .run_jj_in(".", ["git", "init", "--colocate", "main"])
+        .success();
+    let main_dir = test_env.work_dir("main");
+    main_dir.write_file("file", "contents");
+    main_dir.run_jj(["new"]).success();
+    main_dir
+        .run_jj(["workspace", "add", "../secondary"])
+        .success();
+    let secondary_dir = test_env.work_dir("secondary");
+
+    // Register the secondary workspace as a Git worktree
+    let worktree_git_dir = main_dir
+        .root()
+        .join(".git")
+        .join("worktrees")
+        .join("secondary");
+    std::fs::create_dir_all(&worktree_git_dir).unwrap();
+    let head_id = main_dir.run_jj(["log", "-r@-", "-T=commit_id", "--no-graph"]);
+    std::fs::write(worktree_git_dir.join("HEAD"), head_id.stdout.raw()).unwrap();
+    std::fs::write(worktree_git_dir.join("commondir"), "../..\n").unwrap();
+    let dot_git_path = secondary_dir.root().join(".git");
+    std::fs::write(
+        worktree_git_dir.join("gitdir"),
+        format!("{}\n", dot_git_path.display()),
+    )
+    .unwrap();
+    std::fs::write(
+        &dot_git_path,
+        format!("gitdir: {}\n", worktree_git_dir.display()),
+    )
+    .unwrap();
+    let list_worktrees = || {
+        let git_repo = git::open(main_dir.root());
+        git_repo
+            .worktrees()
+            .unwrap()
+            .iter()
+            .map(|worktree| worktree.id().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(list_worktrees(), ["secondary"]);
+
+    let output = main_dir.run_jj(["workspace", "forget", "--colocate-cleanup", "secondary"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Removed Git worktree for workspace secondary
+    [EOF]
+    ");
+    assert!(list_worktrees().is_empty());
+    assert!(!worktree_git_dir.exists());
+    assert!(!dot_git_path.exists());
+
+    // Nothing to clean up in non-worktree workspaces
+    main_dir.run_jj(["workspace", "add", "../third"]).success();
+    let output = main_dir.run_jj(["workspace", "forget", "--colocate-cleanup", "third"]);
+    insta::assert_snapshot!(output, @"");
+
+    // Non-Git backends are rejected before forgetting the workspace
+    test_env
+        .run_jj_in(".", ["debug", "init-simple", "simple"])
+        .success();
+    let simple_dir = test_env.work_dir("simple");
+    simple_dir
+        .run_jj(["workspace", "add", "../simple-second"])
+        .success();
+    let output = simple_dir.run_jj(["workspace", "forget", "--colocate-cleanup", "simple-second"]);
+    insta::assert_snapshot!(output, @"
+    ------- stderr -------
+    Error: The repo is not backed by a Git repo
+    [EOF]
+    [exit status: 1]
+    ");
+    let output = simple_dir.run_jj(["workspace", "list"]);
+    insta::assert_snapshot!(output, @"
+    default: vruxwmqv cef1c26b (empty) (no description set)
+    simple-second: wmwvqwsz effb73a6 (empty) (no description set)
+    [EOF]
+    ");
+}
+
 /// Test forgetting workspace created before workspace store
 #[test]
 fn test_workspaces_forget_from_before_workspace_store() {
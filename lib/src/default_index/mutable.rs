@@ -475,6 +475,15 @@ impl DefaultMutableIndex {
         Self(CompositeIndex::from_mutable(commits, changed_paths))
     }
 
+    /// Starts an index on top of the given commit index segment. The
+    /// changed-path index isn't carried over.
+    pub(super) fn incremental_commits(parent_file: Arc<ReadonlyCommitIndexSegment>) -> Self {
+        let commits = Box::new(MutableCommitIndexSegment::incremental(parent_file));
+        let mut changed_paths = CompositeChangedPathIndex::null();
+        changed_paths.make_mutable();
+        Self(CompositeIndex::from_mutable(commits, changed_paths))
+    }
+
     pub(super) fn into_segment(
         self,
     ) -> (Box<MutableCommitIndexSegment>, CompositeChangedPathIndex) {
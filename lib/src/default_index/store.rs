@@ -19,6 +19,7 @@ use std::fs;
 use std::future;
 use std::io;
 use std::io::Write as _;
+use std::iter;
 use std::path::Path;
 use std::path::PathBuf;
 use std::pin::pin;
@@ -38,6 +39,7 @@ use super::changed_path::ChangedPathIndexSegmentId;
 use super::changed_path::CompositeChangedPathIndex;
 use super::changed_path::collect_changed_paths;
 use super::composite::AsCompositeIndex as _;
+use super::composite::CommitIndexSegment as _;
 use super::composite::CommitIndexSegmentId;
 use super::entry::GlobalCommitPosition;
 use super::mutable::DefaultMutableIndex;
@@ -345,6 +347,113 @@ impl DefaultIndexStore {
         Ok(index)
     }
 
+    /// Reindexes commits whose parents have changed in the backend, and
+    /// associates the new index with the `operation`.
+    ///
+    /// This is needed after the history of a shallow Git repository is
+    /// deepened. Index segments preceding the first of the `commit_ids` are
+    /// reused, and the commits indexed after it are added again with their new
+    /// parents. The changed-path index is discarded, and the indexes of the
+    /// other operations will be rebuilt when loaded.
+    #[tracing::instrument(skip(self, store))]
+    pub async fn reindex_commits_at_operation(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        commit_ids: &[CommitId],
+    ) -> Result<DefaultReadonlyIndex, DefaultIndexStoreError> {
+        let field_lengths = FieldLengths {
+            commit_id: store.commit_id_length(),
+            change_id: store.change_id_length(),
+        };
+        let index = match self.load_index_at_operation(operation.id(), field_lengths) {
+            Ok(index) => index,
+            Err(_) => self.build_index_at_operation(operation, store).await?,
+        };
+        let old_commits = index.as_composite().commits();
+        let Some(GlobalCommitPosition(first_pos)) = commit_ids
+            .iter()
+            .filter_map(|id| old_commits.commit_id_to_pos(id))
+            .min()
+        else {
+            return Ok(index);
+        };
+        // Segments are ordered by position, so the ones ending before the
+        // first affected commit don't have to be rebuilt.
+        let base_segment = iter::successors(Some(index.readonly_commits()), |segment| {
+            segment.parent_file()
+        })
+        .find(|segment| segment.as_composite().num_commits() <= first_pos);
+        let mut mutable_index = match base_segment {
+            Some(segment) => DefaultMutableIndex::incremental_commits(segment.clone()),
+            None => DefaultMutableIndex::full(field_lengths),
+        };
+        let base_len = mutable_index.num_commits();
+
+        tracing::info!(
+            commits_count = old_commits.num_commits() - base_len,
+            "reindexing commits with changed parents"
+        );
+        let base_has_id = |id: &CommitId| {
+            old_commits
+                .commit_id_to_pos(id)
+                .is_some_and(|GlobalCommitPosition(pos)| pos < base_len)
+        };
+        let get_commit = async |commit_id: &CommitId| match store.get_commit_async(commit_id).await
+        {
+            Ok(commit) => Ok(CommitByCommitterTimestamp(commit)),
+            Err(source) => Err(DefaultIndexStoreError::IndexCommits {
+                op_id: operation.id().clone(),
+                source,
+            }),
+        };
+        let reindexed_ids = (base_len..old_commits.num_commits())
+            .map(|pos| {
+                old_commits
+                    .entry_by_pos(GlobalCommitPosition(pos))
+                    .commit_id()
+            })
+            .collect_vec();
+        // The new parents of the affected commits may not be indexed yet.
+        let commits = dag_walk_async::topo_order_reverse_ord(
+            stream::iter(&reindexed_ids)
+                .map(get_commit)
+                .buffered(store.concurrency())
+                .collect::<Vec<_>>()
+                .await,
+            |CommitByCommitterTimestamp(commit)| commit.id().clone(),
+            async |CommitByCommitterTimestamp(commit)| {
+                stream::iter(commit.parent_ids())
+                    .filter(|&id| future::ready(!base_has_id(id)))
+                    .map(get_commit)
+                    .buffered(store.concurrency())
+                    .collect::<Vec<_>>()
+                    .await
+            },
+            |_| panic!("graph has cycle"),
+        )
+        .await?;
+        for CommitByCommitterTimestamp(commit) in commits.iter().rev() {
+            mutable_index.add_commit(commit).await.map_err(|source| {
+                DefaultIndexStoreError::IndexCommits {
+                    op_id: operation.id().clone(),
+                    source,
+                }
+            })?;
+        }
+
+        // The indexes of the other operations still have the old parents.
+        file_util::remove_dir_contents(&self.op_links_dir())
+            .map_err(DefaultIndexStoreError::SaveIndex)?;
+        let index = self.save_mutable_index(mutable_index, operation.id())?;
+        tracing::info!(
+            ?index,
+            commits_count = commits.len(),
+            "saved reindexed index"
+        );
+        Ok(index)
+    }
+
     /// Builds changed-path index for the specified operation.
     ///
     /// At most `max_commits` number of commits will be scanned from the latest
@@ -572,6 +681,19 @@ impl IndexStore for DefaultIndexStore {
             .map_err(|err| IndexStoreError::Write(err.into()))?;
         Ok(Box::new(index))
     }
+
+    async fn reindex_commits_at_op(
+        &self,
+        op: &Operation,
+        store: &Arc<Store>,
+        commit_ids: &[CommitId],
+    ) -> IndexStoreResult<Box<dyn ReadonlyIndex>> {
+        let index = self
+            .reindex_commits_at_operation(op, store, commit_ids)
+            .await
+            .map_err(|err| IndexStoreError::Write(err.into()))?;
+        Ok(Box::new(index))
+    }
 }
 
 /// Progress of [`DefaultIndexStore::build_changed_path_index_at_operation()`].
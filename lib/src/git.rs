@@ -16,20 +16,28 @@
 
 use std::borrow::Borrow;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::ffi::OsString;
+use std::fs;
 use std::fs::File;
 use std::iter;
+use std::mem;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use bstr::BStr;
 use bstr::BString;
+use bstr::ByteSlice as _;
+use futures::AsyncReadExt as _;
 use futures::StreamExt as _;
 use futures::TryStreamExt as _;
+use gix::objs::Write as _;
 use gix::refspec::Instruction;
 use itertools::Itertools as _;
 use thiserror::Error;
@@ -37,12 +45,17 @@ use thiserror::Error;
 use crate::backend::BackendError;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
+use crate::backend::CopyId;
+use crate::backend::FileId;
+use crate::backend::Signature;
+use crate::backend::SymlinkId;
 use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::git_backend::GitBackend;
+use crate::git_backend::signature_to_git;
 use crate::git_subprocess::GitFetchStatus;
 pub use crate::git_subprocess::GitProgress;
 pub use crate::git_subprocess::GitSidebandLineTerminator;
@@ -50,10 +63,13 @@ pub use crate::git_subprocess::GitSubprocessCallback;
 use crate::git_subprocess::GitSubprocessContext;
 use crate::git_subprocess::GitSubprocessError;
 use crate::index::IndexError;
+use crate::index::IndexStoreError;
 use crate::matchers::EverythingMatcher;
 use crate::merge::Diff;
+use crate::merge::Merge;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::TreeDiffEntry;
+use crate::merged_tree_builder::MergedTreeBuilder;
 use crate::object_id::ObjectId as _;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt as _;
@@ -70,15 +86,20 @@ use crate::ref_name::RemoteRefSymbolBuf;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
 use crate::revset::RevsetStreamExt as _;
 use crate::settings::UserSettings;
+use crate::signing::SignError;
+use crate::signing::Verification;
 use crate::store::Store;
 use crate::str_util::StringExpression;
 use crate::str_util::StringMatcher;
 use crate::str_util::StringPattern;
+use crate::trailer::parse_description_trailers;
+use crate::trailer::parse_trailers;
 use crate::view::View;
 
 /// Reserved remote name for the backing Git repo.
@@ -89,6 +110,8 @@ pub const RESERVED_REMOTE_REF_NAMESPACE: &str = "refs/remotes/git/";
 const REMOTE_BOOKMARK_REF_NAMESPACE: &str = "refs/remotes/";
 /// Git ref prefix where remote tags will be temporarily fetched.
 const REMOTE_TAG_REF_NAMESPACE: &str = "refs/jj/remote-tags/";
+/// Git ref prefix where heads of pull requests are fetched.
+const PULL_REQUEST_REF_NAMESPACE: &str = "refs/jj/pull/";
 /// Ref name used as a placeholder to unset HEAD without a commit.
 const UNBORN_ROOT_REF_NAME: &str = "refs/jj/root";
 /// Dummy file to be added to the index to indicate that the user is editing a
@@ -99,8 +122,12 @@ const INDEX_DUMMY_CONFLICT_FILE: &str = ".jj-do-not-resolve-this-conflict";
 pub struct GitSettings {
     pub abandon_unreachable_commits: bool,
     pub executable_path: PathBuf,
+    pub export_attach_head: bool,
+    pub import_deleted_bookmark: GitImportDeletedBookmark,
     pub record_synthetic_predecessors: bool,
     pub write_change_id_header: bool,
+    pub write_change_id_trailer: bool,
+    pub write_intent_to_add: bool,
 }
 
 impl GitSettings {
@@ -108,9 +135,13 @@ impl GitSettings {
         Ok(Self {
             abandon_unreachable_commits: settings.get_bool("git.abandon-unreachable-commits")?,
             executable_path: settings.get("git.executable-path")?,
+            export_attach_head: settings.get_bool("git.export.attach-head")?,
+            import_deleted_bookmark: settings.get("git.import-deleted-bookmark")?,
             record_synthetic_predecessors: settings
                 .get_bool("git.record-synthetic-predecessors")?,
             write_change_id_header: settings.get("git.write-change-id-header")?,
+            write_change_id_trailer: settings.get_bool("git.write-change-id-trailer")?,
+            write_intent_to_add: settings.get_bool("git.write-intent-to-add")?,
         })
     }
 
@@ -375,6 +406,29 @@ fn parse_remote_tag_ref(full_name: &GitRefName) -> Option<(GitRefKind, RemoteRef
     Some((GitRefKind::Tag, RemoteRefSymbol { name, remote }))
 }
 
+fn parse_pull_request_ref(full_name: &GitRefName) -> Option<(GitRefKind, RemoteRefSymbol<'_>)> {
+    let remote_and_name = full_name
+        .as_str()
+        .strip_prefix(PULL_REQUEST_REF_NAMESPACE)?;
+    let (remote, name) = remote_and_name.split_once('/')?;
+    if remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return None;
+    }
+    let name = RefName::new(name);
+    let remote = RemoteName::new(remote);
+    Some((GitRefKind::Bookmark, RemoteRefSymbol { name, remote }))
+}
+
+fn pull_request_ref_name(symbol: RemoteRefSymbol<'_>) -> GitRefNameBuf {
+    let RemoteRefSymbol { name, remote } = symbol;
+    format!(
+        "{PULL_REQUEST_REF_NAMESPACE}{remote}/{name}",
+        remote = remote.as_str(),
+        name = name.as_str()
+    )
+    .into()
+}
+
 fn to_git_ref_name(kind: GitRefKind, symbol: RemoteRefSymbol<'_>) -> Option<GitRefNameBuf> {
     let RemoteRefSymbol { name, remote } = symbol;
     let name = name.as_str();
@@ -489,6 +543,8 @@ pub enum GitImportError {
         #[source]
         err: BackendError,
     },
+    #[error("No Git worktree is registered at {}", .0.display())]
+    NoSuchWorktree(PathBuf),
     #[error("Ancestor of Git ref {symbol} is missing")]
     MissingRefAncestor {
         symbol: RemoteRefSymbolBuf,
@@ -500,6 +556,8 @@ pub enum GitImportError {
     #[error(transparent)]
     Index(#[from] IndexError),
     #[error(transparent)]
+    IndexStore(#[from] IndexStoreError),
+    #[error(transparent)]
     RevsetEvaluation(#[from] RevsetEvaluationError),
     #[error(transparent)]
     Git(Box<dyn std::error::Error + Send + Sync>),
@@ -518,10 +576,34 @@ impl GitImportError {
 pub struct GitImportOptions {
     /// Whether to abandon commits that became unreachable in Git.
     pub abandon_unreachable_commits: bool,
+    /// How to resolve a tracked bookmark that was deleted in Git while it was
+    /// moved locally.
+    pub import_deleted_bookmark: GitImportDeletedBookmark,
     /// Whether to generate synthetic predecessors for imported commits.
     pub record_synthetic_predecessors: bool,
     /// Per-remote patterns whether to track bookmarks automatically.
     pub remote_auto_track_bookmarks: HashMap<RemoteNameBuf, StringMatcher>,
+    /// Whether to skip new tracked bookmarks whose names clash with other
+    /// tracked bookmarks (e.g. `foo` and `foo/bar`.) If disabled, such
+    /// bookmarks are imported as-is, and will fail to export.
+    pub skip_clashing_bookmarks: bool,
+    /// Whether to leave existing local tags alone when the tracked remote tags
+    /// are moved or deleted. New local tags are still created.
+    pub keep_existing_tags: bool,
+}
+
+/// How to resolve a local bookmark that was moved in jj while the tracked
+/// remote bookmark was deleted in Git.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitImportDeletedBookmark {
+    /// Keep the local bookmark at its current target.
+    KeepLocal,
+    /// Delete the local bookmark.
+    Delete,
+    /// Record a change-delete conflict on the local bookmark.
+    #[default]
+    Conflict,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -563,6 +645,12 @@ pub struct GitImportStats {
     /// This list doesn't include refs that are supposed to be ignored, such as
     /// refs pointing to non-commit objects.
     pub failed_ref_names: Vec<BString>,
+    /// Git ref names that weren't imported because the bookmark names clash
+    /// with other tracked bookmarks, sorted by name.
+    ///
+    /// A bookmark name clashes if it looks like a parent directory of another
+    /// (e.g. `foo` and `foo/bar`.) Such bookmarks can't be exported to Git.
+    pub clashing_ref_names: Vec<GitRefNameBuf>,
 }
 
 #[derive(Debug)]
@@ -611,8 +699,13 @@ pub async fn import_some_refs(
 
     // Exclude real remote tags, which should never be updated by Git.
     let all_remote_tags = false;
-    let refs_to_import =
-        diff_refs_to_import(mut_repo.view(), &git_repo, all_remote_tags, git_ref_filter)?;
+    let refs_to_import = diff_refs_to_import(
+        mut_repo.view(),
+        &git_repo,
+        all_remote_tags,
+        &git_ref_filter,
+        |symbol| git_ref_filter(GitRefKind::Bookmark, symbol),
+    )?;
     import_refs_inner(mut_repo, refs_to_import, options).await
 }
 
@@ -625,11 +718,21 @@ async fn import_refs_inner(
     let git_backend = get_git_backend(store).expect("backend type should have been tested");
 
     let RefsToImport {
-        changed_git_refs,
-        changed_remote_bookmarks,
+        mut changed_git_refs,
+        mut changed_remote_bookmarks,
         changed_remote_tags,
         failed_ref_names,
     } = refs_to_import;
+    let clashing_ref_names = if options.skip_clashing_bookmarks {
+        exclude_clashing_bookmarks(
+            mut_repo.view(),
+            &mut changed_git_refs,
+            &mut changed_remote_bookmarks,
+            options,
+        )
+    } else {
+        vec![]
+    };
 
     let iter_changed_refs = || itertools::chain(&changed_remote_bookmarks, &changed_remote_tags);
     // List of changed old/new ref heads, which may include duplicates.
@@ -692,6 +795,8 @@ async fn import_refs_inner(
     for (full_name, new_target) in changed_git_refs {
         mut_repo.set_git_ref_target(&full_name, new_target);
     }
+    // Commits of deleted local bookmarks that shouldn't be abandoned.
+    let mut deleted_local_ids = Vec::new();
     for update in &changed_remote_bookmarks {
         let symbol = update.symbol.as_ref();
         let base_target = update.old_remote_ref.tracked_target();
@@ -704,7 +809,22 @@ async fn import_refs_inner(
             },
         };
         if new_remote_ref.is_tracked() {
+            let old_local_target = mut_repo.get_local_bookmark(symbol.name);
             mut_repo.merge_local_bookmark(symbol.name, base_target, &new_remote_ref.target)?;
+            let is_new_conflict = !old_local_target.has_conflict()
+                && mut_repo.get_local_bookmark(symbol.name).has_conflict();
+            if new_remote_ref.target.is_absent() && is_new_conflict {
+                match options.import_deleted_bookmark {
+                    GitImportDeletedBookmark::KeepLocal => {
+                        mut_repo.set_local_bookmark_target(symbol.name, old_local_target);
+                    }
+                    GitImportDeletedBookmark::Delete => {
+                        deleted_local_ids.extend(old_local_target.added_ids().cloned());
+                        mut_repo.set_local_bookmark_target(symbol.name, RefTarget::absent());
+                    }
+                    GitImportDeletedBookmark::Conflict => {}
+                }
+            }
         }
         // Remote-tracking branch is the last known state of the branch in the remote.
         // It shouldn't diverge even if we had inconsistent view.
@@ -721,7 +841,9 @@ async fn import_refs_inner(
                 default_remote_ref_state_for(GitRefKind::Tag, symbol, options)
             },
         };
-        if new_remote_ref.is_tracked() {
+        let keep_local_tag =
+            options.keep_existing_tags && mut_repo.get_local_tag(symbol.name).is_present();
+        if new_remote_ref.is_tracked() && !keep_local_tag {
             mut_repo.merge_local_tag(symbol.name, base_target, &new_remote_ref.target)?;
         }
         // Remote-tracking tag is the last known state of the tag in the remote.
@@ -735,7 +857,7 @@ async fn import_refs_inner(
     let old_referenced_heads = RevsetExpression::commits(old_referenced_heads);
     let new_referenced_heads = RevsetExpression::commits(new_referenced_heads);
     let mut abandoned_commits = if options.abandon_unreachable_commits && any_old_referenced {
-        abandon_unreachable_commits(mut_repo, &old_referenced_heads).await?
+        abandon_unreachable_commits(mut_repo, &old_referenced_heads, deleted_local_ids).await?
     } else {
         vec![]
     };
@@ -760,19 +882,100 @@ async fn import_refs_inner(
         changed_remote_bookmarks,
         changed_remote_tags,
         failed_ref_names,
+        clashing_ref_names,
     };
     Ok(stats)
 }
 
+/// Removes new bookmarks which can't coexist with the other tracked bookmarks
+/// from the refs to be imported. Returns the Git ref names of the removed
+/// bookmarks.
+///
+/// Changes to the existing remote bookmarks are kept as-is. New bookmarks are
+/// checked in `changed_remote_bookmarks` order, so `foo` wins over `foo/bar`.
+fn exclude_clashing_bookmarks(
+    view: &View,
+    changed_git_refs: &mut Vec<(GitRefNameBuf, RefTarget)>,
+    changed_remote_bookmarks: &mut Vec<GitImportRefUpdate>,
+    options: &GitImportOptions,
+) -> Vec<GitRefNameBuf> {
+    let is_new_tracked = |update: &GitImportRefUpdate| {
+        update.old_remote_ref.target.is_absent()
+            && update.new_target.is_present()
+            && if update.old_remote_ref != *RemoteRef::absent_ref() {
+                update.old_remote_ref.is_tracked()
+            } else {
+                let symbol = update.symbol.as_ref();
+                default_remote_ref_state_for(GitRefKind::Bookmark, symbol, options)
+                    == RemoteRefState::Tracked
+            }
+    };
+    // Tracked bookmarks which will be present after import, excluding new ones.
+    let mut tracked_symbols: BTreeSet<RemoteRefSymbol> = view
+        .all_remote_bookmarks()
+        .filter(|(_, remote_ref)| remote_ref.is_tracked() && remote_ref.is_present())
+        .map(|(symbol, _)| symbol)
+        .collect();
+    for update in changed_remote_bookmarks.iter() {
+        if update.new_target.is_absent() {
+            tracked_symbols.remove(&update.symbol.as_ref());
+        }
+    }
+    let mut tracked_names: BTreeSet<&RefName> =
+        tracked_symbols.iter().map(|symbol| symbol.name).collect();
+    let mut clashing_symbols = HashSet::new();
+    for update in changed_remote_bookmarks.iter() {
+        if !is_new_tracked(update) {
+            continue;
+        }
+        let name = &*update.symbol.name;
+        if tracked_names
+            .iter()
+            .any(|other| bookmark_names_clash(name, other))
+        {
+            clashing_symbols.insert(update.symbol.clone());
+        } else {
+            tracked_names.insert(name);
+        }
+    }
+    if clashing_symbols.is_empty() {
+        return vec![];
+    }
+
+    changed_remote_bookmarks.retain(|update| !clashing_symbols.contains(&update.symbol));
+    let mut clashing_ref_names = clashing_symbols
+        .iter()
+        .filter_map(|symbol| to_git_ref_name(GitRefKind::Bookmark, symbol.as_ref()))
+        .collect_vec();
+    clashing_ref_names.sort_unstable();
+    changed_git_refs.retain(|(full_name, _)| !clashing_ref_names.contains(full_name));
+    clashing_ref_names
+}
+
+/// Returns true if one of the bookmark names looks like a parent directory of
+/// the other (e.g. `foo` and `foo/bar`.)
+///
+/// Such bookmarks can't be stored as Git branches at the same time.
+pub fn bookmark_names_clash(a: &RefName, b: &RefName) -> bool {
+    let is_parent_of = |parent: &str, child: &str| {
+        child
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+    is_parent_of(a.as_str(), b.as_str()) || is_parent_of(b.as_str(), a.as_str())
+}
+
 /// Finds commits that used to be reachable in git that no longer are reachable.
 /// Those commits will be recorded as abandoned in the `MutableRepo`.
 async fn abandon_unreachable_commits(
     mut_repo: &mut MutableRepo,
     hidable_git_heads: &Arc<ResolvedRevsetExpression>,
+    extra_pinned_ids: Vec<CommitId>,
 ) -> Result<Vec<Commit>, GitImportError> {
     let pinned_expression = RevsetExpression::union_all(&[
         // Local refs are usually visible, no need to filter out hidden
         RevsetExpression::commits(pinned_commit_ids(mut_repo.view())),
+        RevsetExpression::commits(extra_pinned_ids),
         RevsetExpression::commits(remotely_pinned_commit_ids(mut_repo.view()))
             // Hidden remote refs should not contribute to pinning
             .intersection(&RevsetExpression::visible_heads().ancestors()),
@@ -889,20 +1092,35 @@ fn diff_refs_to_import(
     git_repo: &gix::Repository,
     all_remote_tags: bool,
     git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+    pull_request_filter: impl Fn(RemoteRefSymbol<'_>) -> bool,
 ) -> Result<RefsToImport, GitImportError> {
+    // Remote bookmarks imported from pull request heads are filtered
+    // separately from the remote-tracking branches.
+    let known_pull_requests: HashSet<RemoteRefSymbol> = view
+        .git_refs()
+        .keys()
+        .filter_map(|full_name| parse_pull_request_ref(full_name))
+        .map(|(_, symbol)| symbol)
+        .collect();
     let mut known_git_refs = view
         .git_refs()
         .iter()
         .filter_map(|(full_name, target)| {
+            if let Some((_, symbol)) = parse_pull_request_ref(full_name) {
+                return pull_request_filter(symbol).then_some((full_name.as_ref(), target));
+            }
             // TODO: or clean up invalid ref in case it was stored due to historical bug?
             let (kind, symbol) =
                 parse_git_ref(full_name).expect("stored git ref should be parsable");
             git_ref_filter(kind, symbol).then_some((full_name.as_ref(), target))
         })
         .collect();
-    let mut known_remote_bookmarks = view
+    let mut known_remote_bookmarks: HashMap<RemoteRefKey, &RemoteRef> = view
         .all_remote_bookmarks()
-        .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Bookmark, symbol))
+        .filter(|&(symbol, _)| {
+            git_ref_filter(GitRefKind::Bookmark, symbol)
+                || known_pull_requests.contains(&symbol) && pull_request_filter(symbol)
+        })
         .map(|(symbol, remote_ref)| (RemoteRefKey(symbol), remote_ref))
         .collect();
     let mut known_remote_tags = if all_remote_tags {
@@ -930,6 +1148,7 @@ fn diff_refs_to_import(
     let actual = git_repo.references().map_err(GitImportError::from_git)?;
     collect_changed_refs_to_import(
         actual.local_branches().map_err(GitImportError::from_git)?,
+        parse_git_ref,
         &mut known_git_refs,
         &mut known_remote_bookmarks,
         &mut changed_git_refs,
@@ -939,6 +1158,7 @@ fn diff_refs_to_import(
     )?;
     collect_changed_refs_to_import(
         actual.remote_branches().map_err(GitImportError::from_git)?,
+        parse_git_ref,
         &mut known_git_refs,
         &mut known_remote_bookmarks,
         &mut changed_git_refs,
@@ -948,6 +1168,7 @@ fn diff_refs_to_import(
     )?;
     collect_changed_refs_to_import(
         actual.tags().map_err(GitImportError::from_git)?,
+        parse_git_ref,
         &mut known_git_refs,
         &mut known_remote_tags,
         &mut changed_git_refs,
@@ -956,20 +1177,60 @@ fn diff_refs_to_import(
         &git_ref_filter,
     )?;
     if all_remote_tags {
-        collect_changed_remote_tags_to_import(
+        collect_changed_jj_managed_refs_to_import(
             actual
                 .prefixed(REMOTE_TAG_REF_NAMESPACE)
                 .map_err(GitImportError::from_git)?,
+            parse_remote_tag_ref,
             &mut known_remote_tags,
             &mut changed_remote_tags,
             &mut failed_ref_names,
             &git_ref_filter,
         )?;
     }
+    // A pull request head shares the bookmark name with a remote-tracking
+    // branch "pr/<number>" if the remote has one. The branch takes precedence
+    // so the remote bookmark doesn't flip between the two.
+    let colliding_pull_requests: HashSet<GitRefNameBuf> = actual
+        .prefixed(PULL_REQUEST_REF_NAMESPACE)
+        .map_err(GitImportError::from_git)?
+        .filter_map(|git_ref| {
+            let git_ref = git_ref.ok()?;
+            let full_name = GitRefName::new(str::from_utf8(git_ref.name().as_bstr()).ok()?);
+            let (_, symbol) = parse_pull_request_ref(full_name)?;
+            let branch_name = to_git_ref_name(GitRefKind::Bookmark, symbol)?;
+            git_repo
+                .try_find_reference(branch_name.as_str())
+                .ok()?
+                .map(|_| full_name.to_owned())
+        })
+        .collect();
+    for full_name in &colliding_pull_requests {
+        failed_ref_names.push(full_name.as_str().into());
+    }
+    collect_changed_refs_to_import(
+        actual
+            .prefixed(PULL_REQUEST_REF_NAMESPACE)
+            .map_err(GitImportError::from_git)?,
+        parse_pull_request_ref,
+        &mut known_git_refs,
+        &mut known_remote_bookmarks,
+        &mut changed_git_refs,
+        &mut changed_remote_bookmarks,
+        &mut failed_ref_names,
+        |_, symbol| {
+            pull_request_filter(symbol)
+                && !colliding_pull_requests.contains(&pull_request_ref_name(symbol))
+        },
+    )?;
     for full_name in known_git_refs.into_keys() {
         changed_git_refs.push((full_name.to_owned(), RefTarget::absent()));
     }
     for (RemoteRefKey(symbol), old) in known_remote_bookmarks {
+        // Pull request heads which weren't fetched are kept.
+        if known_pull_requests.contains(&symbol) && !pull_request_filter(symbol) {
+            continue;
+        }
         if old.is_present() {
             changed_remote_bookmarks.push(GitImportRefUpdate::new(
                 symbol.to_owned(),
@@ -1002,8 +1263,10 @@ fn diff_refs_to_import(
     })
 }
 
+#[expect(clippy::too_many_arguments)]
 fn collect_changed_refs_to_import(
     actual_git_refs: gix::reference::iter::Iter,
+    parse_ref: fn(&GitRefName) -> Option<(GitRefKind, RemoteRefSymbol<'_>)>,
     known_git_refs: &mut HashMap<&GitRefName, &RefTarget>,
     known_remote_refs: &mut HashMap<RemoteRefKey<'_>, &RemoteRef>,
     changed_git_refs: &mut Vec<(GitRefNameBuf, RefTarget)>,
@@ -1024,7 +1287,7 @@ fn collect_changed_refs_to_import(
             continue;
         }
         let full_name = GitRefName::new(full_name);
-        let Some((kind, symbol)) = parse_git_ref(full_name) else {
+        let Some((kind, symbol)) = parse_ref(full_name) else {
             // Skip special refs such as refs/remotes/*/HEAD.
             continue;
         };
@@ -1058,10 +1321,66 @@ fn collect_changed_refs_to_import(
     Ok(())
 }
 
+/// Returns the names of loose bookmark and tag refs which Git considers
+/// invalid (e.g. `refs/heads/foo..bar` or `refs/heads/a@{b`.)
+///
+/// Such refs are skipped by the ref iterator, and therefore by
+/// [`import_refs()`], so they have to be looked up in the file system. This is
+/// relatively expensive, and should only be called when the user explicitly
+/// imports refs. Names which aren't valid UTF-8 are returned as is.
+pub fn find_invalid_loose_ref_names(
+    store: &Store,
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+) -> Result<Vec<BString>, UnexpectedGitBackendError> {
+    fn walk(dir: &Path, prefix: &BStr, names: &mut Vec<BString>) {
+        let Ok(entries) = dir.read_dir() else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_name) = gix::path::os_string_into_bstring(entry.file_name()) else {
+                continue;
+            };
+            let mut name = prefix.to_owned();
+            name.push(b'/');
+            name.extend_from_slice(&file_name);
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => walk(&entry.path(), name.as_ref(), names),
+                Ok(file_type) if file_type.is_file() => names.push(name),
+                _ => {}
+            }
+        }
+    }
+
+    let git_repo = get_git_repo(store)?;
+    let mut names = Vec::new();
+    for prefix in ["refs/heads", "refs/remotes", "refs/tags"] {
+        walk(
+            &git_repo.common_dir().join(prefix),
+            prefix.into(),
+            &mut names,
+        );
+    }
+    names.retain(|name| {
+        // Lock files are created while refs are being updated
+        if name.ends_with(b".lock") || gix::refs::FullName::try_from(name.as_bstr()).is_ok() {
+            return false;
+        }
+        let Ok(name) = str::from_utf8(name) else {
+            // Non-utf8 refs cannot be imported.
+            return true;
+        };
+        parse_git_ref(GitRefName::new(name))
+            .is_some_and(|(kind, symbol)| git_ref_filter(kind, symbol))
+    });
+    names.sort_unstable();
+    Ok(names)
+}
+
 /// Similar to [`collect_changed_refs_to_import()`], but doesn't track Git ref
-/// changes. Remote tags should be managed solely by jj.
-fn collect_changed_remote_tags_to_import(
+/// changes. Remote tags and pull request heads should be managed solely by jj.
+fn collect_changed_jj_managed_refs_to_import(
     actual_git_refs: gix::reference::iter::Iter,
+    parse_ref: fn(&GitRefName) -> Option<(GitRefKind, RemoteRefSymbol<'_>)>,
     known_remote_refs: &mut HashMap<RemoteRefKey<'_>, &RemoteRef>,
     changed_remote_refs: &mut Vec<GitImportRefUpdate>,
     failed_ref_names: &mut Vec<BString>,
@@ -1076,7 +1395,7 @@ fn collect_changed_remote_tags_to_import(
             continue;
         };
         let full_name = GitRefName::new(full_name);
-        let Some((kind, symbol)) = parse_remote_tag_ref(full_name) else {
+        let Some((kind, symbol)) = parse_ref(full_name) else {
             // Skip invalid ref names.
             continue;
         };
@@ -1179,30 +1498,141 @@ pub async fn import_head(mut_repo: &mut MutableRepo) -> Result<(), GitImportErro
 
     // Import new head
     if let Some(head_id) = &new_git_head_id {
-        let index = mut_repo.index();
-        if !index.has_id(head_id)? {
-            git_backend.import_head_commits([head_id]).map_err(|err| {
-                GitImportError::MissingHeadTarget {
-                    id: head_id.clone(),
-                    err,
-                }
-            })?;
-        }
-        // It's unlikely the imported commits were missing, but I/O-related
-        // error can still occur.
-        let commit = store.get_commit_async(head_id).await?;
-        mut_repo.add_head(&commit).await?;
+        import_head_commit(mut_repo, head_id).await?;
     }
 
     mut_repo.set_git_head_target(RefTarget::resolved(new_git_head_id));
     Ok(())
 }
 
+/// Imports the HEAD commit of the Git worktree checked out at `worktree_root`.
+///
+/// If `worktree_root` is the main worktree of the Git repo, the Git HEAD
+/// recorded in the view is updated as well. Since only one Git HEAD can be
+/// recorded, the HEAD of a linked worktree is imported without touching the
+/// recorded one.
+///
+/// Returns the HEAD commit, or `None` if the HEAD is unborn.
+pub async fn import_worktree_head(
+    mut_repo: &mut MutableRepo,
+    worktree_root: &Path,
+) -> Result<Option<Commit>, GitImportError> {
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let canonicalize = |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let worktree_root = canonicalize(worktree_root);
+    if git_repo.workdir().map(canonicalize).as_ref() == Some(&worktree_root) {
+        import_head(mut_repo).await?;
+        let head_id = mut_repo.view().git_head().as_normal().cloned();
+        return match head_id {
+            Some(id) => Ok(Some(mut_repo.store().get_commit_async(&id).await?)),
+            None => Ok(None),
+        };
+    }
+
+    let worktree_repo = find_linked_worktree_repo(&git_repo, &worktree_root)?
+        .ok_or_else(|| GitImportError::NoSuchWorktree(worktree_root.clone()))?;
+    let Ok(oid) = worktree_repo.head_id() else {
+        return Ok(None);
+    };
+    let head_id = CommitId::from_bytes(oid.as_bytes());
+    Ok(Some(import_head_commit(mut_repo, &head_id).await?))
+}
+
+/// Returns true if `workspace_root` is checked out as a linked worktree of the
+/// Git repo.
+///
+/// A linked worktree has its own HEAD and per-worktree refs, which shouldn't
+/// be imported as the Git HEAD of the main worktree.
+pub fn is_linked_git_worktree(
+    store: &Store,
+    workspace_root: &Path,
+) -> Result<bool, GitImportError> {
+    let git_repo = get_git_repo(store)?;
+    let workspace_root =
+        dunce::canonicalize(workspace_root).unwrap_or_else(|_| workspace_root.to_owned());
+    Ok(find_linked_worktree_repo(&git_repo, &workspace_root)?.is_some())
+}
+
+/// Opens the linked worktree checked out at the canonicalized
+/// `worktree_root`.
+fn find_linked_worktree_repo(
+    git_repo: &gix::Repository,
+    worktree_root: &Path,
+) -> Result<Option<gix::Repository>, GitImportError> {
+    let canonicalize = |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let worktrees = git_repo.worktrees().map_err(GitImportError::from_git)?;
+    let worktree_repo = worktrees
+        .into_iter()
+        .filter(|worktree| {
+            // The "gitdir" file may be missing if the worktree is corrupted
+            worktree
+                .base()
+                .is_ok_and(|base| canonicalize(&base) == worktree_root)
+        })
+        .find_map(|worktree| {
+            worktree
+                .into_repo_with_possibly_inaccessible_worktree()
+                .ok()
+        });
+    Ok(worktree_repo)
+}
+
+/// Returns the local bookmark name the Git HEAD symbolically points to.
+///
+/// Returns `None` if the HEAD is detached or unborn, or if the Git repo has no
+/// working tree, i.e. the HEAD isn't managed by jj.
+pub fn get_head_bookmark_name(store: &Store) -> Result<Option<RefNameBuf>, GitImportError> {
+    let git_backend = get_git_backend(store)?;
+    if git_backend.git_workdir().is_none() {
+        return Ok(None);
+    }
+    let git_repo = git_backend.git_repo();
+    let head = git_repo.head().map_err(GitImportError::from_git)?;
+    let gix::head::Kind::Symbolic(git_ref) = head.kind else {
+        return Ok(None);
+    };
+    let Ok(full_name) = str::from_utf8(git_ref.name.as_bstr()) else {
+        return Ok(None);
+    };
+    match parse_git_ref(GitRefName::new(full_name)) {
+        Some((GitRefKind::Bookmark, symbol)) if symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO => {
+            Ok(Some(symbol.name.to_owned()))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn import_head_commit(
+    mut_repo: &mut MutableRepo,
+    head_id: &CommitId,
+) -> Result<Commit, GitImportError> {
+    let store = mut_repo.store().clone();
+    let index = mut_repo.index();
+    if !index.has_id(head_id)? {
+        let git_backend = get_git_backend(&store)?;
+        git_backend.import_head_commits([head_id]).map_err(|err| {
+            GitImportError::MissingHeadTarget {
+                id: head_id.clone(),
+                err,
+            }
+        })?;
+    }
+    // It's unlikely the imported commits were missing, but I/O-related
+    // error can still occur.
+    let commit = store.get_commit_async(head_id).await?;
+    mut_repo.add_head(&commit).await?;
+    Ok(commit)
+}
+
 #[derive(Error, Debug)]
 pub enum GitExportError {
+    #[error(transparent)]
+    Backend(#[from] BackendError),
     #[error(transparent)]
     Git(Box<dyn std::error::Error + Send + Sync>),
     #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+    #[error(transparent)]
     UnexpectedBackend(#[from] UnexpectedGitBackendError),
 }
 
@@ -1222,9 +1652,16 @@ pub enum FailedRefExportReason {
     /// should fix it.
     #[error("Ref was in a conflicted state from the last import")]
     ConflictedOldState,
+    /// The ref is conflicted in jj, so it has no single target to export.
+    #[error("Ref is conflicted")]
+    ConflictedNewState,
     /// The ref points to the root commit, which Git doesn't have.
     #[error("Ref cannot point to the root commit in Git")]
     OnRootCommit,
+    /// The ref points to a hidden commit, which shouldn't be made reachable
+    /// in Git.
+    #[error("Ref points to a hidden commit")]
+    OnHiddenCommit,
     /// We wanted to delete it, but it had been modified in Git.
     #[error("Deleted ref had been modified in Git")]
     DeletedInJjModifiedInGit,
@@ -1284,9 +1721,36 @@ pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<GitExportStats, GitExpo
     export_some_refs(mut_repo, |_, _| true)
 }
 
+/// Like [`export_refs()`], but writes all ref changes to Git in a single
+/// transaction.
+///
+/// If any ref can't be exported, no ref is written, and the refs that would
+/// fail are reported in the returned stats. If the transaction itself fails,
+/// all refs to be exported are reported as failed.
+pub fn export_refs_atomic(mut_repo: &mut MutableRepo) -> Result<GitExportStats, GitExportError> {
+    export_some_refs_atomic(mut_repo, |_, _| true)
+}
+
+/// Like [`export_some_refs()`], but writes all ref changes to Git in a single
+/// transaction. See [`export_refs_atomic()`] for details.
+pub fn export_some_refs_atomic(
+    mut_repo: &mut MutableRepo,
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+) -> Result<GitExportStats, GitExportError> {
+    export_some_refs_impl(mut_repo, git_ref_filter, true)
+}
+
 pub fn export_some_refs(
     mut_repo: &mut MutableRepo,
     git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+) -> Result<GitExportStats, GitExportError> {
+    export_some_refs_impl(mut_repo, git_ref_filter, false)
+}
+
+fn export_some_refs_impl(
+    mut_repo: &mut MutableRepo,
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+    atomic: bool,
 ) -> Result<GitExportStats, GitExportError> {
     fn get<'a, V>(map: &'a [(RemoteRefSymbolBuf, V)], key: RemoteRefSymbol<'_>) -> Option<&'a V> {
         debug_assert!(map.is_sorted_by_key(|(k, _)| k));
@@ -1295,80 +1759,34 @@ pub fn export_some_refs(
         Some(value)
     }
 
-    let AllRefsToExport { bookmarks, tags } = diff_refs_to_export(
-        mut_repo.view(),
-        mut_repo.store().root_commit_id(),
-        &git_ref_filter,
-    );
-
-    let check_and_detach_head = |git_repo: &gix::Repository| -> Result<(), GitExportError> {
-        let Ok(head_ref) = git_repo.find_reference("HEAD") else {
-            return Ok(());
-        };
-        let target_name = head_ref.target().try_name().map(|name| name.to_owned());
-        if let Some((kind, symbol)) = target_name
-            .as_ref()
-            .and_then(|name| str::from_utf8(name.as_bstr()).ok())
-            .and_then(|name| parse_git_ref(name.as_ref()))
-        {
-            let old_target = head_ref.inner.target.clone();
-            let current_oid = match head_ref.into_fully_peeled_id() {
-                Ok(id) => Some(id.detach()),
-                Err(gix::reference::peel::Error::ToId(
-                    gix::refs::peel::to_id::Error::FollowToObject(
-                        gix::refs::peel::to_object::Error::Follow(
-                            gix::refs::file::find::existing::Error::NotFound { .. },
-                        ),
-                    ),
-                )) => None, // Unborn ref should be considered absent
-                Err(err) => return Err(GitExportError::from_git(err)),
-            };
-            let refs = match kind {
-                GitRefKind::Bookmark => &bookmarks,
-                GitRefKind::Tag => &tags,
-            };
-            let new_oid = if let Some((_old_oid, new_oid)) = get(&refs.to_update, symbol) {
-                Some(new_oid)
-            } else if get(&refs.to_delete, symbol).is_some() {
-                None
-            } else {
-                current_oid.as_ref()
-            };
-            if new_oid != current_oid.as_ref() {
-                update_git_head(
-                    git_repo,
-                    gix::refs::transaction::PreviousValue::MustExistAndMatch(old_target),
-                    current_oid,
-                )
-                .map_err(GitExportError::from_git)?;
-            }
-        }
-        Ok(())
-    };
+    let AllRefsToExport { bookmarks, tags } = diff_refs_to_export(mut_repo, &git_ref_filter)?;
 
     let git_repo = get_git_repo(mut_repo.store())?;
+    let plan = plan_refs_export(mut_repo.view(), &git_repo, bookmarks, tags)?;
+    let GitExportStats {
+        failed_bookmarks,
+        failed_tags,
+    } = if atomic {
+        export_refs_to_git_atomically(mut_repo, &git_repo, plan)?
+    } else {
+        export_refs_to_git(mut_repo, &git_repo, plan)?
+    };
 
-    check_and_detach_head(&git_repo)?;
-    for worktree in git_repo.worktrees().map_err(GitExportError::from_git)? {
-        if let Ok(worktree_repo) = worktree.into_repo_with_possibly_inaccessible_worktree() {
-            check_and_detach_head(&worktree_repo)?;
-        }
-    }
-
-    let failed_bookmarks = export_refs_to_git(mut_repo, &git_repo, GitRefKind::Bookmark, bookmarks);
-    let failed_tags = export_refs_to_git(mut_repo, &git_repo, GitRefKind::Tag, tags);
-
+    // Nothing has been exported if the atomic export failed
+    let aborted = atomic && !(failed_bookmarks.is_empty() && failed_tags.is_empty());
     copy_exportable_local_bookmarks_to_remote_view(
         mut_repo,
         REMOTE_NAME_FOR_LOCAL_GIT_REPO,
         |name| {
             let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
-            git_ref_filter(GitRefKind::Bookmark, symbol) && get(&failed_bookmarks, symbol).is_none()
+            !aborted
+                && git_ref_filter(GitRefKind::Bookmark, symbol)
+                && get(&failed_bookmarks, symbol).is_none()
         },
     );
     copy_exportable_local_tags_to_remote_view(mut_repo, REMOTE_NAME_FOR_LOCAL_GIT_REPO, |name| {
         let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
-        git_ref_filter(GitRefKind::Tag, symbol) && get(&failed_tags, symbol).is_none()
+        !aborted && git_ref_filter(GitRefKind::Tag, symbol) && get(&failed_tags, symbol).is_none()
     });
 
     Ok(GitExportStats {
@@ -1377,83 +1795,851 @@ pub fn export_some_refs(
     })
 }
 
-fn export_refs_to_git(
-    mut_repo: &mut MutableRepo,
-    git_repo: &gix::Repository,
-    kind: GitRefKind,
-    refs: RefsToExport,
-) -> Vec<(RemoteRefSymbolBuf, FailedRefExportReason)> {
-    let mut failed = refs.failed;
-    for (symbol, old_oid) in refs.to_delete {
-        let Some(git_ref_name) = to_git_ref_name(kind, symbol.as_ref()) else {
-            failed.push((symbol, FailedRefExportReason::InvalidGitName));
-            continue;
-        };
-        if let Err(reason) = delete_git_ref(git_repo, &git_ref_name, &old_oid) {
-            failed.push((symbol, reason));
-        } else {
-            let new_target = RefTarget::absent();
-            mut_repo.set_git_ref_target(&git_ref_name, new_target);
-        }
-    }
-    for (symbol, (old_commit_oid, new_commit_oid)) in refs.to_update {
-        let Some(git_ref_name) = to_git_ref_name(kind, symbol.as_ref()) else {
-            failed.push((symbol, FailedRefExportReason::InvalidGitName));
-            continue;
-        };
-        let new_ref_oid = match kind {
-            GitRefKind::Bookmark => None,
-            // Copy existing tag ref, which may point to annotated tag object.
-            GitRefKind::Tag => {
-                let remote_matcher = StringMatcher::all();
-                find_git_tag_oid_to_copy(
-                    mut_repo.view(),
-                    git_repo,
-                    &symbol.name,
-                    &remote_matcher,
-                    &new_commit_oid,
-                )
-            }
-        };
-        if let Err(reason) = update_git_ref(
-            git_repo,
-            &git_ref_name,
-            old_commit_oid,
-            new_commit_oid,
-            new_ref_oid,
-        ) {
-            failed.push((symbol, reason));
-        } else {
-            let new_target = RefTarget::normal(CommitId::from_bytes(new_commit_oid.as_bytes()));
-            mut_repo.set_git_ref_target(&git_ref_name, new_target);
-        }
+/// Describes changes that [`export_refs()`] would make.
+#[derive(Debug)]
+pub struct GitExportPreview {
+    /// Git refs that would be created, updated, or deleted, in the order the
+    /// export would write them.
+    pub ref_changes: Vec<(GitRefNameBuf, Diff<Option<gix::ObjectId>>)>,
+    /// Git branches which the HEAD of the repo or its worktrees would be
+    /// detached from because the branches would be updated or deleted.
+    pub detached_head_refs: Vec<GitRefNameBuf>,
+    /// Remote bookmarks that couldn't be exported, sorted by `symbol`.
+    pub failed_bookmarks: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    /// Remote tags that couldn't be exported, sorted by `symbol`.
+    pub failed_tags: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+}
+
+/// Forgets the Git-tracking state of the local bookmark so the next export
+/// won't update or delete the corresponding Git branch.
+///
+/// The Git branch itself is left as is. If it still exists, the next import
+/// will bring it back as a new bookmark.
+pub fn forget_bookmark_git_tracking(mut_repo: &mut MutableRepo, name: &RefName) {
+    let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+    if let Some(git_ref_name) = to_git_ref_name(GitRefKind::Bookmark, symbol) {
+        mut_repo.set_git_ref_target(&git_ref_name, RefTarget::absent());
     }
+    mut_repo.set_remote_bookmark(symbol, RemoteRef::absent());
+}
 
-    // Stabilize output, allow binary search.
-    failed.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
-    failed
+/// Commits rewritten by [`add_change_id_trailers()`].
+#[derive(Clone, Debug, Default)]
+pub struct ChangeIdTrailerStats {
+    /// Number of commits the trailer was added to.
+    pub num_rewritten: usize,
+    /// Number of descendants rebased onto the rewritten commits.
+    pub num_rebased: usize,
+    /// Immutable commits which would need the trailer but were left alone.
+    pub skipped_immutable: Vec<CommitId>,
 }
 
-fn copy_exportable_local_bookmarks_to_remote_view(
+/// Adds `Change-Id` trailers to the descriptions of commits which are about to
+/// be exported to Git.
+///
+/// Commits reachable from the local bookmarks matching `git_ref_filter` are
+/// rewritten unless they have been exported or pushed already, or are in the
+/// `immutable` set. The trailer is rendered by `format_trailer()` from the
+/// current change id of each commit, so a trailer derived from another change
+/// id, which may have been copied by e.g. splitting a commit, is replaced.
+/// Commits with conflicts or empty descriptions, and `Change-Id` trailers set
+/// by the user, are left alone.
+///
+/// The descendants of the rewritten commits are rebased.
+pub async fn add_change_id_trailers(
     mut_repo: &mut MutableRepo,
-    remote: &RemoteName,
-    name_filter: impl Fn(&RefName) -> bool,
-) {
-    let new_local_bookmarks = mut_repo
-        .view()
-        .local_remote_bookmarks(remote)
-        .filter_map(|(name, targets)| {
-            // TODO: filter out untracked bookmarks (if we add support for untracked @git
-            // bookmarks)
-            let old_target = &targets.remote_ref.target;
-            let new_target = targets.local_target;
-            (!new_target.has_conflict() && old_target != new_target).then_some((name, new_target))
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+    immutable: &Arc<ResolvedRevsetExpression>,
+    format_trailer: impl Fn(&Commit) -> String,
+) -> Result<ChangeIdTrailerStats, GitExportError> {
+    let view = mut_repo.view();
+    let bookmark_ids = view
+        .local_bookmarks()
+        .filter(|(name, _)| {
+            let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+            git_ref_filter(GitRefKind::Bookmark, symbol)
         })
-        .filter(|&(name, _)| name_filter(name))
-        .map(|(name, new_target)| (name.to_owned(), new_target.clone()))
+        .flat_map(|(_, target)| target.added_ids())
+        .cloned()
         .collect_vec();
-    for (name, new_target) in new_local_bookmarks {
-        let new_remote_ref = RemoteRef {
+    let exported_ids = itertools::chain(
+        view.git_refs().values(),
+        view.all_remote_bookmarks()
+            .map(|(_, remote_ref)| &remote_ref.target),
+    )
+    .flat_map(|target| target.added_ids())
+    .cloned()
+    .collect_vec();
+    let to_export = RevsetExpression::commits(exported_ids)
+        .union(&RevsetExpression::root())
+        .range(&RevsetExpression::commits(bookmark_ids));
+    let immutable_ids: HashSet<CommitId> = to_export
+        .intersection(immutable)
+        .evaluate(mut_repo)?
+        .stream()
+        .try_collect()
+        .await?;
+    let commits: Vec<Commit> = to_export
+        .evaluate(mut_repo)?
+        .stream()
+        .commits(mut_repo.store())
+        .try_collect()
+        .await?;
+
+    let mut stats = ChangeIdTrailerStats::default();
+    let mut new_descriptions = HashMap::new();
+    for commit in commits {
+        if commit.has_conflict() {
+            continue;
+        }
+        if let Some(description) = description_with_change_id_trailer(
+            commit.description(),
+            commit.change_id(),
+            &format_trailer(&commit),
+        ) {
+            if immutable_ids.contains(commit.id()) {
+                stats.skipped_immutable.push(commit.id().clone());
+            } else {
+                new_descriptions.insert(commit.id().clone(), description);
+            }
+        }
+    }
+    stats.num_rewritten = new_descriptions.len();
+    mut_repo
+        .transform_descendants(
+            new_descriptions.keys().cloned().collect(),
+            async |rewriter| {
+                let new_description = new_descriptions.get(rewriter.old_commit().id());
+                let commit_builder = rewriter.reparent();
+                if let Some(description) = new_description {
+                    commit_builder.set_description(description).write().await?;
+                } else {
+                    commit_builder.write().await?;
+                    stats.num_rebased += 1;
+                }
+                Ok(())
+            },
+        )
+        .await?;
+    Ok(stats)
+}
+
+/// Returns the `description` with the `Change-Id` trailer of the rendered
+/// `trailer_text`, or `None` if the description needn't be updated.
+fn description_with_change_id_trailer(
+    description: &str,
+    change_id: &ChangeId,
+    trailer_text: &str,
+) -> Option<String> {
+    if description.is_empty() {
+        return None;
+    }
+    let new_trailer = parse_trailers(trailer_text)
+        .ok()?
+        .into_iter()
+        .find(|trailer| trailer.key == "Change-Id")?;
+    let trailers = parse_description_trailers(description);
+    let Some(old_trailer) = trailers.iter().find(|trailer| trailer.key == "Change-Id") else {
+        return Some(format!(
+            "{}{}Change-Id: {}\n",
+            description.trim_end(),
+            if trailers.is_empty() { "\n\n" } else { "\n" },
+            new_trailer.value,
+        ));
+    };
+    if old_trailer.value == new_trailer.value {
+        return None;
+    }
+    // Replace the trailer only if it is rendered from another change id.
+    let (prefix, suffix) = new_trailer.value.split_once(&change_id.hex())?;
+    let old_hex = old_trailer
+        .value
+        .strip_prefix(prefix)?
+        .strip_suffix(suffix)?;
+    if old_hex.len() != change_id.hex().len() || !old_hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(description.replacen(
+        &format!("Change-Id: {}", old_trailer.value),
+        &format!("Change-Id: {}", new_trailer.value),
+        1,
+    ))
+}
+
+/// Makes the Git HEAD a symbolic ref to the Git branch if
+/// `git.export.attach-head` is enabled and exactly one local bookmark pointing
+/// to the `commit_id` has been exported to Git.
+///
+/// Returns the name of the bookmark the HEAD is attached to. The Git HEAD
+/// recorded in the view is updated to the `commit_id` so the next import won't
+/// see the change.
+pub fn attach_head_to_exported_bookmark(
+    mut_repo: &mut MutableRepo,
+    commit_id: &CommitId,
+) -> Result<Option<RefNameBuf>, GitExportError> {
+    let git_backend = get_git_backend(mut_repo.store())?;
+    let Some((name, branch)) =
+        find_exported_bookmark_to_check_out(git_backend, mut_repo.view(), commit_id)
+    else {
+        return Ok(None);
+    };
+    let name = name.to_owned();
+    let git_repo = git_backend.git_repo();
+    update_git_head(
+        &git_repo,
+        gix::refs::transaction::PreviousValue::Any,
+        Some(owned_oid_from_commit_id(commit_id)),
+        Some(branch),
+        None,
+    )
+    .map_err(GitExportError::from_git)?;
+    mut_repo.set_git_head_target(RefTarget::normal(commit_id.clone()));
+    Ok(Some(name))
+}
+
+/// Computes the changes [`export_refs()`] would make without writing to the
+/// Git repo.
+///
+/// Refs that already point to the desired target in Git are counted as
+/// exported, but are not included in the ref changes.
+pub fn preview_export_refs(repo: &dyn Repo) -> Result<GitExportPreview, GitExportError> {
+    preview_export_some_refs(repo, |_, _| true)
+}
+
+/// Computes the changes [`export_some_refs()`] would make without writing to
+/// the Git repo.
+pub fn preview_export_some_refs(
+    repo: &dyn Repo,
+    git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
+) -> Result<GitExportPreview, GitExportError> {
+    let AllRefsToExport { bookmarks, tags } = diff_refs_to_export(repo, git_ref_filter)?;
+    let git_repo = get_git_repo(repo.store())?;
+    let plan = plan_refs_export(repo.view(), &git_repo, bookmarks, tags)?;
+    Ok(GitExportPreview {
+        ref_changes: plan
+            .ref_edits
+            .into_iter()
+            .map(|planned| (planned.git_ref_name, planned.diff))
+            .collect(),
+        detached_head_refs: plan.detached_head_refs,
+        failed_bookmarks: plan.failed_bookmarks,
+        failed_tags: plan.failed_tags,
+    })
+}
+
+/// Exports local bookmarks to the Git repository at `git_repo_path`, which
+/// isn't the backing repository of `repo`.
+///
+/// Objects reachable from the exported bookmarks are copied to the target
+/// repository if missing. Existing branches are overwritten, but branches of
+/// deleted bookmarks are left as is. Conflicted bookmarks are reported as
+/// failed. Both repositories must use the same object hash. Unlike
+/// [`export_refs()`], the view isn't updated since the target repository isn't
+/// tracked by jj.
+pub fn export_refs_to_repo(
+    repo: &dyn Repo,
+    git_repo_path: &Path,
+    bookmark_filter: impl Fn(&RefName) -> bool,
+) -> Result<GitExportStats, GitExportError> {
+    let source_repo = get_git_repo(repo.store())?;
+    let target_repo = gix::open(git_repo_path).map_err(GitExportError::from_git)?;
+    if target_repo.object_hash() != source_repo.object_hash() {
+        return Err(GitExportError::from_git(format!(
+            "The Git repository at {} uses {} object hashes, but the source repository uses \
+             {}",
+            git_repo_path.display(),
+            target_repo.object_hash(),
+            source_repo.object_hash()
+        )));
+    }
+    let root_commit_id = repo.store().root_commit_id();
+    let mut failed_bookmarks = Vec::new();
+    for (name, target) in repo.view().local_bookmarks() {
+        if !bookmark_filter(name) {
+            continue;
+        }
+        let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+        let Some(commit_id) = target.as_normal() else {
+            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::ConflictedNewState));
+            continue;
+        };
+        if commit_id == root_commit_id {
+            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::OnRootCommit));
+            continue;
+        }
+        let Some(git_ref_name) = to_git_ref_name(GitRefKind::Bookmark, symbol) else {
+            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::InvalidGitName));
+            continue;
+        };
+        let oid = owned_oid_from_commit_id(commit_id);
+        let result = copy_git_objects(&source_repo, &target_repo, oid).and_then(|()| {
+            target_repo
+                .reference(
+                    git_ref_name.as_str(),
+                    oid,
+                    gix::refs::transaction::PreviousValue::Any,
+                    export_reflog_message(&git_ref_name),
+                )
+                .map_err(Box::from)
+        });
+        if let Err(err) = result {
+            failed_bookmarks.push((symbol.to_owned(), FailedRefExportReason::FailedToSet(err)));
+        }
+    }
+    Ok(GitExportStats {
+        failed_bookmarks,
+        failed_tags: vec![],
+    })
+}
+
+/// Copies objects reachable from `tip` to `target_repo`. Objects which already
+/// exist in `target_repo` are assumed to be complete with their dependencies.
+///
+/// Objects are written after their dependencies so that an interrupted copy
+/// doesn't leave objects with missing parents or trees in `target_repo`.
+fn copy_git_objects(
+    source_repo: &gix::Repository,
+    target_repo: &gix::Repository,
+    tip: gix::ObjectId,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // (oid, whether the dependencies have been queued)
+    let mut pending = vec![(tip, false)];
+    let mut visited = HashSet::new();
+    while let Some((oid, dependencies_queued)) = pending.pop() {
+        if dependencies_queued {
+            let object = source_repo.find_object(oid)?;
+            target_repo.objects.write_buf(object.kind, &object.data)?;
+            continue;
+        }
+        if !visited.insert(oid) || target_repo.has_object(oid) {
+            continue;
+        }
+        pending.push((oid, true));
+        let object = source_repo.find_object(oid)?;
+        let dependencies: Vec<gix::ObjectId> = match object.kind {
+            gix::object::Kind::Commit => {
+                let mut ids = vec![object.to_commit_ref_iter().tree_id()?];
+                ids.extend(object.to_commit_ref_iter().parent_ids());
+                ids
+            }
+            gix::object::Kind::Tree => {
+                let mut ids = Vec::new();
+                for entry in
+                    gix::objs::TreeRefIter::from_bytes(&object.data, source_repo.object_hash())
+                {
+                    let entry = entry?;
+                    // Submodule commits don't live in this repository
+                    if !entry.mode.is_commit() {
+                        ids.push(entry.oid.to_owned());
+                    }
+                }
+                ids
+            }
+            gix::object::Kind::Tag => vec![object.to_tag_ref_iter().target_id()?],
+            gix::object::Kind::Blob => vec![],
+        };
+        pending.extend(dependencies.into_iter().map(|id| (id, false)));
+    }
+    Ok(())
+}
+
+/// Exports bookmarks and tags planned by [`plan_refs_export()`] one by one.
+///
+/// The HEADs are detached first in a single ref transaction. Refs which can't
+/// be written are reported as failed, and the others are exported.
+fn export_refs_to_git(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+    plan: RefExportPlan,
+) -> Result<GitExportStats, GitExportError> {
+    let RefExportPlan {
+        ref_edits,
+        up_to_date_refs,
+        head_edits,
+        detached_head_refs: _,
+        mut failed_bookmarks,
+        mut failed_tags,
+    } = plan;
+    for (git_ref_name, new_oid) in up_to_date_refs {
+        set_exported_git_ref_target(mut_repo, &git_ref_name, new_oid);
+    }
+    if !head_edits.is_empty() {
+        git_repo
+            .edit_references(head_edits)
+            .map_err(GitExportError::from_git)?;
+    }
+    for planned in ref_edits {
+        let new_oid = planned.diff.after;
+        match git_repo.edit_reference(planned.edit) {
+            Ok(_) => set_exported_git_ref_target(mut_repo, &planned.git_ref_name, new_oid),
+            Err(err) => push_failed_ref_export(
+                &mut failed_bookmarks,
+                &mut failed_tags,
+                &planned.git_ref_name,
+                new_oid,
+                err.into(),
+            ),
+        }
+    }
+
+    failed_bookmarks.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    failed_tags.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    Ok(GitExportStats {
+        failed_bookmarks,
+        failed_tags,
+    })
+}
+
+fn set_exported_git_ref_target(
+    mut_repo: &mut MutableRepo,
+    git_ref_name: &GitRefName,
+    new_oid: Option<gix::ObjectId>,
+) {
+    let new_target = match new_oid {
+        Some(oid) => RefTarget::normal(CommitId::from_bytes(oid.as_bytes())),
+        None => RefTarget::absent(),
+    };
+    mut_repo.set_git_ref_target(git_ref_name, new_target);
+}
+
+fn push_failed_ref_export(
+    failed_bookmarks: &mut Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    failed_tags: &mut Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    git_ref_name: &GitRefName,
+    new_oid: Option<gix::ObjectId>,
+    err: Box<dyn std::error::Error + Send + Sync>,
+) {
+    let Some((kind, symbol)) = parse_git_ref(git_ref_name) else {
+        return;
+    };
+    let reason = match new_oid {
+        Some(_) => FailedRefExportReason::FailedToSet(err),
+        None => FailedRefExportReason::FailedToDelete(err),
+    };
+    match kind {
+        GitRefKind::Bookmark => failed_bookmarks.push((symbol.to_owned(), reason)),
+        GitRefKind::Tag => failed_tags.push((symbol.to_owned(), reason)),
+    }
+}
+
+/// Ref edits to be made by the export, computed against the current state of
+/// the Git repo.
+struct RefExportPlan {
+    /// Ref edits in the order they would be written.
+    ref_edits: Vec<PlannedRefEdit>,
+    /// Refs which already point to the desired target in Git, and their new
+    /// commit ids.
+    up_to_date_refs: Vec<(GitRefNameBuf, Option<gix::ObjectId>)>,
+    /// Edits detaching the HEADs of the repo and its worktrees from a branch
+    /// to be updated or deleted.
+    head_edits: Vec<gix::refs::transaction::RefEdit>,
+    /// Branches the HEADs would be detached from.
+    detached_head_refs: Vec<GitRefNameBuf>,
+    /// Remote bookmarks that couldn't be exported, sorted by `symbol`.
+    failed_bookmarks: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+    /// Remote tags that couldn't be exported, sorted by `symbol`.
+    failed_tags: Vec<(RemoteRefSymbolBuf, FailedRefExportReason)>,
+}
+
+#[derive(Debug)]
+struct PlannedRefEdit {
+    git_ref_name: GitRefNameBuf,
+    /// Old and new commit ids of the ref.
+    diff: Diff<Option<gix::ObjectId>>,
+    edit: gix::refs::transaction::RefEdit,
+}
+
+/// Computes ref edits to export bookmarks and tags without writing to the Git
+/// repo.
+///
+/// Refs that already point to the desired target in Git are omitted. The plan
+/// is shared by the dry-run preview and the exports.
+fn plan_refs_export(
+    view: &View,
+    git_repo: &gix::Repository,
+    bookmarks: RefsToExport,
+    tags: RefsToExport,
+) -> Result<RefExportPlan, GitExportError> {
+    let mut existing_ref_names: HashSet<GitRefNameBuf> = git_repo
+        .references()
+        .map_err(GitExportError::from_git)?
+        .all()
+        .map_err(GitExportError::from_git)?
+        .filter_map(Result::ok)
+        .filter_map(|git_ref| {
+            str::from_utf8(git_ref.name().as_bstr())
+                .ok()
+                .map(Into::into)
+        })
+        .collect();
+    let mut ref_edits = Vec::new();
+    let mut up_to_date_refs = Vec::new();
+    let failed_bookmarks = plan_refs_export_of_kind(
+        view,
+        git_repo,
+        GitRefKind::Bookmark,
+        bookmarks,
+        &mut existing_ref_names,
+        &mut ref_edits,
+        &mut up_to_date_refs,
+    );
+    let failed_tags = plan_refs_export_of_kind(
+        view,
+        git_repo,
+        GitRefKind::Tag,
+        tags,
+        &mut existing_ref_names,
+        &mut ref_edits,
+        &mut up_to_date_refs,
+    );
+
+    let new_commit_oid_of = |git_ref_name: &GitRefName| {
+        ref_edits
+            .iter()
+            .find(|planned| *planned.git_ref_name == *git_ref_name)
+            .map(|planned| planned.diff.after)
+    };
+    let mut detached_head_refs = Vec::new();
+    let mut head_edits = Vec::new();
+    if let Some((branch, edits)) = plan_git_head_detach(git_repo, new_commit_oid_of)? {
+        detached_head_refs.push(branch);
+        head_edits = edits;
+    }
+    for (worktree_id, worktree_repo) in get_worktree_repos(git_repo)? {
+        let Some((branch, edits)) = plan_git_head_detach(&worktree_repo, new_commit_oid_of)? else {
+            continue;
+        };
+        detached_head_refs.push(branch);
+        for mut edit in edits {
+            // Per-worktree refs such as HEAD can be written through the main
+            // repo as "worktrees/<id>/<name>", in the same transaction.
+            if !edit.name.as_bstr().starts_with(b"refs/") {
+                let name = format!("worktrees/{worktree_id}/{}", edit.name.as_bstr());
+                edit.name = name.try_into().map_err(GitExportError::from_git)?;
+            }
+            // The placeholder ref of an unborn HEAD is shared
+            if !head_edits.iter().any(|other| other.name == edit.name) {
+                head_edits.push(edit);
+            }
+        }
+    }
+
+    Ok(RefExportPlan {
+        ref_edits,
+        up_to_date_refs,
+        head_edits,
+        detached_head_refs,
+        failed_bookmarks,
+        failed_tags,
+    })
+}
+
+fn plan_refs_export_of_kind(
+    view: &View,
+    git_repo: &gix::Repository,
+    kind: GitRefKind,
+    refs: RefsToExport,
+    existing_ref_names: &mut HashSet<GitRefNameBuf>,
+    ref_edits: &mut Vec<PlannedRefEdit>,
+    up_to_date_refs: &mut Vec<(GitRefNameBuf, Option<gix::ObjectId>)>,
+) -> Vec<(RemoteRefSymbolBuf, FailedRefExportReason)> {
+    use gix::refs::transaction::Change;
+    use gix::refs::transaction::LogChange;
+    use gix::refs::transaction::PreviousValue;
+    use gix::refs::transaction::RefEdit;
+    use gix::refs::transaction::RefLog;
+
+    let make_edit = |git_ref_name: &GitRefName, change: Change| {
+        let name = git_ref_name.as_str().try_into()?;
+        Ok::<_, gix::validate::reference::name::Error>(RefEdit {
+            change,
+            name,
+            deref: false,
+        })
+    };
+
+    let mut failed = refs.failed;
+    for (symbol, old_oid) in refs.to_delete {
+        let Some(git_ref_name) = to_git_ref_name(kind, symbol.as_ref()) else {
+            failed.push((symbol, FailedRefExportReason::InvalidGitName));
+            continue;
+        };
+        let git_ref = match git_repo.try_find_reference(git_ref_name.as_str()) {
+            Ok(git_ref) => git_ref,
+            Err(err) => {
+                failed.push((symbol, FailedRefExportReason::FailedToDelete(err.into())));
+                continue;
+            }
+        };
+        // The ref may already be deleted
+        let Some(git_ref) = git_ref else {
+            up_to_date_refs.push((git_ref_name, None));
+            continue;
+        };
+        if resolve_git_ref_to_commit_id(&git_ref, Some(&old_oid)) != Some(old_oid) {
+            failed.push((symbol, FailedRefExportReason::DeletedInJjModifiedInGit));
+            continue;
+        }
+        let change = Change::Delete {
+            expected: PreviousValue::MustExistAndMatch(git_ref.inner.target.clone()),
+            log: RefLog::AndReference,
+        };
+        match make_edit(&git_ref_name, change) {
+            Ok(edit) => {
+                existing_ref_names.remove(&git_ref_name);
+                ref_edits.push(PlannedRefEdit {
+                    git_ref_name,
+                    diff: Diff::new(Some(old_oid), None),
+                    edit,
+                });
+            }
+            Err(err) => failed.push((symbol, FailedRefExportReason::FailedToDelete(err.into()))),
+        }
+    }
+    for (symbol, (old_commit_oid, new_commit_oid)) in refs.to_update {
+        let Some(git_ref_name) = to_git_ref_name(kind, symbol.as_ref()) else {
+            failed.push((symbol, FailedRefExportReason::InvalidGitName));
+            continue;
+        };
+        let git_ref = match git_repo.try_find_reference(git_ref_name.as_str()) {
+            Ok(git_ref) => git_ref,
+            Err(err) => {
+                failed.push((symbol, FailedRefExportReason::FailedToSet(err.into())));
+                continue;
+            }
+        };
+        let git_commit_oid = git_ref
+            .as_ref()
+            .and_then(|git_ref| resolve_git_ref_to_commit_id(git_ref, old_commit_oid.as_deref()));
+        let expected = match (old_commit_oid, &git_ref) {
+            // Already at the desired target in Git
+            (_, Some(_)) if git_commit_oid == Some(new_commit_oid) => {
+                up_to_date_refs.push((git_ref_name, Some(new_commit_oid)));
+                continue;
+            }
+            (None, None) => {
+                match find_conflicting_git_ref_name(existing_ref_names, &git_ref_name) {
+                    Some(other) => Err(FailedRefExportReason::FailedToSet(
+                        format!("Conflicts with existing Git ref {}", other.as_str()).into(),
+                    )),
+                    None => Ok(PreviousValue::MustNotExist),
+                }
+            }
+            (None, Some(_)) => Err(FailedRefExportReason::AddedInJjAddedInGit),
+            (Some(_), None) => Err(FailedRefExportReason::ModifiedInJjDeletedInGit),
+            (Some(old_oid), Some(git_ref)) if git_commit_oid == Some(old_oid) => Ok(
+                PreviousValue::MustExistAndMatch(git_ref.inner.target.clone()),
+            ),
+            (Some(_), Some(_)) => Err(FailedRefExportReason::FailedToSet(
+                format!("Git ref {} has been modified in Git", git_ref_name.as_str()).into(),
+            )),
+        };
+        let expected = match expected {
+            Ok(expected) => expected,
+            Err(reason) => {
+                failed.push((symbol, reason));
+                continue;
+            }
+        };
+        let new_ref_oid = match kind {
+            GitRefKind::Bookmark => None,
+            // Copy existing tag ref, which may point to annotated tag object.
+            GitRefKind::Tag => {
+                let remote_matcher = StringMatcher::all();
+                find_git_tag_oid_to_copy(
+                    view,
+                    git_repo,
+                    &symbol.name,
+                    &remote_matcher,
+                    &new_commit_oid,
+                )
+            }
+        };
+        let change = Change::Update {
+            log: LogChange {
+                message: export_reflog_message(&git_ref_name).into(),
+                ..Default::default()
+            },
+            expected,
+            new: gix::refs::Target::Object(new_ref_oid.unwrap_or(new_commit_oid)),
+        };
+        match make_edit(&git_ref_name, change) {
+            Ok(edit) => {
+                existing_ref_names.insert(git_ref_name.clone());
+                ref_edits.push(PlannedRefEdit {
+                    git_ref_name,
+                    diff: Diff::new(old_commit_oid, Some(new_commit_oid)),
+                    edit,
+                });
+            }
+            Err(err) => failed.push((symbol, FailedRefExportReason::FailedToSet(err.into()))),
+        }
+    }
+
+    failed.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    failed
+}
+
+/// Exports bookmarks and tags in a single ref transaction.
+///
+/// If the `plan` has failed refs, no ref is written. The HEADs of the Git repo
+/// and its worktrees are detached in the same transaction. If the transaction
+/// fails, all refs to be written are reported as failed.
+fn export_refs_to_git_atomically(
+    mut_repo: &mut MutableRepo,
+    git_repo: &gix::Repository,
+    plan: RefExportPlan,
+) -> Result<GitExportStats, GitExportError> {
+    let RefExportPlan {
+        ref_edits,
+        up_to_date_refs,
+        head_edits,
+        detached_head_refs: _,
+        mut failed_bookmarks,
+        mut failed_tags,
+    } = plan;
+    if !failed_bookmarks.is_empty() || !failed_tags.is_empty() {
+        return Ok(GitExportStats {
+            failed_bookmarks,
+            failed_tags,
+        });
+    }
+    for (git_ref_name, new_oid) in up_to_date_refs {
+        set_exported_git_ref_target(mut_repo, &git_ref_name, new_oid);
+    }
+
+    let (exported, edits): (Vec<_>, Vec<_>) = ref_edits
+        .into_iter()
+        .map(|planned| ((planned.git_ref_name, planned.diff.after), planned.edit))
+        .unzip();
+    match git_repo.edit_references(head_edits.into_iter().chain(edits)) {
+        Ok(_) => {
+            for (git_ref_name, new_oid) in exported {
+                set_exported_git_ref_target(mut_repo, &git_ref_name, new_oid);
+            }
+        }
+        Err(err) => {
+            for (git_ref_name, new_oid) in exported {
+                push_failed_ref_export(
+                    &mut failed_bookmarks,
+                    &mut failed_tags,
+                    &git_ref_name,
+                    new_oid,
+                    format!("Ref transaction failed: {err}").into(),
+                );
+            }
+        }
+    }
+
+    failed_bookmarks.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    failed_tags.sort_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    Ok(GitExportStats {
+        failed_bookmarks,
+        failed_tags,
+    })
+}
+
+/// Returns the ids and repos of the accessible worktrees of the `git_repo`.
+fn get_worktree_repos(
+    git_repo: &gix::Repository,
+) -> Result<Vec<(BString, gix::Repository)>, GitExportError> {
+    let worktrees = git_repo.worktrees().map_err(GitExportError::from_git)?;
+    Ok(worktrees
+        .into_iter()
+        .filter_map(|worktree| {
+            let id = worktree.id().to_owned();
+            let repo = worktree
+                .into_repo_with_possibly_inaccessible_worktree()
+                .ok()?;
+            Some((id, repo))
+        })
+        .collect())
+}
+
+/// Computes ref edits to detach the HEAD of the `git_repo` if it points to a
+/// branch which would be updated or deleted.
+///
+/// `new_commit_oid_of(git_ref_name)` returns the new commit id of the ref if
+/// the ref would be exported. Returns the branch name and the edits.
+fn plan_git_head_detach(
+    git_repo: &gix::Repository,
+    new_commit_oid_of: impl Fn(&GitRefName) -> Option<Option<gix::ObjectId>>,
+) -> Result<Option<(GitRefNameBuf, Vec<gix::refs::transaction::RefEdit>)>, GitExportError> {
+    let Ok(head_ref) = git_repo.find_reference("HEAD") else {
+        return Ok(None);
+    };
+    let Some(branch) = head_ref
+        .target()
+        .try_name()
+        .and_then(|name| str::from_utf8(name.as_bstr()).ok())
+        .map(GitRefNameBuf::from)
+    else {
+        return Ok(None);
+    };
+    let Some(new_oid) = new_commit_oid_of(&branch) else {
+        return Ok(None);
+    };
+    let old_target = head_ref.inner.target.clone();
+    let current_oid = match head_ref.into_fully_peeled_id() {
+        Ok(id) => Some(id.detach()),
+        Err(gix::reference::peel::Error::ToId(gix::refs::peel::to_id::Error::FollowToObject(
+            gix::refs::peel::to_object::Error::Follow(
+                gix::refs::file::find::existing::Error::NotFound { .. },
+            ),
+        ))) => None, // Unborn ref should be considered absent
+        Err(err) => return Err(GitExportError::from_git(err)),
+    };
+    if new_oid == current_oid {
+        return Ok(None);
+    }
+    let edits = git_head_ref_edits(
+        gix::refs::transaction::PreviousValue::MustExistAndMatch(old_target),
+        current_oid,
+        None,
+        None,
+    );
+    Ok(Some((branch, edits)))
+}
+
+/// Finds existing ref which would prevent `git_ref_name` from being created
+/// on the file system, such as `refs/heads/foo` for `refs/heads/foo/bar`.
+fn find_conflicting_git_ref_name<'a>(
+    existing_ref_names: &'a HashSet<GitRefNameBuf>,
+    git_ref_name: &GitRefName,
+) -> Option<&'a GitRefNameBuf> {
+    let name = git_ref_name.as_str();
+    existing_ref_names.iter().find(|other| {
+        let other = other.as_str();
+        [(name, other), (other, name)]
+            .iter()
+            .any(|(parent, child)| {
+                child
+                    .strip_prefix(parent)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+    })
+}
+
+fn copy_exportable_local_bookmarks_to_remote_view(
+    mut_repo: &mut MutableRepo,
+    remote: &RemoteName,
+    name_filter: impl Fn(&RefName) -> bool,
+) {
+    let new_local_bookmarks = mut_repo
+        .view()
+        .local_remote_bookmarks(remote)
+        .filter_map(|(name, targets)| {
+            // TODO: filter out untracked bookmarks (if we add support for untracked @git
+            // bookmarks)
+            let old_target = &targets.remote_ref.target;
+            let new_target = targets.local_target;
+            (!new_target.has_conflict() && old_target != new_target).then_some((name, new_target))
+        })
+        .filter(|&(name, _)| name_filter(name))
+        .map(|(name, new_target)| (name.to_owned(), new_target.clone()))
+        .collect_vec();
+    for (name, new_target) in new_local_bookmarks {
+        let new_remote_ref = RemoteRef {
             target: new_target,
             state: RemoteRefState::Tracked,
         };
@@ -1489,10 +2675,18 @@ fn copy_exportable_local_tags_to_remote_view(
 
 /// Calculates diff of bookmarks and tags to be exported.
 fn diff_refs_to_export(
-    view: &View,
-    root_commit_id: &CommitId,
+    repo: &dyn Repo,
     git_ref_filter: impl Fn(GitRefKind, RemoteRefSymbol<'_>) -> bool,
-) -> AllRefsToExport {
+) -> Result<AllRefsToExport, GitExportError> {
+    let view = repo.view();
+    // Pull request heads are managed by jj, and aren't remote-tracking branches
+    // in Git.
+    let pull_request_symbols: HashSet<RemoteRefSymbol> = view
+        .git_refs()
+        .keys()
+        .filter_map(|full_name| parse_pull_request_ref(full_name))
+        .map(|(_, symbol)| symbol)
+        .collect();
     // Local targets will be copied to the "git" remote if successfully exported. So
     // the local refs are considered to be the new "git" remote refs.
     let mut all_bookmark_targets: HashMap<RemoteRefSymbol, (&RefTarget, &RefTarget)> =
@@ -1503,6 +2697,7 @@ fn diff_refs_to_export(
             }),
             view.all_remote_bookmarks()
                 .filter(|&(symbol, _)| symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO)
+                .filter(|(symbol, _)| !pull_request_symbols.contains(symbol))
                 .map(|(symbol, remote_ref)| (symbol, &remote_ref.target)),
         )
         .filter(|&(symbol, _)| git_ref_filter(GitRefKind::Bookmark, symbol))
@@ -1521,6 +2716,7 @@ fn diff_refs_to_export(
     let known_git_refs = view
         .git_refs()
         .iter()
+        .filter(|(full_name, _)| !full_name.as_str().starts_with(PULL_REQUEST_REF_NAMESPACE))
         .map(|(full_name, target)| {
             let (kind, symbol) =
                 parse_git_ref(full_name).expect("stored git ref should be parsable");
@@ -1541,15 +2737,48 @@ fn diff_refs_to_export(
             .or_insert((target, RefTarget::absent_ref()));
     }
 
-    let root_commit_target = RefTarget::normal(root_commit_id.clone());
-    let bookmarks = collect_changed_refs_to_export(&all_bookmark_targets, &root_commit_target);
-    let tags = collect_changed_refs_to_export(&all_tag_targets, &root_commit_target);
-    AllRefsToExport { bookmarks, tags }
+    // Exporting a local bookmark pointing to a hidden commit would resurrect
+    // the commit in Git. Remote bookmarks may legitimately point to hidden
+    // commits, so they are exported as usual.
+    // Visibility is only checked for the bookmarks to be updated, and the
+    // visible commits are only evaluated if there are any.
+    let candidate_ids: HashSet<&CommitId> = all_bookmark_targets
+        .iter()
+        .filter(|(symbol, (old_target, new_target))| {
+            symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO && new_target != old_target
+        })
+        .filter_map(|(_, (_, new_target))| new_target.as_normal())
+        .filter(|id| !view.heads().contains(*id))
+        .collect();
+    let mut hidden_commit_ids = HashSet::new();
+    if !candidate_ids.is_empty() {
+        let visible_commits = RevsetExpression::visible_heads()
+            .ancestors()
+            .evaluate(repo)
+            .map_err(GitExportError::from_git)?;
+        let is_visible = visible_commits.containing_fn();
+        for id in candidate_ids {
+            if !is_visible(id).map_err(GitExportError::from_git)? {
+                hidden_commit_ids.insert(id);
+            }
+        }
+    }
+
+    let root_commit_target = RefTarget::normal(repo.store().root_commit_id().clone());
+    let bookmarks = collect_changed_refs_to_export(
+        &all_bookmark_targets,
+        &root_commit_target,
+        &hidden_commit_ids,
+    );
+    let tags =
+        collect_changed_refs_to_export(&all_tag_targets, &root_commit_target, &HashSet::new());
+    Ok(AllRefsToExport { bookmarks, tags })
 }
 
 fn collect_changed_refs_to_export(
     old_new_ref_targets: &HashMap<RemoteRefSymbol, (&RefTarget, &RefTarget)>,
     root_commit_target: &RefTarget,
+    hidden_commit_ids: &HashSet<&CommitId>,
 ) -> RefsToExport {
     let mut to_update = Vec::new();
     let mut to_delete = Vec::new();
@@ -1563,6 +2792,14 @@ fn collect_changed_refs_to_export(
             failed.push((symbol.to_owned(), FailedRefExportReason::OnRootCommit));
             continue;
         }
+        if symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO
+            && new_target
+                .as_normal()
+                .is_some_and(|id| hidden_commit_ids.contains(id))
+        {
+            failed.push((symbol.to_owned(), FailedRefExportReason::OnHiddenCommit));
+            continue;
+        }
         let old_oid = if let Some(id) = old_target.as_normal() {
             Some(owned_oid_from_commit_id(id))
         } else if old_target.has_conflict() {
@@ -1625,123 +2862,217 @@ fn find_git_tag_oid_to_copy(
         .find_map(|git_ref| git_ref.inner.target.try_into_id().ok())
 }
 
-fn delete_git_ref(
+/// Looks up the Git tag object of the remote tag if it points to the commit.
+fn find_remote_git_tag_oid(
     git_repo: &gix::Repository,
-    git_ref_name: &GitRefName,
-    old_oid: &gix::oid,
-) -> Result<(), FailedRefExportReason> {
-    let Some(git_ref) = git_repo
-        .try_find_reference(git_ref_name.as_str())
-        .map_err(|err| FailedRefExportReason::FailedToDelete(err.into()))?
-    else {
-        // The ref is already deleted
-        return Ok(());
-    };
-    if resolve_git_ref_to_commit_id(&git_ref, Some(old_oid)).as_deref() == Some(old_oid) {
-        // The ref has not been updated by git, so go ahead and delete it
-        git_ref
-            .delete()
-            .map_err(|err| FailedRefExportReason::FailedToDelete(err.into()))
-    } else {
-        // The ref was updated by git
-        Err(FailedRefExportReason::DeletedInJjModifiedInGit)
+    symbol: RemoteRefSymbol<'_>,
+    commit_oid: &gix::oid,
+) -> Option<gix::ObjectId> {
+    let git_ref_name = to_git_or_remote_tag_ref_name(symbol);
+    let git_ref = git_repo.find_reference(git_ref_name.as_str()).ok()?;
+    if resolve_git_ref_to_commit_id(&git_ref, Some(commit_oid)).as_deref() != Some(commit_oid) {
+        return None;
     }
+    git_ref.inner.target.try_into_id().ok()
 }
 
-/// Creates new ref pointing to `new_ref_oid` or (peeled) `new_commit_oid`.
-fn create_git_ref(
-    git_repo: &gix::Repository,
-    git_ref_name: &GitRefName,
-    new_commit_oid: gix::ObjectId,
-    new_ref_oid: Option<gix::ObjectId>,
-) -> Result<(), FailedRefExportReason> {
-    let new_oid = new_ref_oid.unwrap_or(new_commit_oid);
-    let constraint = gix::refs::transaction::PreviousValue::MustNotExist;
-    let Err(set_err) =
-        git_repo.reference(git_ref_name.as_str(), new_oid, constraint, "export from jj")
-    else {
-        // The ref was added in jj but still doesn't exist in git
-        return Ok(());
-    };
-    let Some(git_ref) = git_repo
-        .try_find_reference(git_ref_name.as_str())
-        .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?
-    else {
-        return Err(FailedRefExportReason::FailedToSet(set_err.into()));
-    };
-    // The ref was added in jj and in git. We're good if and only if git
-    // pointed it to our desired target.
-    if resolve_git_ref_to_commit_id(&git_ref, None) == Some(new_commit_oid) {
-        Ok(())
-    } else {
-        Err(FailedRefExportReason::AddedInJjAddedInGit)
-    }
+#[derive(Debug, Error)]
+pub enum GitAnnotatedTagError {
+    #[error("Tag name {} can't be exported to Git", .0.as_symbol())]
+    InvalidName(RefNameBuf),
+    #[error(transparent)]
+    Git(Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
 }
 
-/// Updates existing ref to point to `new_ref_oid` or (peeled) `new_commit_oid`.
-fn move_git_ref(
-    git_repo: &gix::Repository,
-    git_ref_name: &GitRefName,
-    old_commit_oid: gix::ObjectId,
-    new_commit_oid: gix::ObjectId,
-    new_ref_oid: Option<gix::ObjectId>,
-) -> Result<(), FailedRefExportReason> {
-    let new_oid = new_ref_oid.unwrap_or(new_commit_oid);
-    let constraint =
-        gix::refs::transaction::PreviousValue::MustExistAndMatch(old_commit_oid.into());
-    let Err(set_err) =
-        git_repo.reference(git_ref_name.as_str(), new_oid, constraint, "export from jj")
-    else {
-        // Successfully updated from old_oid to new_oid (unchanged in git)
-        return Ok(());
+/// Creates the local tag `name` pointing to `commit_id`, and exports it to Git
+/// as an annotated tag object.
+///
+/// Like [`export_refs()`], the Git ref `refs/tags/<name>` is updated
+/// immediately, and recorded as exported in `mut_repo`. If the transaction is
+/// abandoned, the Git ref will be reconciled by the next import or export.
+pub fn create_annotated_tag(
+    mut_repo: &mut MutableRepo,
+    name: &RefName,
+    commit_id: &CommitId,
+    message: &str,
+    tagger: &Signature,
+) -> Result<gix::ObjectId, GitAnnotatedTagError> {
+    let git_repo = get_git_repo(mut_repo.store())?;
+    let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+    let git_ref_name = to_git_ref_name(GitRefKind::Tag, symbol)
+        .ok_or_else(|| GitAnnotatedTagError::InvalidName(name.to_owned()))?;
+    let tag = gix::objs::Tag {
+        target: gix::ObjectId::from_bytes_or_panic(commit_id.as_bytes()),
+        target_kind: gix::object::Kind::Commit,
+        name: name.as_str().into(),
+        tagger: Some(signature_to_git(tagger)),
+        message: message.into(),
+        pgp_signature: None,
     };
-    // The reference was probably updated in git
-    let Some(git_ref) = git_repo
-        .try_find_reference(git_ref_name.as_str())
-        .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?
-    else {
-        // The reference was deleted in git and moved in jj
-        return Err(FailedRefExportReason::ModifiedInJjDeletedInGit);
+    let tag_oid = git_repo
+        .write_object(&tag)
+        .map_err(|err| GitAnnotatedTagError::Git(err.into()))?
+        .detach();
+    // Don't overwrite a tag created in Git but not imported yet.
+    let previous_value = match mut_repo.get_git_ref(&git_ref_name).as_normal() {
+        Some(old_id) => gix::refs::transaction::PreviousValue::MustExistAndMatch(
+            gix::refs::Target::Object(gix::ObjectId::from_bytes_or_panic(old_id.as_bytes())),
+        ),
+        None => gix::refs::transaction::PreviousValue::MustNotExist,
     };
-    // We still consider this a success if it was updated to our desired target
-    let git_commit_oid = resolve_git_ref_to_commit_id(&git_ref, Some(&old_commit_oid));
-    if git_commit_oid == Some(new_commit_oid) {
-        Ok(())
-    } else if git_commit_oid == Some(old_commit_oid) {
-        // The reference would point to annotated tag, try again
-        let constraint =
-            gix::refs::transaction::PreviousValue::MustExistAndMatch(git_ref.inner.target);
+    git_repo
+        .reference(
+            git_ref_name.as_str(),
+            tag_oid,
+            previous_value,
+            export_reflog_message(&git_ref_name),
+        )
+        .map_err(|err| GitAnnotatedTagError::Git(err.into()))?;
+    let target = RefTarget::normal(commit_id.clone());
+    mut_repo.set_git_ref_target(&git_ref_name, target.clone());
+    mut_repo.set_local_tag_target(name, target);
+    Ok(tag_oid)
+}
+
+#[derive(Debug, Error)]
+pub enum GitTagVerifyError {
+    #[error(transparent)]
+    Git(Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to verify tag signature")]
+    Sign(#[from] SignError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+/// Verifies the signature of the annotated Git tag that the tag `name` or
+/// `name@remote` points to.
+///
+/// A local tag is resolved to the tag object which would be exported to Git,
+/// i.e. the one of a tracked remote tag pointing to the same commit. Returns
+/// `None` if the ref doesn't point to an annotated tag of `commit_id`, or if
+/// the tag isn't signed.
+pub fn verify_tag_signature(
+    repo: &dyn Repo,
+    name: &RefName,
+    remote: Option<&RemoteName>,
+    commit_id: &CommitId,
+) -> Result<Option<Verification>, GitTagVerifyError> {
+    let store = repo.store();
+    let git_repo = get_git_repo(store)?;
+    let commit_oid = owned_oid_from_commit_id(commit_id);
+    let maybe_tag_oid = if let Some(remote) = remote {
+        let git_ref_name = to_git_or_remote_tag_ref_name(name.to_remote_symbol(remote));
         git_repo
-            .reference(git_ref_name.as_str(), new_oid, constraint, "export from jj")
-            .map_err(|err| FailedRefExportReason::FailedToSet(err.into()))?;
-        Ok(())
+            .try_find_reference(git_ref_name.as_str())
+            .map_err(|err| GitTagVerifyError::Git(err.into()))?
+            .and_then(|git_ref| git_ref.inner.target.try_into_id().ok())
     } else {
-        Err(FailedRefExportReason::FailedToSet(set_err.into()))
+        let remote_matcher = StringMatcher::all();
+        find_git_tag_oid_to_copy(repo.view(), &git_repo, name, &remote_matcher, &commit_oid)
+    };
+    let Some(tag_oid) = maybe_tag_oid else {
+        return Ok(None);
+    };
+    let object = git_repo
+        .find_object(tag_oid)
+        .map_err(|err| GitTagVerifyError::Git(err.into()))?;
+    let Ok(tag) = object.try_to_tag_ref() else {
+        return Ok(None);
+    };
+    if tag.target() != commit_oid {
+        return Ok(None);
     }
+    let Some((data, signature)) = split_tag_signature(&object.data) else {
+        return Ok(None);
+    };
+    let verification = store.signer().verify_data(data, signature)?;
+    Ok(Some(verification))
 }
 
-fn update_git_ref(
-    git_repo: &gix::Repository,
-    git_ref_name: &GitRefName,
-    old_commit_oid: Option<gix::ObjectId>,
-    new_commit_oid: gix::ObjectId,
-    new_ref_oid: Option<gix::ObjectId>,
-) -> Result<(), FailedRefExportReason> {
-    match old_commit_oid {
-        None => create_git_ref(git_repo, git_ref_name, new_commit_oid, new_ref_oid),
-        Some(old_oid) => move_git_ref(git_repo, git_ref_name, old_oid, new_commit_oid, new_ref_oid),
+/// Splits a signed tag object into the signed payload and the signature which
+/// Git appends to the tag message.
+fn split_tag_signature(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    const SIGNATURE_MARKERS: [&[u8]; 4] = [
+        b"-----BEGIN PGP SIGNATURE-----",
+        b"-----BEGIN PGP MESSAGE-----",
+        b"-----BEGIN SIGNED MESSAGE-----",
+        b"-----BEGIN SSH SIGNATURE-----",
+    ];
+    // Like Git, the last line starting with a marker begins the signature.
+    let (start, _) = data
+        .lines_with_terminator()
+        .scan(0, |pos, line| {
+            let start = *pos;
+            *pos += line.len();
+            Some((start, line))
+        })
+        .filter(|(_, line)| SIGNATURE_MARKERS.iter().any(|m| line.starts_with(m)))
+        .last()?;
+    Some(data.split_at(start))
+}
+
+/// Returns the reflog message for a ref written by export, e.g. `export
+/// bookmark main from jj`.
+fn export_reflog_message(git_ref_name: &GitRefName) -> String {
+    let Some((kind, symbol)) = parse_git_ref(git_ref_name) else {
+        return "export from jj".to_owned();
+    };
+    let kind = match kind {
+        GitRefKind::Bookmark => "bookmark",
+        GitRefKind::Tag => "tag",
+    };
+    if symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        format!("export {kind} {} from jj", symbol.name.as_str())
+    } else {
+        format!("export {kind} {symbol} from jj")
     }
 }
 
-/// Ensures Git HEAD is detached and pointing to the `new_oid`. If `new_oid`
-/// is `None` (meaning absent), dummy placeholder ref will be set.
+/// Ensures Git HEAD is pointing to the `new_oid`. If `new_branch` is
+/// specified, HEAD will be a symbolic ref to the branch, which should point to
+/// `new_oid`. Otherwise, HEAD will be detached. If `new_oid` is `None` (meaning
+/// absent), dummy placeholder ref will be set.
+///
+/// If `orig_head_oid` is specified, `ORIG_HEAD` is set to it in the same
+/// transaction.
 fn update_git_head(
     git_repo: &gix::Repository,
     expected_ref: gix::refs::transaction::PreviousValue,
     new_oid: Option<gix::ObjectId>,
+    new_branch: Option<gix::refs::FullName>,
+    orig_head_oid: Option<gix::ObjectId>,
 ) -> Result<(), gix::reference::edit::Error> {
+    let ref_edits = git_head_ref_edits(expected_ref, new_oid, new_branch, orig_head_oid);
+    git_repo.edit_references(ref_edits)?;
+    Ok(())
+}
+
+fn git_head_ref_edits(
+    expected_ref: gix::refs::transaction::PreviousValue,
+    new_oid: Option<gix::ObjectId>,
+    new_branch: Option<gix::refs::FullName>,
+    orig_head_oid: Option<gix::ObjectId>,
+) -> Vec<gix::refs::transaction::RefEdit> {
     let mut ref_edits = Vec::new();
-    let new_target = if let Some(oid) = new_oid {
+    if let Some(oid) = orig_head_oid {
+        ref_edits.push(gix::refs::transaction::RefEdit {
+            change: gix::refs::transaction::Change::Update {
+                log: gix::refs::transaction::LogChange {
+                    message: "export from jj".into(),
+                    ..Default::default()
+                },
+                expected: gix::refs::transaction::PreviousValue::Any,
+                new: gix::refs::Target::Object(oid),
+            },
+            name: "ORIG_HEAD".try_into().unwrap(),
+            deref: false,
+        });
+    }
+    let new_target = if let (Some(_), Some(branch)) = (new_oid, new_branch) {
+        gix::refs::Target::Symbolic(branch)
+    } else if let Some(oid) = new_oid {
         gix::refs::Target::Object(oid)
     } else {
         // Can't detach HEAD without a commit. Use placeholder ref to nullify
@@ -1770,8 +3101,7 @@ fn update_git_head(
         name: "HEAD".try_into().unwrap(),
         deref: false,
     });
-    git_repo.edit_references(ref_edits)?;
-    Ok(())
+    ref_edits
 }
 
 #[derive(Debug, Error)]
@@ -1798,7 +3128,9 @@ pub async fn reset_head(
     mut_repo: &mut MutableRepo,
     wc_commit: &Commit,
 ) -> Result<(), GitResetHeadError> {
-    let git_repo = get_git_repo(mut_repo.store())?;
+    let git_backend = get_git_backend(mut_repo.store())?;
+    let git_repo = git_backend.git_repo();
+    let write_intent_to_add = git_backend.write_intent_to_add();
 
     let first_parent_id = &wc_commit.parent_ids()[0];
     let new_head_target = if first_parent_id != mut_repo.store().root_commit_id() {
@@ -1827,7 +3159,14 @@ pub async fn reset_head(
             gix::refs::transaction::PreviousValue::MustExist
         };
         let new_oid = new_head_target.as_normal().map(owned_oid_from_commit_id);
-        update_git_head(&git_repo, expected_ref, new_oid)
+        let new_branch = new_head_target
+            .as_normal()
+            .and_then(|id| find_exported_bookmark_to_check_out(git_backend, mut_repo.view(), id))
+            .map(|(_, branch)| branch);
+        // Like Git, record the previous HEAD in ORIG_HEAD so that it can be
+        // recovered by Git tooling.
+        let orig_head_oid = old_head_target.as_normal().map(owned_oid_from_commit_id);
+        update_git_head(&git_repo, expected_ref, new_oid, new_branch, orig_head_oid)
             .map_err(|err| GitResetHeadError::UpdateHeadRef(err.into()))?;
         mut_repo.set_git_head_target(new_head_target);
     }
@@ -1838,7 +3177,50 @@ pub async fn reset_head(
         clear_operation_state(&git_repo)?;
     }
 
-    reset_index(mut_repo, &git_repo, wc_commit).await
+    reset_index(mut_repo, &git_repo, wc_commit, write_intent_to_add).await
+}
+
+/// Returns the bookmark and Git branch names if `git.export.attach-head` is
+/// enabled and exactly one local bookmark pointing to the `commit_id` has been
+/// exported to Git.
+///
+/// Git HEAD can be a symbolic ref to that branch instead of being detached.
+fn find_exported_bookmark_to_check_out<'a>(
+    git_backend: &GitBackend,
+    view: &'a View,
+    commit_id: &CommitId,
+) -> Option<(&'a RefName, gix::refs::FullName)> {
+    if !git_backend.export_attach_head() {
+        return None;
+    }
+    let (name, git_ref_name) = find_single_exported_bookmark(view, commit_id)?;
+    Some((name, git_ref_name.as_str().try_into().ok()?))
+}
+
+/// Returns the local bookmark name if exactly one local bookmark pointing to
+/// the `commit_id` has been exported to Git.
+pub fn find_exported_bookmark<'a>(view: &'a View, commit_id: &CommitId) -> Option<&'a RefName> {
+    let (name, _) = find_single_exported_bookmark(view, commit_id)?;
+    Some(name)
+}
+
+fn find_single_exported_bookmark<'a>(
+    view: &'a View,
+    commit_id: &CommitId,
+) -> Option<(&'a RefName, GitRefNameBuf)> {
+    let mut exported_bookmarks =
+        view.local_bookmarks_for_commit(commit_id)
+            .filter_map(|(name, target)| {
+                let symbol = name.to_remote_symbol(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
+                let git_ref_name = to_git_ref_name(GitRefKind::Bookmark, symbol)?;
+                (target.as_normal() == Some(commit_id) && view.get_git_ref(&git_ref_name) == target)
+                    .then_some((name, git_ref_name))
+            });
+    let exported_bookmark = exported_bookmarks.next()?;
+    if exported_bookmarks.next().is_some() {
+        return None;
+    }
+    Some(exported_bookmark)
 }
 
 // TODO: Polish and upstream this to `gix`.
@@ -1878,7 +3260,41 @@ async fn reset_index(
     repo: &dyn Repo,
     git_repo: &gix::Repository,
     wc_commit: &Commit,
+    write_intent_to_add: bool,
 ) -> Result<(), GitResetHeadError> {
+    let mut index =
+        build_index_for_wc_commit(repo, git_repo, wc_commit, write_intent_to_add).await?;
+
+    // Match entries in the new index with entries in the old index, and copy stat
+    // information if the entry didn't change.
+    if let Some(old_index) = git_repo.try_index().map_err(GitResetHeadError::from_git)? {
+        index
+            .entries_mut_with_paths()
+            .merge_join_by(old_index.entries(), |(entry, path), old_entry| {
+                gix::index::Entry::cmp_filepaths(path, old_entry.path(&old_index))
+                    .then_with(|| entry.stage().cmp(&old_entry.stage()))
+            })
+            .filter_map(|merged| merged.both())
+            .map(|((entry, _), old_entry)| (entry, old_entry))
+            .filter(|(entry, old_entry)| entry.id == old_entry.id && entry.mode == old_entry.mode)
+            .for_each(|(entry, old_entry)| entry.stat = old_entry.stat);
+    }
+
+    debug_assert!(index.verify_entries().is_ok());
+
+    index
+        .write(gix::index::write::Options::default())
+        .map_err(GitResetHeadError::from_git)
+}
+
+/// Builds the Git index that corresponds to the given working-copy commit
+/// without writing it.
+async fn build_index_for_wc_commit(
+    repo: &dyn Repo,
+    git_repo: &gix::Repository,
+    wc_commit: &Commit,
+    write_intent_to_add: bool,
+) -> Result<gix::index::File, GitResetHeadError> {
     let parent_tree = wc_commit.parent_tree(repo).await?;
     // Use the merged parent tree as the Git index, allowing `git diff` to show the
     // same changes as `jj diff`. If the merged parent tree has conflicts, then the
@@ -1904,28 +3320,292 @@ async fn reset_index(
     };
 
     let wc_tree = wc_commit.tree();
-    update_intent_to_add_impl(git_repo, &mut index, &parent_tree, &wc_tree).await?;
+    update_intent_to_add_impl(
+        git_repo,
+        &mut index,
+        &parent_tree,
+        &wc_tree,
+        write_intent_to_add,
+    )
+    .await?;
+    Ok(index)
+}
 
-    // Match entries in the new index with entries in the old index, and copy stat
-    // information if the entry didn't change.
-    if let Some(old_index) = git_repo.try_index().map_err(GitResetHeadError::from_git)? {
+/// How a path in the Git index differs from what jj would write.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GitIndexDiffKind {
+    /// The path is in the Git index, but jj wouldn't add it.
+    Added,
+    /// The path isn't in the Git index, but jj would add it.
+    Removed,
+    /// The path is in both, but with different contents, modes, stages, or
+    /// intent-to-add flags.
+    Modified,
+}
+
+/// Path whose Git index entries differ from what jj would write.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitIndexDiffEntry {
+    /// Path of the index entries.
+    pub path: BString,
+    /// How the entries differ.
+    pub kind: GitIndexDiffKind,
+}
+
+/// Compares the current Git index with the index that would be written for
+/// the given working-copy commit.
+///
+/// Returns the differing paths in index order. Stat information isn't
+/// compared, so an index that merely needs refreshing isn't reported.
+pub async fn diff_git_index(
+    repo: &dyn Repo,
+    wc_commit: &Commit,
+) -> Result<Vec<GitIndexDiffEntry>, GitResetHeadError> {
+    let git_backend = get_git_backend(repo.store())?;
+    let git_repo = git_backend.git_repo();
+    let expected_index = build_index_for_wc_commit(
+        repo,
+        &git_repo,
+        wc_commit,
+        git_backend.write_intent_to_add(),
+    )
+    .await?;
+    let actual_index = git_repo
+        .index_or_empty()
+        .map_err(GitResetHeadError::from_git)?;
+
+    let entries_by_path = |index: &gix::index::State| {
         index
-            .entries_mut_with_paths()
-            .merge_join_by(old_index.entries(), |(entry, path), old_entry| {
-                gix::index::Entry::cmp_filepaths(path, old_entry.path(&old_index))
-                    .then_with(|| entry.stage().cmp(&old_entry.stage()))
+            .entries()
+            .iter()
+            .map(|entry| {
+                let intent_to_add = entry
+                    .flags
+                    .contains(gix::index::entry::Flags::INTENT_TO_ADD);
+                let state = (entry.stage(), entry.id, entry.mode, intent_to_add);
+                (entry.path(index).to_owned(), state)
             })
-            .filter_map(|merged| merged.both())
-            .map(|((entry, _), old_entry)| (entry, old_entry))
-            .filter(|(entry, old_entry)| entry.id == old_entry.id && entry.mode == old_entry.mode)
-            .for_each(|(entry, old_entry)| entry.stat = old_entry.stat);
+            .into_group_map()
+    };
+    let expected_entries = entries_by_path(&expected_index);
+    let actual_entries = entries_by_path(&actual_index);
+    let diff = expected_entries
+        .keys()
+        .chain(actual_entries.keys())
+        .unique()
+        .sorted_by(|a, b| gix::index::Entry::cmp_filepaths(a.as_ref(), b.as_ref()))
+        .filter_map(|path| {
+            let kind = match (expected_entries.get(path), actual_entries.get(path)) {
+                (Some(expected), Some(actual)) if expected == actual => return None,
+                (Some(_), Some(_)) => GitIndexDiffKind::Modified,
+                (Some(_), None) => GitIndexDiffKind::Removed,
+                (None, Some(_)) => GitIndexDiffKind::Added,
+                (None, None) => unreachable!(),
+            };
+            let path = path.clone();
+            Some(GitIndexDiffEntry { path, kind })
+        })
+        .collect();
+    Ok(diff)
+}
+
+/// Stage of a conflicted Git index entry.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GitIndexStage {
+    /// The common ancestor of the conflict sides.
+    Base,
+    /// The first ("ours") side of the conflict.
+    Ours,
+    /// The second ("theirs") side of the conflict.
+    Theirs,
+}
+
+/// Tree built from the Git index by [`tree_from_git_index()`].
+#[derive(Debug)]
+pub struct GitIndexTree {
+    /// Tree with the contents of the Git index.
+    pub tree: MergedTree,
+    /// Paths which have conflicted entries in the Git index.
+    pub conflicted_paths: HashSet<RepoPathBuf>,
+}
+
+/// Builds a tree from the entries of the current Git index.
+///
+/// If `stage` is specified, conflicted entries are resolved to that stage.
+/// Otherwise, they are turned into conflicts. Intent-to-add entries have no
+/// contents in the index, so the values in `wc_tree` are used instead.
+pub async fn tree_from_git_index(
+    repo: &dyn Repo,
+    wc_tree: &MergedTree,
+    stage: Option<GitIndexStage>,
+) -> Result<GitIndexTree, GitResetHeadError> {
+    use gix::index::entry::Stage;
+
+    let git_backend = get_git_backend(repo.store())?;
+    let git_repo = git_backend.git_repo();
+    let index = git_repo
+        .index_or_empty()
+        .map_err(GitResetHeadError::from_git)?;
+    let mut tree_builder = MergedTreeBuilder::new(repo.store().empty_merged_tree());
+    let mut conflicted_paths = HashSet::new();
+    // Entries are sorted by path and then by stage.
+    let entries_by_path = index.entries().iter().chunk_by(|entry| entry.path(&index));
+    for (path, entries) in &entries_by_path {
+        if path == INDEX_DUMMY_CONFLICT_FILE {
+            continue;
+        }
+        let path = str::from_utf8(path)
+            .ok()
+            .and_then(|path| RepoPathBuf::from_internal_string(path).ok())
+            .ok_or_else(|| {
+                GitResetHeadError::from_git(format!("Invalid path in Git index: {path}"))
+            })?;
+        let mut intent_to_add = false;
+        let mut is_conflicted = false;
+        let (mut resolved, mut base, mut ours, mut theirs) = (None, None, None, None);
+        for entry in entries {
+            if entry
+                .flags
+                .contains(gix::index::entry::Flags::INTENT_TO_ADD)
+            {
+                intent_to_add = true;
+                continue;
+            }
+            let value = tree_value_from_index_entry(entry);
+            match entry.stage() {
+                Stage::Unconflicted => resolved = value,
+                Stage::Base => (is_conflicted, base) = (true, value),
+                Stage::Ours => (is_conflicted, ours) = (true, value),
+                Stage::Theirs => (is_conflicted, theirs) = (true, value),
+            }
+        }
+        let value = if intent_to_add {
+            wc_tree.path_value(&path).await?
+        } else if !is_conflicted {
+            Merge::resolved(resolved)
+        } else {
+            conflicted_paths.insert(path.clone());
+            match stage {
+                Some(GitIndexStage::Base) => Merge::resolved(base),
+                Some(GitIndexStage::Ours) => Merge::resolved(ours),
+                Some(GitIndexStage::Theirs) => Merge::resolved(theirs),
+                None => Merge::from_vec(vec![ours, base, theirs]),
+            }
+        };
+        tree_builder.set_or_remove(path, value);
+    }
+    let tree = tree_builder.write_tree().await?;
+    Ok(GitIndexTree {
+        tree,
+        conflicted_paths,
+    })
+}
+
+fn tree_value_from_index_entry(entry: &gix::index::Entry) -> Option<TreeValue> {
+    use gix::index::entry::Mode;
+
+    let id = entry.id.as_bytes();
+    match entry.mode {
+        Mode::FILE | Mode::FILE_EXECUTABLE => Some(TreeValue::File {
+            id: FileId::from_bytes(id),
+            executable: entry.mode == Mode::FILE_EXECUTABLE,
+            copy_id: CopyId::placeholder(),
+        }),
+        Mode::SYMLINK => Some(TreeValue::Symlink(SymlinkId::from_bytes(id))),
+        Mode::COMMIT => Some(TreeValue::GitSubmodule(CommitId::from_bytes(id))),
+        // Directory entries of sparse indexes aren't expanded.
+        _ => None,
     }
+}
 
-    debug_assert!(index.verify_entries().is_ok());
+/// Submodule found in a tree by [`list_submodules()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitSubmodule {
+    /// Path to the submodule.
+    pub path: RepoPathBuf,
+    /// Name of the submodule in `.gitmodules`, if declared there.
+    pub name: Option<String>,
+    /// URL of the submodule in `.gitmodules`, if declared there.
+    pub url: Option<String>,
+    /// Commit recorded in the gitlink tree entry, if any.
+    pub commit_id: Option<CommitId>,
+}
 
-    index
-        .write(gix::index::write::Options::default())
-        .map_err(GitResetHeadError::from_git)
+#[derive(Debug, Error)]
+pub enum GitSubmoduleListError {
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error("The .gitmodules file is conflicted")]
+    ConflictedGitmodules,
+    #[error("Failed to parse .gitmodules file")]
+    InvalidGitmodules(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Lists submodules declared in the `.gitmodules` file of the `tree` and the
+/// gitlink entries of the `tree`, sorted by path.
+///
+/// Submodules declared in `.gitmodules` without a matching gitlink have no
+/// `commit_id`, and gitlinks without a `.gitmodules` entry have no `name`.
+/// Conflicted gitlinks are ignored.
+pub async fn list_submodules(
+    tree: &MergedTree,
+) -> Result<Vec<GitSubmodule>, GitSubmoduleListError> {
+    let mut submodules: BTreeMap<RepoPathBuf, GitSubmodule> = BTreeMap::new();
+    let gitmodules_path = RepoPath::from_internal_string(".gitmodules").unwrap();
+    let gitmodules_id = match tree.path_value(gitmodules_path).await?.into_resolved() {
+        Ok(Some(TreeValue::File { id, .. })) => Some(id),
+        Ok(_) => None,
+        Err(_) => return Err(GitSubmoduleListError::ConflictedGitmodules),
+    };
+    if let Some(id) = gitmodules_id {
+        let mut reader = tree.store().read_file(gitmodules_path, &id).await?;
+        let mut content = vec![];
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| BackendError::ReadFile {
+                path: gitmodules_path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        let file = gix::submodule::File::from_bytes(&content, None, &Default::default())
+            .map_err(|err| GitSubmoduleListError::InvalidGitmodules(err.into()))?;
+        for name in file.names() {
+            let path = file
+                .path(name)
+                .map_err(|err| GitSubmoduleListError::InvalidGitmodules(err.into()))?;
+            let path = RepoPathBuf::from_internal_string(path.to_str_lossy())
+                .map_err(|err| GitSubmoduleListError::InvalidGitmodules(err.into()))?;
+            let url = file
+                .config()
+                .string_by("submodule", Some(name), "url")
+                .map(|url| url.to_str_lossy().into_owned());
+            submodules.insert(
+                path.clone(),
+                GitSubmodule {
+                    path,
+                    name: Some(name.to_str_lossy().into_owned()),
+                    url,
+                    commit_id: None,
+                },
+            );
+        }
+    }
+    for (path, value) in tree.entries() {
+        let Ok(Some(TreeValue::GitSubmodule(id))) = value?.into_resolved() else {
+            continue;
+        };
+        submodules
+            .entry(path.clone())
+            .or_insert_with(|| GitSubmodule {
+                path,
+                name: None,
+                url: None,
+                commit_id: None,
+            })
+            .commit_id = Some(id);
+    }
+    Ok(submodules.into_values().collect())
 }
 
 fn build_index_from_merged_tree(
@@ -2048,17 +3728,29 @@ fn build_index_from_merged_tree(
 ///
 /// Should be called when the diff between the working-copy commit and its
 /// parent(s) has changed.
+///
+/// If `git.write-intent-to-add` is disabled, added files are omitted from the
+/// index instead.
 pub async fn update_intent_to_add(
     repo: &dyn Repo,
     old_tree: &MergedTree,
     new_tree: &MergedTree,
 ) -> Result<(), GitResetHeadError> {
-    let git_repo = get_git_repo(repo.store())?;
+    let git_backend = get_git_backend(repo.store())?;
+    let git_repo = git_backend.git_repo();
+    let write_intent_to_add = git_backend.write_intent_to_add();
     let mut index = git_repo
         .index_or_empty()
         .map_err(GitResetHeadError::from_git)?;
     let mut_index = Arc::make_mut(&mut index);
-    update_intent_to_add_impl(&git_repo, mut_index, old_tree, new_tree).await?;
+    update_intent_to_add_impl(
+        &git_repo,
+        mut_index,
+        old_tree,
+        new_tree,
+        write_intent_to_add,
+    )
+    .await?;
     debug_assert!(mut_index.verify_entries().is_ok());
     mut_index
         .write(gix::index::write::Options::default())
@@ -2072,20 +3764,26 @@ async fn update_intent_to_add_impl(
     index: &mut gix::index::File,
     old_tree: &MergedTree,
     new_tree: &MergedTree,
+    write_intent_to_add: bool,
 ) -> Result<(), GitResetHeadError> {
     let mut diff_stream = old_tree.diff_stream(new_tree, &EverythingMatcher);
     let mut added_paths = vec![];
     let mut removed_paths = HashSet::new();
     while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
         let values = values?;
-        if values.before.is_absent() {
-            let executable = match values.after.as_normal() {
+        if values.before.is_absent() && write_intent_to_add {
+            let mode = match values.after.as_normal() {
                 Some(TreeValue::File {
                     id: _,
-                    executable,
+                    executable: true,
                     copy_id: _,
-                }) => *executable,
-                Some(TreeValue::Symlink(_)) => false,
+                }) => gix::index::entry::Mode::FILE_EXECUTABLE,
+                Some(TreeValue::File {
+                    id: _,
+                    executable: false,
+                    copy_id: _,
+                }) => gix::index::entry::Mode::FILE,
+                Some(TreeValue::Symlink(_)) => gix::index::entry::Mode::SYMLINK,
                 _ => {
                     continue;
                 }
@@ -2094,7 +3792,7 @@ async fn update_intent_to_add_impl(
                 .entry_index_by_path(BStr::new(path.as_internal_file_string()))
                 .is_err()
             {
-                added_paths.push((BString::from(path.into_internal_string()), executable));
+                added_paths.push((BString::from(path.into_internal_string()), mode));
             }
         } else if values.after.is_absent() {
             removed_paths.insert(BString::from(path.into_internal_string()));
@@ -2111,17 +3809,13 @@ async fn update_intent_to_add_impl(
             .write_blob(b"")
             .map_err(GitResetHeadError::from_git)?
             .detach();
-        for (path, executable) in added_paths {
+        for (path, mode) in added_paths {
             // We have checked that the index doesn't have this entry
             index.dangerously_push_entry(
                 gix::index::entry::Stat::default(),
                 empty_blob,
                 gix::index::entry::Flags::INTENT_TO_ADD | gix::index::entry::Flags::EXTENDED,
-                if executable {
-                    gix::index::entry::Mode::FILE_EXECUTABLE
-                } else {
-                    gix::index::entry::Mode::FILE
-                },
+                mode,
                 path.as_ref(),
             );
         }
@@ -2140,6 +3834,50 @@ async fn update_intent_to_add_impl(
     Ok(())
 }
 
+#[derive(Debug, Error)]
+pub enum GitWorktreeRemoveError {
+    #[error(transparent)]
+    Git(Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    Io(#[from] PathError),
+    #[error(transparent)]
+    UnexpectedBackend(#[from] UnexpectedGitBackendError),
+}
+
+/// Removes the Git worktree registered at `workspace_root`, including its
+/// `HEAD` and the `.git` file in the workspace directory.
+///
+/// Returns `false` if no Git worktree is registered at `workspace_root`. The
+/// workspace directory may have already been deleted.
+pub fn remove_git_worktree(
+    store: &Store,
+    workspace_root: &Path,
+) -> Result<bool, GitWorktreeRemoveError> {
+    let git_repo = get_git_repo(store)?;
+    let canonicalize = |path: &Path| dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let workspace_root = canonicalize(workspace_root);
+    let worktrees = git_repo
+        .worktrees()
+        .map_err(|err| GitWorktreeRemoveError::Git(err.into()))?;
+    for worktree in worktrees {
+        // The "gitdir" file may be missing if the worktree is corrupted
+        let Ok(base) = worktree.base() else {
+            continue;
+        };
+        if canonicalize(&base) != workspace_root {
+            continue;
+        }
+        let dot_git_path = workspace_root.join(".git");
+        if dot_git_path.is_file() {
+            fs::remove_file(&dot_git_path).context(&dot_git_path)?;
+        }
+        let worktree_git_dir = worktree.git_dir();
+        fs::remove_dir_all(worktree_git_dir).context(worktree_git_dir)?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
 #[derive(Debug, Error)]
 pub enum GitRemoteManagementError {
     #[error("No git remote named '{}'", .0.as_symbol())]
@@ -2443,32 +4181,29 @@ fn remove_remote_git_refs(
         "{REMOTE_TAG_REF_NAMESPACE}{remote}/",
         remote = remote.as_str()
     );
-    let edits: Vec<_> = itertools::chain(
-        git_repo
-            .references()?
-            .prefixed(bookmark_prefix.as_str())?
-            .map_ok(remove_ref),
-        git_repo
-            .references()?
-            .prefixed(tag_prefix.as_str())?
-            .map_ok(remove_ref),
-    )
-    .try_collect()?;
+    let pull_prefix = format!(
+        "{PULL_REQUEST_REF_NAMESPACE}{remote}/",
+        remote = remote.as_str()
+    );
+    let mut edits = Vec::new();
+    for prefix in [&bookmark_prefix, &tag_prefix, &pull_prefix] {
+        for git_ref in git_repo.references()?.prefixed(prefix.as_str())? {
+            edits.push(remove_ref(git_ref?));
+        }
+    }
     git_repo.edit_references(edits)?;
     Ok(())
 }
 
 fn remove_remote_refs(mut_repo: &mut MutableRepo, remote: &RemoteName) {
     mut_repo.remove_remote(remote);
-    let prefix = format!(
-        "{REMOTE_BOOKMARK_REF_NAMESPACE}{remote}/",
-        remote = remote.as_str()
-    );
+    let prefixes = [REMOTE_BOOKMARK_REF_NAMESPACE, PULL_REQUEST_REF_NAMESPACE]
+        .map(|namespace| format!("{namespace}{remote}/", remote = remote.as_str()));
     let git_refs_to_delete = mut_repo
         .view()
         .git_refs()
         .keys()
-        .filter(|&r| r.as_str().starts_with(&prefix))
+        .filter(|&r| prefixes.iter().any(|prefix| r.as_str().starts_with(prefix)))
         .cloned()
         .collect_vec();
     for git_ref in git_refs_to_delete {
@@ -2553,19 +4288,24 @@ fn rename_remote_git_refs(
             new_remote_name = new_remote_name.as_symbol(),
         ));
         move |old_prefix: &str, new_prefix: &str, old_ref: gix::Reference| {
-            let new_name = BString::new(
-                [
-                    new_prefix.as_bytes(),
-                    &old_ref.name().as_bstr()[old_prefix.len()..],
-                ]
-                .concat(),
-            );
+            let rename = |name: &BStr| -> gix::refs::FullName {
+                let new_name =
+                    BString::new([new_prefix.as_bytes(), &name[old_prefix.len()..]].concat());
+                new_name.try_into().expect("new ref name to be valid")
+            };
+            let new_name = rename(old_ref.name().as_bstr());
+            // Symbolic ref such as "refs/remotes/<remote>/HEAD" should point to
+            // the renamed ref.
+            let new_target = match old_ref.target() {
+                gix::refs::TargetRef::Symbolic(target)
+                    if target.as_bstr().starts_with(old_prefix.as_bytes()) =>
+                {
+                    gix::refs::Target::Symbolic(rename(target.as_bstr()))
+                }
+                target => target.into_owned(),
+            };
             [
-                add_ref(
-                    new_name.try_into().expect("new ref name to be valid"),
-                    old_ref.target().into_owned(),
-                    ref_log_message.clone(),
-                ),
+                add_ref(new_name, new_target, ref_log_message.clone()),
                 remove_ref(old_ref),
             ]
         }
@@ -2573,22 +4313,40 @@ fn rename_remote_git_refs(
 
     let (old_bookmark_prefix, new_bookmark_prefix) = to_prefixes(REMOTE_BOOKMARK_REF_NAMESPACE);
     let (old_tag_prefix, new_tag_prefix) = to_prefixes(REMOTE_TAG_REF_NAMESPACE);
-    let edits: Vec<_> = itertools::chain(
-        git_repo
-            .references()?
-            .prefixed(old_bookmark_prefix.as_str())?
-            .map_ok(|old_ref| to_rename_edits(&old_bookmark_prefix, &new_bookmark_prefix, old_ref)),
-        git_repo
-            .references()?
-            .prefixed(old_tag_prefix.as_str())?
-            .map_ok(|old_ref| to_rename_edits(&old_tag_prefix, &new_tag_prefix, old_ref)),
-    )
-    .flatten_ok()
-    .try_collect()?;
+    let (old_pull_prefix, new_pull_prefix) = to_prefixes(PULL_REQUEST_REF_NAMESPACE);
+    let mut edits = Vec::new();
+    for (old_prefix, new_prefix) in [
+        (&old_bookmark_prefix, &new_bookmark_prefix),
+        (&old_tag_prefix, &new_tag_prefix),
+        (&old_pull_prefix, &new_pull_prefix),
+    ] {
+        for old_ref in git_repo.references()?.prefixed(old_prefix.as_str())? {
+            edits.extend(to_rename_edits(old_prefix, new_prefix, old_ref?));
+        }
+    }
     git_repo.edit_references(edits)?;
     Ok(())
 }
 
+/// Lists remote-tracking bookmark and tag refs of the remote.
+fn list_remote_git_refs(
+    git_repo: &gix::Repository,
+    remote_name: &RemoteName,
+) -> Result<Vec<gix::refs::Reference>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut refs = vec![];
+    for namespace in [
+        REMOTE_BOOKMARK_REF_NAMESPACE,
+        REMOTE_TAG_REF_NAMESPACE,
+        PULL_REQUEST_REF_NAMESPACE,
+    ] {
+        let prefix = format!("{namespace}{remote}/", remote = remote_name.as_str());
+        for reference in git_repo.references()?.prefixed(prefix.as_str())? {
+            refs.push(reference?.detach());
+        }
+    }
+    Ok(refs)
+}
+
 /// Sets the new URLs on the remote. If a URL of given kind is not provided, it
 /// is not changed. I.e. it is not possible to remove a fetch/push URL from a
 /// remote using this method.
@@ -2639,23 +4397,24 @@ fn rename_remote_refs(
     new_remote_name: &RemoteName,
 ) {
     mut_repo.rename_remote(old_remote_name.as_ref(), new_remote_name.as_ref());
-    let prefix = format!(
-        "{REMOTE_BOOKMARK_REF_NAMESPACE}{remote}/",
-        remote = old_remote_name.as_str()
-    );
     let git_refs = mut_repo
         .view()
         .git_refs()
         .iter()
         .filter_map(|(old, target)| {
-            old.as_str().strip_prefix(&prefix).map(|p| {
-                let new: GitRefNameBuf = format!(
-                    "{REMOTE_BOOKMARK_REF_NAMESPACE}{remote}/{p}",
-                    remote = new_remote_name.as_str()
-                )
-                .into();
-                (old.clone(), new, target.clone())
-            })
+            [REMOTE_BOOKMARK_REF_NAMESPACE, PULL_REQUEST_REF_NAMESPACE]
+                .into_iter()
+                .find_map(|namespace| {
+                    let p = old
+                        .as_str()
+                        .strip_prefix(namespace)?
+                        .strip_prefix(old_remote_name.as_str())?
+                        .strip_prefix('/')?;
+                    let new: GitRefNameBuf =
+                        format!("{namespace}{remote}/{p}", remote = new_remote_name.as_str())
+                            .into();
+                    Some((old.clone(), new, target.clone()))
+                })
         })
         .collect_vec();
     for (old, new, target) in git_refs {
@@ -2676,6 +4435,25 @@ pub enum GitFetchError {
     RejectedUpdates(Vec<GitRefNameBuf>),
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
+    #[error("Unexpected Git error when saving or restoring remote-tracking refs")]
+    InternalGitError(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl GitFetchError {
+    fn from_git(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::InternalGitError(source.into())
+    }
+
+    /// Returns the object hash algorithm of the remote if the fetch failed
+    /// because it differs from the local repository's.
+    pub fn mismatched_remote_object_hash(&self) -> Option<gix::hash::Kind> {
+        match self {
+            Self::Subprocess(GitSubprocessError::MismatchedObjectHash { remote, .. }) => {
+                remote.parse().ok()
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -2690,6 +4468,7 @@ struct FetchedRefs {
     remote: RemoteNameBuf,
     bookmark_matcher: StringMatcher,
     tag_matcher: StringMatcher,
+    pull_requests: bool,
 }
 
 /// Name patterns that will be transformed to Git refspecs.
@@ -2712,6 +4491,64 @@ pub struct ExpandedFetchRefSpecs {
     expr: GitFetchRefExpression,
     refspecs: Vec<RefSpec>,
     negative_refspecs: Vec<NegativeRefSpec>,
+    /// Whether the heads of pull requests are fetched.
+    pull_requests: bool,
+}
+
+/// Forge-specific namespace of refs pointing to the heads of pull requests.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GitPullRequestRefs {
+    /// `refs/pull/<number>/head` as used by GitHub and Gitea.
+    GitHub,
+    /// `refs/merge-requests/<number>/head` as used by GitLab.
+    GitLab,
+}
+
+impl GitPullRequestRefs {
+    /// Bookmark name prefix under which pull request heads are imported.
+    pub const BOOKMARK_PREFIX: &str = "pr/";
+
+    /// Guesses the forge from the host name of the remote URL.
+    pub fn detect_from_url(url: &gix::Url) -> Option<Self> {
+        let host = url.host()?.to_ascii_lowercase();
+        host.split('.').find_map(|label| match label {
+            "github" | "gitea" | "codeberg" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            _ => None,
+        })
+    }
+
+    fn source_glob(self) -> &'static str {
+        match self {
+            Self::GitHub => "refs/pull/*/head",
+            Self::GitLab => "refs/merge-requests/*/head",
+        }
+    }
+}
+
+impl ExpandedFetchRefSpecs {
+    /// Adds a refspec to fetch the heads of pull requests. They are fetched
+    /// into a separate ref namespace, and imported as remote bookmarks named
+    /// `pr/<number>`. If the remote also has a branch of the same name, the
+    /// branch is imported instead.
+    pub fn add_pull_requests(&mut self, remote: &RemoteName, kind: GitPullRequestRefs) {
+        let prefix = GitPullRequestRefs::BOOKMARK_PREFIX;
+        self.refspecs.push(RefSpec::forced(
+            kind.source_glob(),
+            format!(
+                "{PULL_REQUEST_REF_NAMESPACE}{remote}/{prefix}*",
+                remote = remote.as_str()
+            ),
+        ));
+        self.pull_requests = true;
+    }
+
+    /// Restricts the tags to be imported. Tags not matching the `expr` are
+    /// fetched into the Git repo, but not imported.
+    pub fn restrict_imported_tags(&mut self, expr: StringExpression) {
+        let tag = mem::replace(&mut self.expr.tag, StringExpression::none());
+        self.expr.tag = tag.intersection(expr);
+    }
 }
 
 #[derive(Error, Debug)]
@@ -2778,6 +4615,7 @@ pub fn expand_fetch_refspecs(
         expr,
         refspecs,
         negative_refspecs,
+        pull_requests: false,
     })
 }
 
@@ -3015,6 +4853,52 @@ fn parse_fetch_refspec(
     }
 }
 
+/// How much history `git fetch` should download.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GitFetchDepth {
+    /// Limit the history to the given number of commits from the tips
+    /// (`--depth`.)
+    Depth(NonZeroU32),
+    /// Deepen the history of a shallow repository by the given number of
+    /// commits from the current shallow boundary (`--deepen`.)
+    Deepen(NonZeroU32),
+    /// Fetch the full history of a shallow repository (`--unshallow`.)
+    Unshallow,
+}
+
+/// Which remote-tracking refs are deleted by `git fetch` if they no longer
+/// exist on the remote.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GitFetchPrune {
+    /// Whether to delete remote bookmarks.
+    pub bookmarks: bool,
+    /// Whether to delete remote tags.
+    pub tags: bool,
+}
+
+impl GitFetchPrune {
+    /// Deletes all kinds of refs.
+    pub const ALL: Self = Self {
+        bookmarks: true,
+        tags: true,
+    };
+    /// Keeps all kinds of refs.
+    pub const NONE: Self = Self {
+        bookmarks: false,
+        tags: false,
+    };
+
+    /// Reads `fetch.prune` and `fetch.pruneTags` from the Git config. Returns
+    /// `None` if `fetch.prune` isn't set. As in Git, `fetch.pruneTags` has no
+    /// effect unless `fetch.prune` is also set.
+    pub fn from_git_config(git_repo: &gix::Repository) -> Option<Self> {
+        let config = git_repo.config_snapshot();
+        let bookmarks = config.boolean("fetch.prune")?;
+        let tags = bookmarks && config.boolean("fetch.pruneTags").unwrap_or(false);
+        Some(Self { bookmarks, tags })
+    }
+}
+
 /// Helper struct to execute multiple `git fetch` operations
 pub struct GitFetch<'a> {
     mut_repo: &'a mut MutableRepo,
@@ -3022,6 +4906,10 @@ pub struct GitFetch<'a> {
     git_ctx: GitSubprocessContext,
     import_options: &'a GitImportOptions,
     fetched: Vec<FetchedRefs>,
+    prune: GitFetchPrune,
+    saved_remote_refs: SavedRemoteRefs,
+    /// Shallow roots before the history was deepened by `fetch()`.
+    old_shallow_ids: Option<Vec<CommitId>>,
 }
 
 impl<'a> GitFetch<'a> {
@@ -3033,15 +4921,28 @@ impl<'a> GitFetch<'a> {
         let git_backend = get_git_backend(mut_repo.store())?;
         let git_repo = Box::new(git_backend.git_repo());
         let git_ctx = GitSubprocessContext::from_git_backend(git_backend, subprocess_options);
+        let saved_remote_refs = SavedRemoteRefs {
+            git_repo: git_backend.git_repo(),
+            refs: HashMap::new(),
+        };
         Ok(GitFetch {
             mut_repo,
             git_repo,
             git_ctx,
             import_options,
             fetched: vec![],
+            prune: GitFetchPrune::ALL,
+            saved_remote_refs,
+            old_shallow_ids: None,
         })
     }
 
+    /// Sets which remote-tracking refs are deleted if they no longer exist on
+    /// the remote. All of them are deleted by default.
+    pub fn set_prune(&mut self, prune: GitFetchPrune) {
+        self.prune = prune;
+    }
+
     /// Perform a `git fetch` on the local git repo, updating the
     /// remote-tracking branches in the git repo.
     ///
@@ -3053,11 +4954,12 @@ impl<'a> GitFetch<'a> {
         remote_name: &RemoteName,
         ExpandedFetchRefSpecs {
             expr,
-            refspecs: mut remaining_refspecs,
+            refspecs,
             negative_refspecs,
+            pull_requests,
         }: ExpandedFetchRefSpecs,
         callback: &mut dyn GitSubprocessCallback,
-        depth: Option<NonZeroU32>,
+        depth: Option<GitFetchDepth>,
     ) -> Result<(), GitFetchError> {
         validate_remote_name(remote_name)?;
 
@@ -3070,12 +4972,70 @@ impl<'a> GitFetch<'a> {
             return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
         }
 
-        if remaining_refspecs.is_empty() {
+        if refspecs.is_empty() {
             // Don't fall back to the base refspecs.
             return Ok(());
         }
+        if depth.is_some() && self.old_shallow_ids.is_none() {
+            self.old_shallow_ids = Some(shallow_commit_ids(self.mut_repo.store())?);
+        }
 
+        // git fetch --prune deletes stale refs of all kinds matching the
+        // refspecs, so tags are fetched separately if only one of bookmarks and
+        // tags should be pruned.
+        let (tag_refspecs, other_refspecs): (Vec<_>, Vec<_>) =
+            if self.prune.bookmarks == self.prune.tags {
+                (vec![], refspecs)
+            } else {
+                refspecs
+                    .into_iter()
+                    .partition(|refspec| refspec.destination.starts_with(REMOTE_TAG_REF_NAMESPACE))
+            };
         let mut branches_to_prune = Vec::new();
+        for (refspecs, prune) in [
+            (other_refspecs, self.prune.bookmarks),
+            (tag_refspecs, self.prune.tags),
+        ] {
+            self.fetch_refspecs(
+                remote_name,
+                refspecs,
+                &negative_refspecs,
+                prune,
+                callback,
+                depth,
+                &mut branches_to_prune,
+            )?;
+        }
+
+        // Even if git fetch has --prune, if a branch is not found it will not be
+        // pruned on fetch
+        if self.prune.bookmarks {
+            self.git_ctx.spawn_branch_prune(&branches_to_prune)?;
+        }
+
+        self.fetched.push(FetchedRefs {
+            remote: remote_name.to_owned(),
+            bookmark_matcher: expr.bookmark.to_matcher(),
+            tag_matcher: expr.tag.to_matcher(),
+            pull_requests,
+        });
+        Ok(())
+    }
+
+    /// Runs `git fetch` with the given refspecs. Refspecs of refs missing on
+    /// the remote are dropped, and the corresponding branches are added to
+    /// `branches_to_prune`.
+    #[expect(clippy::too_many_arguments)]
+    fn fetch_refspecs(
+        &self,
+        remote_name: &RemoteName,
+        mut remaining_refspecs: Vec<RefSpec>,
+        negative_refspecs: &[NegativeRefSpec],
+        prune: bool,
+        callback: &mut dyn GitSubprocessCallback,
+        depth: Option<GitFetchDepth>,
+        branches_to_prune: &mut Vec<String>,
+    ) -> Result<(), GitFetchError> {
         // git unfortunately errors out if one of the many refspecs is not found
         //
         // our approach is to filter out failures and retry,
@@ -3087,7 +5047,8 @@ impl<'a> GitFetch<'a> {
             let status = self.git_ctx.spawn_fetch(
                 remote_name,
                 &remaining_refspecs,
-                &negative_refspecs,
+                negative_refspecs,
+                prune,
                 callback,
                 depth,
             )?;
@@ -3112,16 +5073,46 @@ impl<'a> GitFetch<'a> {
             let names = updates.rejected.into_iter().map(|(name, _)| name).collect();
             return Err(GitFetchError::RejectedUpdates(names));
         }
+        Ok(())
+    }
 
-        // Even if git fetch has --prune, if a branch is not found it will not be
-        // pruned on fetch
-        self.git_ctx.spawn_branch_prune(&branches_to_prune)?;
+    /// Records the current remote-tracking Git refs of the remote so they can
+    /// be restored later by `restore_remote_refs()`.
+    ///
+    /// This should be called before `fetch()`ing from the remote. The saved
+    /// refs are also restored when `GitFetch` is dropped, so they are restored
+    /// even if the fetch or import fails.
+    pub fn save_remote_refs(&mut self, remote_name: &RemoteName) -> Result<(), GitFetchError> {
+        self.saved_remote_refs.save(remote_name)
+    }
 
-        self.fetched.push(FetchedRefs {
-            remote: remote_name.to_owned(),
-            bookmark_matcher: expr.bookmark.to_matcher(),
-            tag_matcher: expr.tag.to_matcher(),
-        });
+    /// Resets the remote-tracking Git refs to the states recorded by
+    /// `save_remote_refs()`.
+    ///
+    /// The fetched objects are kept in the Git repository, but the refs
+    /// pointing to them are reverted so that the next import won't see the
+    /// fetched changes.
+    #[tracing::instrument(skip(self))]
+    pub fn restore_remote_refs(&mut self) -> Result<(), GitFetchError> {
+        self.saved_remote_refs.restore()
+    }
+
+    /// Reindexes the former shallow roots and their descendants if the
+    /// shallow boundary was moved by `fetch()`. The ancestors made available
+    /// by deepening the history are indexed, and will be imported with the
+    /// refs.
+    async fn reindex_deepened_history(
+        &mut self,
+        old_shallow_ids: &[CommitId],
+    ) -> Result<(), GitImportError> {
+        let store = self.mut_repo.store().clone();
+        let new_shallow_ids = shallow_commit_ids(&store).map_err(GitImportError::from_git)?;
+        if new_shallow_ids == old_shallow_ids {
+            return Ok(());
+        }
+        get_git_backend(&store)?.refresh_shallow_commits();
+        store.clear_caches();
+        self.mut_repo.reindex_commits(old_shallow_ids).await?;
         Ok(())
     }
 
@@ -3152,6 +5143,9 @@ impl<'a> GitFetch<'a> {
     #[tracing::instrument(skip(self))]
     pub async fn import_refs(&mut self) -> Result<GitImportStats, GitImportError> {
         tracing::debug!("import_refs");
+        if let Some(old_shallow_ids) = self.old_shallow_ids.take() {
+            self.reindex_deepened_history(&old_shallow_ids).await?;
+        }
         let all_remote_tags = true;
         let refs_to_import = diff_refs_to_import(
             self.mut_repo.view(),
@@ -3169,6 +5163,11 @@ impl<'a> GitFetch<'a> {
                     .filter(|fetched| fetched.remote == symbol.remote)
                     .any(|fetched| fetched.tag_matcher.is_match(symbol.name.as_str())),
             },
+            |symbol| {
+                self.fetched
+                    .iter()
+                    .any(|fetched| fetched.remote == symbol.remote && fetched.pull_requests)
+            },
         )?;
         let import_stats =
             import_refs_inner(self.mut_repo, refs_to_import, self.import_options).await?;
@@ -3179,15 +5178,196 @@ impl<'a> GitFetch<'a> {
     }
 }
 
+/// Remote-tracking Git refs to be restored after a dry-run fetch.
+///
+/// Any refs not restored yet are restored when dropped.
+struct SavedRemoteRefs {
+    git_repo: gix::Repository,
+    refs: HashMap<RemoteNameBuf, HashMap<gix::refs::FullName, gix::refs::Target>>,
+}
+
+impl SavedRemoteRefs {
+    fn save(&mut self, remote_name: &RemoteName) -> Result<(), GitFetchError> {
+        if self.refs.contains_key(remote_name) {
+            return Ok(());
+        }
+        let refs = list_remote_git_refs(&self.git_repo, remote_name)
+            .map_err(GitFetchError::from_git)?
+            .into_iter()
+            .map(|reference| (reference.name, reference.target))
+            .collect();
+        self.refs.insert(remote_name.to_owned(), refs);
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<(), GitFetchError> {
+        let ref_log_message = BString::from("restored by jj git fetch --dry-run");
+        let mut edits = vec![];
+        for (remote_name, saved_refs) in self.refs.drain() {
+            let current_refs = list_remote_git_refs(&self.git_repo, &remote_name)
+                .map_err(GitFetchError::from_git)?;
+            for reference in &current_refs {
+                if !saved_refs.contains_key(&reference.name) {
+                    edits.push(gix::refs::transaction::RefEdit {
+                        change: gix::refs::transaction::Change::Delete {
+                            expected: gix::refs::transaction::PreviousValue::Any,
+                            log: gix::refs::transaction::RefLog::AndReference,
+                        },
+                        name: reference.name.clone(),
+                        deref: false,
+                    });
+                }
+            }
+            let current_targets: HashMap<_, _> = current_refs
+                .iter()
+                .map(|reference| (&reference.name, &reference.target))
+                .collect();
+            for (name, target) in saved_refs {
+                if current_targets.get(&name) == Some(&&target) {
+                    continue;
+                }
+                edits.push(gix::refs::transaction::RefEdit {
+                    change: gix::refs::transaction::Change::Update {
+                        log: gix::refs::transaction::LogChange {
+                            mode: gix::refs::transaction::RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: ref_log_message.clone(),
+                        },
+                        expected: gix::refs::transaction::PreviousValue::Any,
+                        new: target,
+                    },
+                    name,
+                    deref: false,
+                });
+            }
+        }
+        if !edits.is_empty() {
+            self.git_repo
+                .edit_references(edits)
+                .map_err(GitFetchError::from_git)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SavedRemoteRefs {
+    fn drop(&mut self) {
+        if let Err(err) = self.restore() {
+            tracing::warn!(?err, "failed to restore remote-tracking Git refs");
+        }
+    }
+}
+
+/// Returns the shallow boundary commits of the underlying Git repository.
+///
+/// The commits are listed in the order of the `shallow` file. The list is
+/// empty if the repository isn't shallow.
+pub fn shallow_commit_ids(store: &Store) -> Result<Vec<CommitId>, GitFetchError> {
+    let git_repo = get_git_backend(store)
+        .map_err(GitFetchError::from_git)?
+        .git_repo();
+    let shallow_ids = git_repo
+        .shallow_commits()
+        .map_err(GitFetchError::from_git)?
+        .iter()
+        .flat_map(|oids| oids.iter())
+        .map(|oid| CommitId::from_bytes(oid.as_bytes()))
+        .collect();
+    Ok(shallow_ids)
+}
+
+/// Returns the parents of the given commits which are missing in the underlying
+/// Git repository.
+///
+/// Commits which are missing themselves are skipped. The parents of shallow
+/// commits aren't considered missing.
+pub fn find_missing_parents<'a>(
+    store: &Store,
+    commit_ids: impl IntoIterator<Item = &'a CommitId>,
+) -> Result<Vec<CommitId>, GitFetchError> {
+    let git_repo = get_git_backend(store)
+        .map_err(GitFetchError::from_git)?
+        .git_repo();
+    let shallow_oids: HashSet<gix::ObjectId> = git_repo
+        .shallow_commits()
+        .map_err(GitFetchError::from_git)?
+        .iter()
+        .flat_map(|oids| oids.iter().copied())
+        .collect();
+    let mut missing_ids = Vec::new();
+    for id in commit_ids {
+        let oid = oid_from_commit_id(id);
+        if shallow_oids.contains(oid) {
+            continue;
+        }
+        let Some(object) = git_repo
+            .try_find_object(oid)
+            .map_err(GitFetchError::from_git)?
+        else {
+            continue;
+        };
+        let Ok(commit) = object.try_into_commit() else {
+            continue;
+        };
+        for parent_oid in commit.parent_ids() {
+            if !git_repo.has_object(parent_oid) {
+                missing_ids.push(CommitId::from_bytes(parent_oid.as_bytes()));
+            }
+        }
+    }
+    missing_ids.sort_unstable();
+    missing_ids.dedup();
+    Ok(missing_ids)
+}
+
+/// Fetches the given commits and their ancestors from the remote without
+/// updating the refs or the repo view.
+///
+/// This can be used to restore commits missing in the underlying Git
+/// repository. Use [`find_missing_parents()`] to find such commits.
+pub fn fetch_commits(
+    store: &Store,
+    subprocess_options: GitSubprocessOptions,
+    remote_name: &RemoteName,
+    commit_ids: &[CommitId],
+    callback: &mut dyn GitSubprocessCallback,
+) -> Result<(), GitFetchError> {
+    validate_remote_name(remote_name)?;
+    let git_backend = get_git_backend(store).map_err(GitFetchError::from_git)?;
+    let git_repo = git_backend.git_repo();
+    if git_repo.try_find_remote(remote_name.as_str()).is_none() {
+        return Err(GitFetchError::NoSuchRemote(remote_name.to_owned()));
+    }
+    let git_ctx = GitSubprocessContext::from_git_backend(git_backend, subprocess_options);
+    let oids = commit_ids
+        .iter()
+        .map(owned_oid_from_commit_id)
+        .collect_vec();
+    git_ctx.spawn_fetch_objects(remote_name, &oids, callback)?;
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub enum GitPushError {
     #[error("No git remote named '{}'", .0.as_symbol())]
     NoSuchRemote(RemoteNameBuf),
     #[error(transparent)]
     RemoteName(#[from] GitRemoteNameError),
+    #[error("The remote doesn't support signed pushes")]
+    UnsupportedSignedPush,
+    #[error(
+        "The remote HEAD is {}, but expected {expected}",
+        actual.map_or("not set".to_owned(), |id| format!("at {id}"))
+    )]
+    UnexpectedRemoteHead {
+        expected: gix::ObjectId,
+        actual: Option<gix::ObjectId>,
+    },
     #[error(transparent)]
     Subprocess(#[from] GitSubprocessError),
     #[error(transparent)]
+    Export(#[from] GitExportError),
+    #[error(transparent)]
     UnexpectedBackend(#[from] UnexpectedGitBackendError),
 }
 
@@ -3213,6 +5393,35 @@ pub struct GitRefUpdate {
 pub struct GitPushOptions {
     /// `--push-option` arguments.
     pub remote_push_options: Vec<String>,
+    /// Whether to send a self-contained pack instead of a thin pack
+    /// (`--no-thin`.)
+    pub no_thin: bool,
+    /// Whether to update either all refs or none of them on the remote
+    /// (`--atomic`.)
+    pub atomic: bool,
+    /// Whether to send a push certificate (`--signed`.)
+    pub signed: GitPushSigned,
+    /// Commit the remote's `HEAD` is expected to point to. If set, the push
+    /// is aborted unless the remote `HEAD` matches. The remote `HEAD` is
+    /// looked up right before the push, but not atomically with it.
+    pub expected_remote_head: Option<gix::ObjectId>,
+    /// Remote whose fetched tags are pushed as they are. If set, the annotated
+    /// tag objects of this remote are pushed instead of the local Git tags, and
+    /// the tag objects last known on the destination remote are expected
+    /// regardless of whether they are tracked.
+    pub tag_source_remote: Option<RemoteNameBuf>,
+}
+
+/// Mode of sending a push certificate signed with the key configured in Git.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GitPushSigned {
+    /// Don't sign the push.
+    #[default]
+    No,
+    /// Sign the push, failing if the server doesn't support signed pushes.
+    Yes,
+    /// Sign the push only if the server supports signed pushes.
+    IfAsked,
 }
 
 /// Pushes the specified refs and updates the repo view accordingly.
@@ -3228,11 +5437,19 @@ pub fn push_refs(
 
     let git_repo = get_git_repo(mut_repo.store())?;
     let to_tag_target = |name: &RefName, remote: &RemoteName, id: &CommitId| {
-        let remote_matcher = StringMatcher::exact(remote);
         let oid = owned_oid_from_commit_id(id);
-        find_git_tag_oid_to_copy(mut_repo.view(), &git_repo, name, &remote_matcher, &oid)
-            .unwrap_or(oid)
+        if options.tag_source_remote.is_some() {
+            find_remote_git_tag_oid(&git_repo, name.to_remote_symbol(remote), &oid)
+        } else {
+            let remote_matcher = StringMatcher::exact(remote);
+            find_git_tag_oid_to_copy(mut_repo.view(), &git_repo, name, &remote_matcher, &oid)
+        }
+        .unwrap_or(oid)
     };
+    let tag_source_remote = options
+        .tag_source_remote
+        .as_deref()
+        .unwrap_or(REMOTE_NAME_FOR_LOCAL_GIT_REPO);
     let ref_updates = itertools::chain(
         targets.bookmarks.iter().map(|(name, update)| GitRefUpdate {
             qualified_name: format!("refs/heads/{name}", name = name.as_str()).into(),
@@ -3250,7 +5467,7 @@ pub fn push_refs(
                 after: update
                     .after
                     .as_ref()
-                    .map(|id| to_tag_target(name, REMOTE_NAME_FOR_LOCAL_GIT_REPO, id)),
+                    .map(|id| to_tag_target(name, tag_source_remote, id)),
             },
         }),
     )
@@ -3282,7 +5499,13 @@ pub fn push_refs(
     // case, this only updates our record about the last exported state.
     let unexported_bookmarks = {
         let refs = build_pushed_bookmarks_to_export(remote, pushed_bookmark_updates());
-        export_refs_to_git(mut_repo, &git_repo, GitRefKind::Bookmark, refs)
+        let no_tags = RefsToExport {
+            to_update: vec![],
+            to_delete: vec![],
+            failed: vec![],
+        };
+        let plan = plan_refs_export(mut_repo.view(), &git_repo, refs, no_tags)?;
+        export_refs_to_git(mut_repo, &git_repo, plan)?.failed_bookmarks
     };
     // Update remote tags so we can look up annotated tag oid without fetching.
     // Since remote tags should never be imported without fetching from the
@@ -3375,7 +5598,19 @@ pub fn push_updates(
         .map(|full_refspec| RefToPush::new(full_refspec, &qualified_remote_refs_expected_locations))
         .collect();
 
-    let mut push_stats = git_ctx.spawn_push(remote_name, &refs_to_push, callback, options)?;
+    if let Some(expected) = options.expected_remote_head {
+        let actual = git_ctx.spawn_ls_remote_head(remote_name)?;
+        if actual != Some(expected) {
+            return Err(GitPushError::UnexpectedRemoteHead { expected, actual });
+        }
+    }
+
+    let mut push_stats = git_ctx
+        .spawn_push(remote_name, &refs_to_push, callback, options)
+        .map_err(|err| match err {
+            GitSubprocessError::UnsupportedSignedPush => GitPushError::UnsupportedSignedPush,
+            err => err.into(),
+        })?;
     push_stats.pushed.sort();
     push_stats.rejected.sort();
     push_stats.remote_rejected.sort();
@@ -3540,4 +5775,29 @@ mod tests {
             split("~(a|*)"),
             @r#"([Substring("")], [Exact("a"), Glob(GlobPattern("*"))])"#);
     }
+
+    #[test]
+    fn test_detect_pull_request_refs() {
+        let detect =
+            |url: &str| GitPullRequestRefs::detect_from_url(&gix::url::parse(url.into()).unwrap());
+        assert_eq!(
+            detect("git@github.com:jj-vcs/jj.git"),
+            Some(GitPullRequestRefs::GitHub)
+        );
+        assert_eq!(
+            detect("https://codeberg.org/foo/bar"),
+            Some(GitPullRequestRefs::GitHub)
+        );
+        assert_eq!(
+            detect("https://GitLab.com/foo/bar.git"),
+            Some(GitPullRequestRefs::GitLab)
+        );
+        assert_eq!(
+            detect("https://gitlab.example.com/foo/bar.git"),
+            Some(GitPullRequestRefs::GitLab)
+        );
+        // Only the host name is considered
+        assert_eq!(detect("https://example.com/github/repo.git"), None);
+        assert_eq!(detect("/home/user/github/repo"), None);
+    }
 }
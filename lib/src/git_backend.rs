@@ -14,6 +14,7 @@
 
 #![expect(missing_docs)]
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::fmt::Debug;
@@ -38,13 +39,14 @@ use futures::AsyncReadExt as _;
 use futures::StreamExt as _;
 use futures::io::Cursor;
 use futures::stream::BoxStream;
+use gix::bstr::BStr;
 use gix::bstr::BString;
+use gix::bstr::ByteSlice as _;
 use gix::objs::CommitRefIter;
 use gix::objs::Exists as _;
 use gix::objs::Write as _;
 use gix::objs::WriteTo as _;
 use itertools::Itertools as _;
-use once_cell::sync::OnceCell as OnceLock;
 use pollster::FutureExt as _;
 use prost::Message as _;
 use smallvec::SmallVec;
@@ -165,6 +167,9 @@ pub enum GitGcError {
     GcCommandErrorStatus(ExitStatus),
 }
 
+/// Commits whose parents are replaced, mapped to the new parents.
+type GraftedParentIds = HashMap<CommitId, Vec<CommitId>>;
+
 pub struct GitBackend {
     // While gix::Repository can be created from gix::ThreadSafeRepository, it's
     // cheaper to cache the thread-local instance behind a mutex than creating
@@ -175,11 +180,13 @@ pub struct GitBackend {
     root_commit_id: CommitId,
     root_change_id: ChangeId,
     empty_tree_id: TreeId,
-    shallow_root_ids: OnceLock<Vec<CommitId>>,
+    grafted_parent_ids: Mutex<Option<Arc<GraftedParentIds>>>,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
     git_executable: PathBuf,
+    export_attach_head: bool,
     write_change_id_header: bool,
+    write_intent_to_add: bool,
 }
 
 impl GitBackend {
@@ -203,11 +210,13 @@ impl GitBackend {
             root_commit_id,
             root_change_id,
             empty_tree_id,
-            shallow_root_ids: OnceLock::new(),
+            grafted_parent_ids: Mutex::new(None),
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
             git_executable: git_settings.executable_path,
+            export_attach_head: git_settings.export_attach_head,
             write_change_id_header: git_settings.write_change_id_header,
+            write_intent_to_add: git_settings.write_intent_to_add,
         }
     }
 
@@ -367,23 +376,86 @@ impl GitBackend {
         self.base_repo.work_dir()
     }
 
-    fn shallow_root_ids(&self, git_repo: &gix::Repository) -> BackendResult<&[CommitId]> {
+    /// Whether the Git HEAD should be attached to the exported bookmark
+    /// pointing to it instead of being detached.
+    pub fn export_attach_head(&self) -> bool {
+        self.export_attach_head
+    }
+
+    /// Whether newly added files should be marked as intent-to-add in the Git
+    /// index.
+    pub fn write_intent_to_add(&self) -> bool {
+        self.write_intent_to_add
+    }
+
+    /// Reads the values of the extra header `name` (e.g. `mergetag`) from the
+    /// Git commit object, in order of appearance. A header may be repeated,
+    /// such as `mergetag` in an octopus merge of several tags. Continuation
+    /// lines are joined with `\n`.
+    pub fn read_commit_headers(&self, id: &CommitId, name: &str) -> BackendResult<Vec<BString>> {
+        if *id == self.root_commit_id {
+            return Ok(vec![]);
+        }
+        let locked_repo = self.lock_git_repo();
+        let git_commit_id = validate_git_object_id(&locked_repo, id)?;
+        let git_object = locked_repo
+            .find_object(git_commit_id)
+            .map_err(|err| map_not_found_err(err, id))?;
+        let commit = git_object
+            .try_to_commit_ref()
+            .map_err(|err| to_read_object_err(err, id))?;
+        Ok(commit
+            .extra_headers()
+            .find_all(name)
+            .map(|value| value.to_owned())
+            .collect())
+    }
+
+    /// Returns commits whose parents should be replaced, as specified by the
+    /// `info/grafts` file. Shallow roots are mapped to no parents.
+    fn grafted_parent_ids(
+        &self,
+        git_repo: &gix::Repository,
+    ) -> BackendResult<Arc<GraftedParentIds>> {
         // The list of shallow roots is cached by gix, but it's still expensive
         // to stat file on every read_object() call. Refreshing shallow roots is
-        // also bad for consistency reasons.
-        self.shallow_root_ids
-            .get_or_try_init(|| {
-                let maybe_oids = git_repo
-                    .shallow_commits()
-                    .map_err(|err| BackendError::Other(err.into()))?;
-                let commit_ids = maybe_oids.map_or(vec![], |oids| {
-                    oids.iter()
-                        .map(|oid| CommitId::from_bytes(oid.as_bytes()))
-                        .collect()
-                });
-                Ok(commit_ids)
-            })
-            .map(AsRef::as_ref)
+        // also bad for consistency reasons, so it has to be requested by
+        // refresh_shallow_commits().
+        let mut locked_ids = self.grafted_parent_ids.lock().unwrap();
+        if let Some(ids) = locked_ids.as_ref() {
+            return Ok(ids.clone());
+        }
+        let ids = Arc::new({
+            let grafts_path = git_repo.common_dir().join("info").join("grafts");
+            let mut grafted_parent_ids = match fs::read(&grafts_path) {
+                Ok(data) => parse_git_grafts(&data).map_err(|line| {
+                    BackendError::Other(
+                        format!("Invalid graft {line:?} in {}", grafts_path.display()).into(),
+                    )
+                })?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+                Err(err) => return Err(BackendError::Other(err.into())),
+            };
+            let maybe_oids = git_repo
+                .shallow_commits()
+                .map_err(|err| BackendError::Other(err.into()))?;
+            for oid in maybe_oids.iter().flat_map(|oids| oids.iter()) {
+                grafted_parent_ids.insert(CommitId::from_bytes(oid.as_bytes()), vec![]);
+            }
+            grafted_parent_ids
+        });
+        *locked_ids = Some(ids.clone());
+        Ok(ids)
+    }
+
+    /// Reloads the shallow roots after the shallow boundary of the Git
+    /// repository has moved, e.g. by `git fetch --deepen`.
+    ///
+    /// Commits previously read by the [`Store`](crate::store::Store) are
+    /// cached with the old parents, so the store caches should be cleared as
+    /// well.
+    pub fn refresh_shallow_commits(&self) {
+        *self.grafted_parent_ids.lock().unwrap() = None;
     }
 
     fn cached_extra_metadata_table(&self) -> BackendResult<Arc<ReadonlyTable>> {
@@ -461,7 +533,7 @@ impl GitBackend {
             &mut mut_table,
             &table_lock,
             &head_ids,
-            self.shallow_root_ids(&locked_repo)?,
+            self.grafted_parent_ids(&locked_repo)?.as_ref(),
         )?;
         self.save_extra_metadata_table(mut_table, &table_lock)
     }
@@ -628,10 +700,30 @@ fn extract_root_tree_from_commit(commit: &gix::objs::CommitRef) -> Result<Merge<
     Ok(Merge::from_vec(tree_ids))
 }
 
+/// Parses the Git `info/grafts` file, which maps commits to replacement
+/// parents. Returns the invalid line on error.
+fn parse_git_grafts(data: &[u8]) -> Result<HashMap<CommitId, Vec<CommitId>>, String> {
+    let mut grafted_parent_ids = HashMap::new();
+    for line in data.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+        let invalid_line = || line.to_str_lossy().into_owned();
+        let mut ids = line
+            .fields()
+            .map(|hex| str::from_utf8(hex).ok().and_then(CommitId::try_from_hex));
+        let id = ids.next().flatten().ok_or_else(invalid_line)?;
+        let parent_ids: Vec<_> = ids.collect::<Option<_>>().ok_or_else(invalid_line)?;
+        grafted_parent_ids.insert(id, parent_ids);
+    }
+    Ok(grafted_parent_ids)
+}
+
 fn commit_from_git_without_root_parent(
     id: &CommitId,
     git_object: &gix::Object,
-    is_shallow: bool,
+    grafted_parent_ids: Option<&[CommitId]>,
 ) -> BackendResult<Commit> {
     let decode_err = |err: gix::objs::decode::Error| to_read_object_err(err, id);
     let commit = git_object
@@ -644,10 +736,11 @@ fn commit_from_git_without_root_parent(
         .unwrap_or_else(|| synthetic_change_id_from_git_commit_id(id));
 
     // shallow commits don't have parents their parents actually fetched, so we
-    // discard them here
-    // TODO: This causes issues when a shallow repository is deepened/unshallowed
-    let parents = if is_shallow {
-        vec![]
+    // discard them here. Grafted commits have their parents replaced. If a
+    // shallow repository is deepened, the affected commits have to be
+    // reindexed by MutableRepo::reindex_commits().
+    let parents = if let Some(parent_ids) = grafted_parent_ids {
+        parent_ids.to_vec()
     } else {
         commit
             .parents()
@@ -664,11 +757,11 @@ fn commit_from_git_without_root_parent(
     let root_tree = extract_root_tree_from_commit(&commit)
         .map_err(|()| to_read_object_err("Invalid jj:trees header", id))?;
     // Use lossy conversion as commit message with "mojibake" is still better than
-    // nothing.
-    // TODO: what should we do with commit.encoding?
-    let description = String::from_utf8_lossy(commit.message).into_owned();
-    let author = signature_from_git(commit.author().map_err(decode_err)?);
-    let committer = signature_from_git(commit.committer().map_err(decode_err)?);
+    // nothing. The raw bytes can be reused when the commit is rewritten.
+    let encoding = commit.encoding.filter(|name| !is_utf8_encoding(name));
+    let description = decode_commit_text(commit.message, encoding);
+    let author = signature_from_git(commit.author().map_err(decode_err)?, encoding);
+    let committer = signature_from_git(commit.committer().map_err(decode_err)?, encoding);
 
     // If the commit is signed, extract both the signature and the signed data
     // (which is the commit buffer with the gpgsig header omitted).
@@ -690,7 +783,7 @@ fn commit_from_git_without_root_parent(
             sig: sig.into_owned().into(),
         });
 
-    Ok(Commit {
+    let commit = Commit {
         parents,
         predecessors: vec![],
         // If this commit has associated extra metadata, we may reset this later.
@@ -701,7 +794,8 @@ fn commit_from_git_without_root_parent(
         author,
         committer,
         secure_sig,
-    })
+    };
+    Ok(commit)
 }
 
 /// Extracts change id from commit headers.
@@ -734,19 +828,41 @@ pub fn synthetic_change_id_from_git_commit_id(id: &CommitId) -> ChangeId {
 
 const EMPTY_STRING_PLACEHOLDER: &str = "JJ_EMPTY_STRING";
 
-fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
-    let name = signature.name;
-    let name = if name != EMPTY_STRING_PLACEHOLDER {
-        String::from_utf8_lossy(name).into_owned()
+fn is_utf8_encoding(name: &BStr) -> bool {
+    ["utf-8", "utf8"]
+        .iter()
+        .any(|utf8| name.eq_ignore_ascii_case(utf8.as_bytes()))
+}
+
+fn is_latin1_encoding(name: &BStr) -> bool {
+    ["iso-8859-1", "iso8859-1", "latin1", "latin-1"]
+        .iter()
+        .any(|latin1| name.eq_ignore_ascii_case(latin1.as_bytes()))
+}
+
+/// Decodes commit message or signature field stored in the given `encoding`.
+///
+/// Only Latin-1 is decoded specially. Text in other encodings is decoded as
+/// UTF-8 lossily.
+fn decode_commit_text(text: &[u8], encoding: Option<&BStr>) -> String {
+    if encoding.is_some_and(is_latin1_encoding) {
+        text.iter().copied().map(char::from).collect()
     } else {
-        "".to_string()
-    };
-    let email = signature.email;
-    let email = if email != EMPTY_STRING_PLACEHOLDER {
-        String::from_utf8_lossy(email).into_owned()
+        String::from_utf8_lossy(text).into_owned()
+    }
+}
+
+fn decode_signature_field(field: &[u8], encoding: Option<&BStr>) -> String {
+    if field != EMPTY_STRING_PLACEHOLDER.as_bytes() {
+        decode_commit_text(field, encoding)
     } else {
         "".to_string()
-    };
+    }
+}
+
+fn signature_from_git(signature: gix::actor::SignatureRef, encoding: Option<&BStr>) -> Signature {
+    let name = decode_signature_field(signature.name, encoding);
+    let email = decode_signature_field(signature.email, encoding);
     let time = signature.time().unwrap_or_default();
     let timestamp = MillisSinceEpoch(time.seconds * 1000);
     let tz_offset = time.offset.div_euclid(60); // in minutes
@@ -760,7 +876,7 @@ fn signature_from_git(signature: gix::actor::SignatureRef) -> Signature {
     }
 }
 
-fn signature_to_git(signature: &Signature) -> gix::actor::Signature {
+pub(crate) fn signature_to_git(signature: &Signature) -> gix::actor::Signature {
     // git does not support empty names or emails
     let name = if !signature.name.is_empty() {
         &signature.name
@@ -783,6 +899,88 @@ fn signature_to_git(signature: &Signature) -> gix::actor::Signature {
     }
 }
 
+/// Commit metadata as stored in a Git commit in a legacy encoding, or with
+/// invalid UTF-8.
+#[derive(Clone, Debug)]
+struct RawCommitMetadata {
+    encoding: Option<BString>,
+    author_name: BString,
+    author_email: BString,
+    committer_name: BString,
+    committer_email: BString,
+    description: BString,
+}
+
+impl RawCommitMetadata {
+    /// Extracts the metadata from the `commit` if it is stored in a legacy
+    /// encoding, or isn't valid UTF-8.
+    fn from_git_commit(
+        commit: &gix::objs::CommitRef,
+    ) -> Result<Option<Self>, gix::objs::decode::Error> {
+        let encoding = commit.encoding.filter(|name| !is_utf8_encoding(name));
+        let author = commit.author()?;
+        let committer = commit.committer()?;
+        let fields = [
+            author.name,
+            author.email,
+            committer.name,
+            committer.email,
+            commit.message,
+        ];
+        if encoding.is_none() && fields.iter().all(|field| field.is_utf8()) {
+            return Ok(None);
+        }
+        let [
+            author_name,
+            author_email,
+            committer_name,
+            committer_email,
+            description,
+        ] = fields.map(ToOwned::to_owned);
+        Ok(Some(Self {
+            encoding: encoding.map(ToOwned::to_owned),
+            author_name,
+            author_email,
+            committer_name,
+            committer_email,
+            description,
+        }))
+    }
+
+    /// Returns true if the raw bytes decode to the fields of the `commit`, i.e.
+    /// they can be written in place of the fields.
+    fn decodes_to(&self, commit: &Commit) -> bool {
+        let encoding = self.encoding.as_deref().map(BStr::new);
+        decode_signature_field(&self.author_name, encoding) == commit.author.name
+            && decode_signature_field(&self.author_email, encoding) == commit.author.email
+            && decode_signature_field(&self.committer_name, encoding) == commit.committer.name
+            && decode_signature_field(&self.committer_email, encoding) == commit.committer.email
+            && decode_commit_text(&self.description, encoding) == commit.description
+    }
+}
+
+/// Returns the raw metadata of the predecessor of the `commit` if the metadata
+/// of the predecessor is stored in a legacy encoding and hasn't been modified.
+///
+/// Since only the predecessors recorded in the commit are looked up, the raw
+/// metadata is lost if `experimental.record-predecessors-in-commit` is
+/// disabled.
+fn find_unmodified_raw_metadata(
+    repo: &gix::Repository,
+    commit: &Commit,
+) -> Option<RawCommitMetadata> {
+    commit.predecessors.iter().find_map(|id| {
+        let git_object = repo
+            .find_object(validate_git_object_id(repo, id).ok()?)
+            .ok()?;
+        let git_commit = git_object.try_to_commit_ref().ok()?;
+        RawCommitMetadata::from_git_commit(&git_commit)
+            .ok()
+            .flatten()
+            .filter(|raw| raw.decodes_to(commit))
+    })
+}
+
 fn serialize_extras(commit: &Commit) -> Vec<u8> {
     let mut proto = crate::protos::git_store::Commit {
         change_id: commit.change_id.to_bytes(),
@@ -991,7 +1189,7 @@ fn import_extra_metadata_entries_from_heads(
     mut_table: &mut MutableTable,
     _table_lock: &FileLock,
     head_ids: &HashSet<&CommitId>,
-    shallow_roots: &[CommitId],
+    grafted_parent_ids: &HashMap<CommitId, Vec<CommitId>>,
 ) -> BackendResult<()> {
     let mut work_ids = head_ids
         .iter()
@@ -1002,11 +1200,11 @@ fn import_extra_metadata_entries_from_heads(
         let git_object = git_repo
             .find_object(validate_git_object_id(git_repo, &id)?)
             .map_err(|err| map_not_found_err(err, &id))?;
-        let is_shallow = shallow_roots.contains(&id);
+        let parent_ids = grafted_parent_ids.get(&id).map(Vec::as_slice);
         // TODO(#1624): Should we read the root tree here and check if it has a
         // `.jjconflict-...` entries? That could happen if the user used `git` to e.g.
         // change the description of a commit with tree-level conflicts.
-        let commit = commit_from_git_without_root_parent(&id, &git_object, is_shallow)?;
+        let commit = commit_from_git_without_root_parent(&id, &git_object, parent_ids)?;
         mut_table.add_entry(id.to_bytes(), serialize_extras(&commit));
         work_ids.extend(
             commit
@@ -1244,8 +1442,9 @@ impl Backend for GitBackend {
             let git_object = locked_repo
                 .find_object(git_commit_id)
                 .map_err(|err| map_not_found_err(err, id))?;
-            let is_shallow = self.shallow_root_ids(&locked_repo)?.contains(id);
-            commit_from_git_without_root_parent(id, &git_object, is_shallow)?
+            let grafted_parent_ids = self.grafted_parent_ids(&locked_repo)?;
+            let parent_ids = grafted_parent_ids.get(id).map(Vec::as_slice);
+            commit_from_git_without_root_parent(id, &git_object, parent_ids)?
         };
         if commit.parents.is_empty() {
             commit.parents.push(self.root_commit_id.clone());
@@ -1281,9 +1480,20 @@ impl Backend for GitBackend {
             Some(tree_id) => validate_git_object_id(&locked_repo, tree_id)?,
             None => write_tree_conflict(&locked_repo, tree_ids)?,
         };
-        let author = signature_to_git(&contents.author);
+        let mut author = signature_to_git(&contents.author);
         let mut committer = signature_to_git(&contents.committer);
-        let message = &contents.description;
+        let mut message = BString::from(contents.description.as_str());
+        let mut encoding = None;
+        // Write the metadata of a rewritten commit in its original encoding if
+        // it hasn't been modified. Otherwise, everything is written in UTF-8.
+        if let Some(raw) = find_unmodified_raw_metadata(&locked_repo, &contents) {
+            author.name = raw.author_name;
+            author.email = raw.author_email;
+            committer.name = raw.committer_name;
+            committer.email = raw.committer_email;
+            message = raw.description;
+            encoding = raw.encoding;
+        }
         if contents.parents.is_empty() {
             return Err(BackendError::Other(
                 "Cannot write a commit with no parents".into(),
@@ -1357,11 +1567,11 @@ impl Backend for GitBackend {
         let (table, table_lock) = self.read_extra_metadata_table_locked()?;
         let id = loop {
             let mut commit = gix::objs::Commit {
-                message: message.to_owned().into(),
+                message: message.clone(),
                 tree: git_tree_id,
                 author: author.clone(),
                 committer: committer.clone(),
-                encoding: None,
+                encoding: encoding.clone(),
                 parents: parents.clone(),
                 extra_headers: extra_headers.clone(),
             };
@@ -2039,6 +2249,78 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(gix::hash::Kind::Sha1 ; "sha1")]
+    #[test_case(gix::hash::Kind::Sha256; "sha256")]
+    fn round_trip_commit_with_latin1_encoding(object_hash: gix::hash::Kind) -> TestResult {
+        let settings = user_settings();
+        let temp_dir = new_temp_dir();
+        let store_path = temp_dir.path();
+        let git_repo_path = temp_dir.path().join("git");
+        let git_repo = git_init(git_repo_path, object_hash);
+
+        let signature = gix::actor::Signature {
+            name: b"Ren\xe9".as_slice().into(),
+            email: GIT_EMAIL.into(),
+            time: gix::date::Time::new(1000, 60 * 60),
+        };
+        let change_id = ChangeId::from_hex("1111eeee1111eeee1111eeee1111eeee");
+        let git_commit = gix::objs::Commit {
+            tree: gix::ObjectId::empty_tree(git_repo.object_hash()),
+            parents: smallvec::SmallVec::new(),
+            author: signature.clone(),
+            committer: signature,
+            encoding: Some("ISO-8859-1".into()),
+            message: b"Caf\xe9\n".as_slice().into(),
+            extra_headers: vec![(
+                CHANGE_ID_COMMIT_HEADER.into(),
+                change_id.reverse_hex().into(),
+            )],
+        };
+        let git_commit_id = git_repo.write_object(&git_commit)?.detach();
+        let commit_id = CommitId::from_bytes(git_commit_id.as_bytes());
+
+        let backend = GitBackend::init_external(&settings, store_path, git_repo.path())?;
+        let commit = backend.read_commit(&commit_id).block_on()?;
+        assert_eq!(commit.author.name, "René");
+        assert_eq!(commit.committer.name, "René");
+        assert_eq!(commit.description, "Café\n");
+
+        // Unmodified metadata of a rewritten commit is written in the original
+        // encoding
+        let mut rewritten_commit = commit.clone();
+        rewritten_commit.predecessors = vec![commit_id.clone()];
+        rewritten_commit.committer.timestamp.timestamp = MillisSinceEpoch(2000 * 1000);
+        let (rewritten_commit_id, _) = backend.write_commit(rewritten_commit, None).block_on()?;
+        let git_object = git_repo.find_object(git_id(&rewritten_commit_id))?;
+        let git_commit = git_object.try_to_commit_ref()?;
+        assert_eq!(git_commit.encoding, Some(b"ISO-8859-1".as_bstr()));
+        assert_eq!(git_commit.author()?.name, b"Ren\xe9".as_bstr());
+        assert_eq!(git_commit.message, b"Caf\xe9\n".as_bstr());
+
+        // Unrelated commit with the same metadata is written in UTF-8
+        let mut unrelated_commit = commit.clone();
+        unrelated_commit.committer.timestamp.timestamp = MillisSinceEpoch(3000 * 1000);
+        let (unrelated_commit_id, _) = backend.write_commit(unrelated_commit, None).block_on()?;
+        let git_object = git_repo.find_object(git_id(&unrelated_commit_id))?;
+        let git_commit = git_object.try_to_commit_ref()?;
+        assert_eq!(git_commit.encoding, None);
+        assert_eq!(git_commit.author()?.name, "René");
+
+        // Modified metadata is written in UTF-8
+        let mut modified_commit = commit;
+        modified_commit.predecessors = vec![commit_id];
+        modified_commit.description = "Café au lait\n".to_owned();
+        let (modified_commit_id, _) = backend.write_commit(modified_commit, None).block_on()?;
+        let git_object = git_repo.find_object(git_id(&modified_commit_id))?;
+        let git_commit = git_object.try_to_commit_ref()?;
+        assert_eq!(git_commit.encoding, None);
+        assert_eq!(git_commit.author()?.name, "René");
+        assert_eq!(git_commit.message, "Café au lait\n");
+        let commit = backend.read_commit(&modified_commit_id).block_on()?;
+        assert_eq!(commit.author.name, "René");
+        Ok(())
+    }
+
     #[test]
     fn read_empty_string_placeholder() {
         let git_signature1 = gix::actor::Signature {
@@ -2046,7 +2328,7 @@ mod tests {
             email: "git.author@example.com".into(),
             time: gix::date::Time::new(1000, 60 * 60),
         };
-        let signature1 = signature_from_git(git_signature1.to_ref(&mut TimeBuf::default()));
+        let signature1 = signature_from_git(git_signature1.to_ref(&mut TimeBuf::default()), None);
         assert!(signature1.name.is_empty());
         assert_eq!(signature1.email, "git.author@example.com");
         let git_signature2 = gix::actor::Signature {
@@ -2054,7 +2336,7 @@ mod tests {
             email: EMPTY_STRING_PLACEHOLDER.into(),
             time: gix::date::Time::new(2000, -480 * 60),
         };
-        let signature2 = signature_from_git(git_signature2.to_ref(&mut TimeBuf::default()));
+        let signature2 = signature_from_git(git_signature2.to_ref(&mut TimeBuf::default()), None);
         assert_eq!(signature2.name, "git committer");
         assert!(signature2.email.is_empty());
     }
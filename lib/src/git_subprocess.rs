@@ -15,7 +15,6 @@
 use std::io;
 use std::io::BufReader;
 use std::io::Read;
-use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::process::Child;
 use std::process::Command;
@@ -28,7 +27,9 @@ use bstr::ByteSlice as _;
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::git::GitFetchDepth;
 use crate::git::GitPushOptions;
+use crate::git::GitPushSigned;
 use crate::git::GitPushStats;
 use crate::git::GitSubprocessOptions;
 use crate::git::NegativeRefSpec;
@@ -70,6 +71,12 @@ pub enum GitSubprocessError {
          {MINIMUM_GIT_VERSION})"
     )]
     UnsupportedGitOption(String),
+    #[error("The remote doesn't support signed pushes")]
+    UnsupportedSignedPush,
+    #[error(
+        "The remote repository uses {remote} object hashes, but the local repository uses {local}"
+    )]
+    MismatchedObjectHash { local: String, remote: String },
     #[error("Git process failed: {0}")]
     External(String),
 }
@@ -170,22 +177,34 @@ impl GitSubprocessContext {
         remote_name: &RemoteName,
         refspecs: &[RefSpec],
         negative_refspecs: &[NegativeRefSpec],
+        prune: bool,
         callback: &mut dyn GitSubprocessCallback,
-        depth: Option<NonZeroU32>,
+        depth: Option<GitFetchDepth>,
     ) -> Result<GitFetchStatus, GitSubprocessError> {
         if refspecs.is_empty() {
             return Ok(GitFetchStatus::Updates(GitRefUpdates::default()));
         }
         let mut command = self.create_command();
         command.stdout(Stdio::piped());
-        // attempt to prune stale refs with --prune
         // --no-write-fetch-head ensures our request is invisible to other parties
-        command.args(["fetch", "--porcelain", "--prune", "--no-write-fetch-head"]);
+        command.args(["fetch", "--porcelain", "--no-write-fetch-head"]);
+        // attempt to prune stale refs with --prune. --no-prune is passed
+        // explicitly to override the fetch.prune config.
+        command.arg(if prune { "--prune" } else { "--no-prune" });
         if callback.needs_progress() {
             command.arg("--progress");
         }
-        if let Some(d) = depth {
-            command.arg(format!("--depth={d}"));
+        match depth {
+            Some(GitFetchDepth::Depth(d)) => {
+                command.arg(format!("--depth={d}"));
+            }
+            Some(GitFetchDepth::Deepen(d)) => {
+                command.arg(format!("--deepen={d}"));
+            }
+            Some(GitFetchDepth::Unshallow) => {
+                command.arg("--unshallow");
+            }
+            None => {}
         }
         // Tags should be fetched explicitly by the refspecs
         command.arg("--no-tags");
@@ -202,6 +221,35 @@ impl GitSubprocessContext {
         parse_git_fetch_output(&output)
     }
 
+    /// Fetch the given objects and their ancestors without updating refs
+    ///
+    /// No local commits are advertised to the remote, so the ancestors missing
+    /// in the local repository are sent even if they are reachable from the
+    /// local refs.
+    pub(crate) fn spawn_fetch_objects(
+        &self,
+        remote_name: &RemoteName,
+        object_ids: &[gix::ObjectId],
+        callback: &mut dyn GitSubprocessCallback,
+    ) -> Result<(), GitSubprocessError> {
+        if object_ids.is_empty() {
+            return Ok(());
+        }
+        let mut command = self.create_command();
+        command.stdout(Stdio::piped());
+        command.args(["-c", "fetch.negotiationAlgorithm=noop"]);
+        command.args(["fetch", "--no-write-fetch-head", "--no-tags"]);
+        if callback.needs_progress() {
+            command.arg("--progress");
+        }
+        command.arg("--").arg(remote_name.as_str());
+        command.args(object_ids.iter().map(|id| id.to_string()));
+
+        let output = wait_with_progress(self.spawn_cmd(command)?, callback)?;
+
+        parse_git_fetch_objects_output(output)
+    }
+
     /// Prune particular branches
     pub(crate) fn spawn_branch_prune(
         &self,
@@ -246,6 +294,44 @@ impl GitSubprocessContext {
         Ok(maybe_branch.map(Into::into))
     }
 
+    /// Looks up the commit the remote `HEAD` points to with
+    /// `git ls-remote <remote_name> HEAD`
+    ///
+    /// Returns `None` if the remote `HEAD` doesn't point to any commit.
+    pub(crate) fn spawn_ls_remote_head(
+        &self,
+        remote_name: &RemoteName,
+    ) -> Result<Option<gix::ObjectId>, GitSubprocessError> {
+        let mut command = self.create_command();
+        command.stdout(Stdio::piped());
+        command.args(["ls-remote", "--", remote_name.as_str(), "HEAD"]);
+        let output = wait_with_output(self.spawn_cmd(command)?)?;
+        if !output.status.success() {
+            if let Some(remote) = parse_no_such_remote(&output.stderr) {
+                return Err(GitSubprocessError::NoSuchRepository(remote));
+            }
+            return Err(external_git_error(&output.stderr));
+        }
+        // <object-id> TAB <ref>. The pattern also matches refs ending with
+        // "/HEAD", so only the exact "HEAD" ref is considered.
+        for line in output.stdout.lines() {
+            let Some((oid, name)) = line.split_once_str("\t") else {
+                continue;
+            };
+            if name != b"HEAD" {
+                continue;
+            }
+            let oid = gix::ObjectId::from_hex(oid).map_err(|_| {
+                GitSubprocessError::External(format!(
+                    "Unexpected ls-remote output: {}",
+                    BStr::new(line)
+                ))
+            })?;
+            return Ok(Some(oid));
+        }
+        Ok(None)
+    }
+
     /// Push references to git
     ///
     /// All pushes are forced, using --force-with-lease to perform a test&set
@@ -272,6 +358,21 @@ impl GitSubprocessContext {
         if callback.needs_progress() {
             command.arg("--progress");
         }
+        if options.no_thin {
+            command.arg("--no-thin");
+        }
+        if options.atomic {
+            command.arg("--atomic");
+        }
+        match options.signed {
+            GitPushSigned::No => {}
+            GitPushSigned::Yes => {
+                command.arg("--signed=true");
+            }
+            GitPushSigned::IfAsked => {
+                command.arg("--signed=if-asked");
+            }
+        }
         command.args(
             options
                 .remote_push_options
@@ -339,6 +440,22 @@ fn parse_no_such_remote(stderr: &[u8]) -> Option<String> {
         .map(|remote| remote.to_str_lossy().into_owned())
 }
 
+/// Parse error from fetching between repositories of different object hash
+/// algorithms
+///
+/// This returns `Some((local_hash, remote_hash))` if the first line is of the
+/// form: `fatal: mismatched algorithms: client <local>; server <remote>`
+fn parse_mismatched_algorithms(stderr: &[u8]) -> Option<(String, String)> {
+    let first_line = stderr.lines().next()?;
+    let (local, remote) = first_line
+        .strip_prefix(b"fatal: mismatched algorithms: client ")?
+        .split_once_str("; server ")?;
+    Some((
+        local.to_str_lossy().into_owned(),
+        remote.to_str_lossy().into_owned(),
+    ))
+}
+
 /// Parse error from refspec not present on the remote
 ///
 /// This returns
@@ -422,6 +539,10 @@ fn parse_git_fetch_output(output: &Output) -> Result<GitFetchStatus, GitSubproce
         return Err(GitSubprocessError::NoSuchRepository(remote));
     }
 
+    if let Some((local, remote)) = parse_mismatched_algorithms(&output.stderr) {
+        return Err(GitSubprocessError::MismatchedObjectHash { local, remote });
+    }
+
     if let Some(refspec) = parse_no_remote_ref(&output.stderr) {
         return Ok(GitFetchStatus::NoRemoteRef(refspec));
     }
@@ -496,6 +617,23 @@ fn parse_ref_updates(stdout: &[u8]) -> Result<GitRefUpdates, GitSubprocessError>
     Ok(GitRefUpdates { updated, rejected })
 }
 
+fn parse_git_fetch_objects_output(output: Output) -> Result<(), GitSubprocessError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // There are some git errors we want to parse out
+    if let Some(option) = parse_unknown_option(&output.stderr) {
+        return Err(GitSubprocessError::UnsupportedGitOption(option));
+    }
+
+    if let Some(remote) = parse_no_such_remote(&output.stderr) {
+        return Err(GitSubprocessError::NoSuchRepository(remote));
+    }
+
+    Err(external_git_error(&output.stderr))
+}
+
 fn parse_git_branch_prune_output(output: Output) -> Result<(), GitSubprocessError> {
     if output.status.success() {
         return Ok(());
@@ -662,6 +800,14 @@ fn parse_git_push_output(output: Output) -> Result<GitPushStats, GitSubprocessEr
         return Err(GitSubprocessError::NoSuchRepository(remote));
     }
 
+    if output
+        .stderr
+        .lines()
+        .any(|line| line == b"fatal: the receiving end does not support --signed push")
+    {
+        return Err(GitSubprocessError::UnsupportedSignedPush);
+    }
+
     if output
         .stderr
         .lines()
@@ -936,6 +1082,8 @@ fatal: Could not read from remote repository.
 
 Please make sure you have the correct access rights
 and the repository exists. "###;
+    const SAMPLE_MISMATCHED_ALGORITHMS_ERROR: &[u8] =
+        b"fatal: mismatched algorithms: client sha1; server sha256\n";
     const SAMPLE_NO_REMOTE_REF_ERROR: &[u8] = b"fatal: couldn't find remote ref refs/heads/noexist";
     const SAMPLE_NO_REMOTE_TRACKING_BRANCH_ERROR: &[u8] =
         b"error: remote-tracking branch 'bookmark' not found";
@@ -1024,6 +1172,19 @@ Done";
         assert_eq!(parse_no_such_remote(SAMPLE_OK_STDERR), None);
     }
 
+    #[test]
+    fn test_parse_mismatched_algorithms() {
+        assert_eq!(
+            parse_mismatched_algorithms(SAMPLE_MISMATCHED_ALGORITHMS_ERROR),
+            Some(("sha1".to_string(), "sha256".to_string()))
+        );
+        assert_eq!(
+            parse_mismatched_algorithms(SAMPLE_NO_SUCH_REPOSITORY_ERROR),
+            None
+        );
+        assert_eq!(parse_mismatched_algorithms(SAMPLE_OK_STDERR), None);
+    }
+
     #[test]
     fn test_parse_no_remote_ref() {
         assert_eq!(parse_no_remote_ref(SAMPLE_NO_SUCH_REPOSITORY_ERROR), None);
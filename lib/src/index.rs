@@ -86,6 +86,16 @@ pub trait IndexStore: Any + Send + Sync + Debug {
         index: Box<dyn MutableIndex>,
         op: &Operation,
     ) -> IndexStoreResult<Box<dyn ReadonlyIndex>>;
+
+    /// Rebuilds the index at the specified operation after the parents of
+    /// `commit_ids` have changed in the backend, which happens when the history
+    /// of a shallow Git repository is deepened.
+    async fn reindex_commits_at_op(
+        &self,
+        op: &Operation,
+        store: &Arc<Store>,
+        commit_ids: &[CommitId],
+    ) -> IndexStoreResult<Box<dyn ReadonlyIndex>>;
 }
 
 impl dyn IndexStore {
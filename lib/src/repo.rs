@@ -59,6 +59,7 @@ use crate::index::IndexError;
 use crate::index::IndexResult;
 use crate::index::IndexStore;
 use crate::index::IndexStoreError;
+use crate::index::IndexStoreResult;
 use crate::index::MutableIndex;
 use crate::index::ReadonlyIndex;
 use crate::index::ResolvedChangeTargets;
@@ -1796,6 +1797,22 @@ impl MutableRepo {
         self.view.mark_dirty();
     }
 
+    /// Reindexes `commit_ids` and their descendants after their parents have
+    /// changed in the backend, such as when a shallow Git repository is
+    /// deepened.
+    ///
+    /// The index of the base operation is rebuilt, so this should be called
+    /// before any commits are added in this transaction.
+    pub async fn reindex_commits(&mut self, commit_ids: &[CommitId]) -> IndexStoreResult<()> {
+        let base_repo = &self.base_repo;
+        let index = base_repo
+            .index_store()
+            .reindex_commits_at_op(base_repo.operation(), base_repo.store(), commit_ids)
+            .await?;
+        self.index = index.start_modification();
+        Ok(())
+    }
+
     /// Adds the given `heads` and ancestor commits to the index without making
     /// them visible. Returns newly-indexed commits.
     pub async fn index_commits(&mut self, heads: &[Commit]) -> BackendResult<Vec<Commit>> {
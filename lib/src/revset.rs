@@ -54,6 +54,7 @@ use crate::op_store::RemoteRefState;
 use crate::op_walk;
 use crate::ref_name::RefName;
 use crate::ref_name::RemoteName;
+use crate::ref_name::RemoteNameBuf;
 use crate::ref_name::RemoteRefSymbol;
 use crate::ref_name::RemoteRefSymbolBuf;
 use crate::ref_name::WorkspaceName;
@@ -91,6 +92,19 @@ pub enum RevsetResolutionError {
     },
     #[error("Workspace `{}` doesn't have a working-copy commit", name.as_symbol())]
     WorkspaceMissingWorkingCopy { name: WorkspaceNameBuf },
+    #[error(
+        "No bookmark points to the working-copy commit of workspace `{}`",
+        name.as_symbol()
+    )]
+    NoWorkingCopyBookmark { name: WorkspaceNameBuf },
+    #[error(
+        "Multiple bookmarks point to the working-copy commit of workspace `{}`",
+        name.as_symbol()
+    )]
+    AmbiguousWorkingCopyBookmark {
+        name: WorkspaceNameBuf,
+        candidates: Vec<String>,
+    },
     #[error("An empty string is not a valid revision")]
     EmptyString,
     #[error("Commit ID prefix `{0}` is ambiguous")]
@@ -147,6 +161,11 @@ pub enum RevsetCommitRef {
     WorkingCopies,
     Symbol(String),
     RemoteSymbol(RemoteRefSymbolBuf),
+    /// Remote ref of the bookmark pointing to the working-copy commit.
+    WorkingCopyRemoteSymbol {
+        name: WorkspaceNameBuf,
+        remote: RemoteNameBuf,
+    },
     ChangeId(HexPrefix),
     CommitId(HexPrefix),
     Bookmarks(StringExpression),
@@ -159,6 +178,7 @@ pub enum RevsetCommitRef {
         symbol: RemoteRefSymbolExpression,
         remote_ref_state: Option<RemoteRefState>,
     },
+    GitRefs(StringExpression),
 }
 
 /// String expressions to match `name@remote` bookmarks/tags.
@@ -424,6 +444,11 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
         Arc::new(Self::CommitRef(commit_ref))
     }
 
+    pub fn working_copy_remote_symbol(name: WorkspaceNameBuf, remote: RemoteNameBuf) -> Arc<Self> {
+        let commit_ref = RevsetCommitRef::WorkingCopyRemoteSymbol { name, remote };
+        Arc::new(Self::CommitRef(commit_ref))
+    }
+
     pub fn change_id_prefix(prefix: HexPrefix) -> Arc<Self> {
         let commit_ref = RevsetCommitRef::ChangeId(prefix);
         Arc::new(Self::CommitRef(commit_ref))
@@ -461,6 +486,10 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
             remote_ref_state,
         }))
     }
+
+    pub fn git_refs(expression: StringExpression) -> Arc<Self> {
+        Arc::new(Self::CommitRef(RevsetCommitRef::GitRefs(expression)))
+    }
 }
 
 // Compound expression
@@ -986,6 +1015,15 @@ static BUILTIN_FUNCTION_MAP: LazyLock<HashMap<&str, RevsetFunction>> = LazyLock:
         let state = Some(RemoteRefState::New);
         Ok(RevsetExpression::remote_tags(symbol, state))
     });
+    map.insert("git_refs", |diagnostics, function, _context| {
+        let ([], [opt_arg]) = function.expect_arguments()?;
+        let expr = if let Some(arg) = opt_arg {
+            expect_string_expression(diagnostics, arg)?
+        } else {
+            StringExpression::all()
+        };
+        Ok(RevsetExpression::git_refs(expr))
+    });
     map.insert("latest", |diagnostics, function, context| {
         let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
         let candidates = lower_expression(diagnostics, candidates_arg, context)?;
@@ -1267,6 +1305,7 @@ pub fn expect_string_expression(
             ExpressionKind::RemoteSymbol(_)
             | ExpressionKind::AtWorkspace(_)
             | ExpressionKind::AtCurrentWorkspace
+            | ExpressionKind::AtCurrentWorkspaceRemote(_)
             | ExpressionKind::DagRangeAll
             | ExpressionKind::RangeAll => Err(expr_error()),
             ExpressionKind::Unary(op, arg_node) => {
@@ -1391,6 +1430,18 @@ pub fn lower_expression(
                 ctx.workspace_name.to_owned(),
             ))
         }
+        ExpressionKind::AtCurrentWorkspaceRemote(remote) => {
+            let ctx = context.workspace.as_ref().ok_or_else(|| {
+                RevsetParseError::with_span(
+                    RevsetParseErrorKind::WorkingCopyWithoutWorkspace,
+                    node.span,
+                )
+            })?;
+            Ok(RevsetExpression::working_copy_remote_symbol(
+                ctx.workspace_name.to_owned(),
+                remote.clone(),
+            ))
+        }
         ExpressionKind::DagRangeAll => Ok(RevsetExpression::all()),
         ExpressionKind::RangeAll => Ok(RevsetExpression::root().negated()),
         ExpressionKind::Unary(op, arg_node) => {
@@ -2938,6 +2989,32 @@ fn resolve_commit_ref(
                 Err(RevsetResolutionError::WorkspaceMissingWorkingCopy { name: name.clone() })
             }
         }
+        RevsetCommitRef::WorkingCopyRemoteSymbol { name, remote } => {
+            let Some(wc_commit_id) = repo.view().get_wc_commit_id(name) else {
+                return Err(RevsetResolutionError::WorkspaceMissingWorkingCopy {
+                    name: name.clone(),
+                });
+            };
+            let bookmark_names = repo
+                .view()
+                .local_bookmarks_for_commit(wc_commit_id)
+                .map(|(bookmark_name, _)| bookmark_name)
+                .collect_vec();
+            match bookmark_names.as_slice() {
+                [] => Err(RevsetResolutionError::NoWorkingCopyBookmark { name: name.clone() }),
+                [bookmark_name] => {
+                    let symbol = bookmark_name.to_remote_symbol(remote);
+                    Ok(vec![resolve_remote_symbol(repo, symbol)?])
+                }
+                _ => Err(RevsetResolutionError::AmbiguousWorkingCopyBookmark {
+                    name: name.clone(),
+                    candidates: bookmark_names
+                        .iter()
+                        .map(|bookmark_name| bookmark_name.to_remote_symbol(remote).to_string())
+                        .collect(),
+                }),
+            }
+        }
         RevsetCommitRef::WorkingCopies => {
             let wc_commits = repo.view().wc_commit_ids().values().cloned().collect_vec();
             Ok(wc_commits)
@@ -3005,6 +3082,21 @@ fn resolve_commit_ref(
                 .collect();
             Ok(commit_ids)
         }
+        RevsetCommitRef::GitRefs(expression) => {
+            let matcher = expression.to_matcher();
+            // Git refs can't point to the root commit
+            let root_commit_id = repo.store().root_commit_id();
+            let commit_ids = repo
+                .view()
+                .git_refs()
+                .iter()
+                .filter(|(name, _)| matcher.is_match(name.as_str()))
+                .flat_map(|(_, target)| target.added_ids())
+                .filter(|id| *id != root_commit_id)
+                .cloned()
+                .collect();
+            Ok(commit_ids)
+        }
     }
 }
 
@@ -3045,7 +3137,8 @@ impl ExpressionStateFolder<UserExpressionState, ResolvedExpressionState>
             RevsetExpression::Present(candidates) => {
                 self.fold_expression(candidates).or_else(|err| match err {
                     RevsetResolutionError::NoSuchRevision { .. }
-                    | RevsetResolutionError::WorkspaceMissingWorkingCopy { .. } => {
+                    | RevsetResolutionError::WorkspaceMissingWorkingCopy { .. }
+                    | RevsetResolutionError::NoWorkingCopyBookmark { .. } => {
                         Ok(RevsetExpression::none())
                     }
                     RevsetResolutionError::EmptyString
@@ -3053,6 +3146,7 @@ impl ExpressionStateFolder<UserExpressionState, ResolvedExpressionState>
                     | RevsetResolutionError::AmbiguousChangeIdPrefix(_)
                     | RevsetResolutionError::DivergentChangeId { .. }
                     | RevsetResolutionError::ConflictedRef { .. }
+                    | RevsetResolutionError::AmbiguousWorkingCopyBookmark { .. }
                     | RevsetResolutionError::Backend(_)
                     | RevsetResolutionError::Other(_) => Err(err),
                 })
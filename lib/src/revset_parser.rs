@@ -328,6 +328,8 @@ pub enum ExpressionKind<'i> {
     AtWorkspace(String),
     /// `@`
     AtCurrentWorkspace,
+    /// `@<remote>`
+    AtCurrentWorkspaceRemote(RemoteNameBuf),
     /// `::`
     DagRangeAll,
     /// `..`
@@ -353,6 +355,7 @@ impl<'i> FoldableExpression<'i> for ExpressionKind<'i> {
             Self::RemoteSymbol(_)
             | ExpressionKind::AtWorkspace(_)
             | Self::AtCurrentWorkspace
+            | Self::AtCurrentWorkspaceRemote(_)
             | Self::DagRangeAll
             | Self::RangeAll => Ok(self),
             Self::Unary(op, arg) => {
@@ -628,8 +631,14 @@ fn parse_primary_node(pair: Pair<Rule>) -> Result<ExpressionNode, RevsetParseErr
                 }
             }
         }
-        // nullary "@"
-        Rule::at_op => ExpressionKind::AtCurrentWorkspace,
+        Rule::at_op => match pairs.next() {
+            // nullary "@"
+            None => ExpressionKind::AtCurrentWorkspace,
+            // prefix "@<remote>"
+            Some(second) => {
+                ExpressionKind::AtCurrentWorkspaceRemote(parse_as_string_literal(second).into())
+            }
+        },
         r => panic!("unexpected revset parse rule: {r:?}"),
     };
     Ok(ExpressionNode::new(expr, span))
@@ -919,6 +928,7 @@ mod tests {
             ExpressionKind::RemoteSymbol(_)
             | ExpressionKind::AtWorkspace(_)
             | ExpressionKind::AtCurrentWorkspace
+            | ExpressionKind::AtCurrentWorkspaceRemote(_)
             | ExpressionKind::DagRangeAll
             | ExpressionKind::RangeAll => node.kind,
             ExpressionKind::Unary(op, arg) => {
@@ -1305,6 +1315,10 @@ mod tests {
     fn parse_at_workspace_and_remote_symbol() {
         // Parse "@" (the current working copy)
         assert_eq!(parse_into_kind("@"), Ok(ExpressionKind::AtCurrentWorkspace));
+        assert_eq!(
+            parse_into_kind("@git"),
+            Ok(ExpressionKind::AtCurrentWorkspaceRemote("git".into()))
+        );
         assert_eq!(
             parse_into_kind("main@"),
             Ok(ExpressionKind::AtWorkspace("main".to_owned()))
@@ -1336,6 +1350,10 @@ mod tests {
                 remote: "foo bar".into()
             }))
         );
+        assert_eq!(
+            parse_into_kind(r#"@"foo bar""#),
+            Ok(ExpressionKind::AtCurrentWorkspaceRemote("foo bar".into()))
+        );
         assert_eq!(
             parse_into_kind(r#"'foo bar'@'bar baz'"#),
             Ok(ExpressionKind::RemoteSymbol(RemoteRefSymbolBuf {
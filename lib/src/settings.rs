@@ -104,13 +104,19 @@ pub struct SignSettings {
 impl SignSettings {
     /// Check if a commit should be signed according to the configured behavior
     /// and email.
+    ///
+    /// With `Own`, commits with conflicts aren't signed since their Git
+    /// representation isn't meaningful to other Git tools. Existing signatures
+    /// are still kept with `Keep`.
     pub fn should_sign(&self, commit: &Commit) -> bool {
         match self.behavior {
             SignBehavior::Drop => false,
             SignBehavior::Keep => {
                 commit.secure_sig.is_some() && commit.author.email == self.user_email
             }
-            SignBehavior::Own => commit.author.email == self.user_email,
+            SignBehavior::Own => {
+                commit.root_tree.is_resolved() && commit.author.email == self.user_email
+            }
             SignBehavior::Force => true,
         }
     }
@@ -240,20 +240,7 @@ impl Signer {
             return Ok(check);
         }
 
-        let verification = self
-            .main_backend
-            .iter()
-            .chain(self.backends.iter())
-            .filter(|b| b.can_read(signature))
-            // skip unknown and invalid sigs to allow other backends that can read to try
-            // for example, we might have gpg and sq, both of which could read a PGP signature
-            .find_map(|backend| match backend.verify(data, signature) {
-                Ok(check) if check.status == SigStatus::Unknown => None,
-                Err(SignError::InvalidSignatureFormat) => None,
-                e => Some(e),
-            })
-            .transpose()?;
-
+        let verification = self.find_verification(data, signature)?;
         if let Some(verification) = verification {
             // a key might get imported before next call?.
             // realistically this is unlikely, but technically
@@ -277,4 +264,26 @@ impl Signer {
             Ok(Verification::unknown())
         }
     }
+
+    /// Verifies the signature of arbitrary data, such as a Git tag object,
+    /// without caching the result.
+    pub fn verify_data(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        let verification = self.find_verification(data, signature)?;
+        Ok(verification.unwrap_or_else(Verification::unknown))
+    }
+
+    fn find_verification(&self, data: &[u8], signature: &[u8]) -> SignResult<Option<Verification>> {
+        self.main_backend
+            .iter()
+            .chain(self.backends.iter())
+            .filter(|b| b.can_read(signature))
+            // skip unknown and invalid sigs to allow other backends that can read to try
+            // for example, we might have gpg and sq, both of which could read a PGP signature
+            .find_map(|backend| match backend.verify(data, signature) {
+                Ok(check) if check.status == SigStatus::Unknown => None,
+                Err(SignError::InvalidSignatureFormat) => None,
+                e => Some(e),
+            })
+            .transpose()
+    }
 }
@@ -45,12 +45,14 @@ use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitFetch;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitFetchRefExpression;
+use jj_lib::git::GitImportDeletedBookmark;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitImportOptions;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::GitPushError;
 use jj_lib::git::GitPushOptions;
 use jj_lib::git::GitPushRefTargets;
+use jj_lib::git::GitPushSigned;
 use jj_lib::git::GitPushStats;
 use jj_lib::git::GitRefKind;
 use jj_lib::git::GitRefUpdate;
@@ -2616,6 +2618,56 @@ fn test_export_refs_bookmark_changed() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_export_refs_rewritten_bookmarked_commit() -> TestResult {
+    // Rewriting a bookmarked commit moves the local bookmark, and the export
+    // updates both the Git ref and the @git remote bookmark
+    let test_data = GitRepoData::create();
+    let import_options = default_import_options();
+    let git_repo = test_data.git_repo;
+    let commit = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &import_options).block_on()?;
+    mut_repo.rebase_descendants().block_on()?;
+    let stats = git::export_refs(mut_repo)?;
+    assert!(stats.failed_bookmarks.is_empty());
+
+    let old_commit = mut_repo.store().get_commit(&jj_id(commit))?;
+    let new_commit = mut_repo
+        .rewrite_commit(&old_commit)
+        .set_description("rewritten")
+        .write_unwrap();
+    mut_repo.rebase_descendants().block_on()?;
+    assert_eq!(
+        mut_repo.get_local_bookmark("main".as_ref()),
+        RefTarget::normal(new_commit.id().clone())
+    );
+
+    let stats = git::export_refs(mut_repo)?;
+    assert!(stats.failed_bookmarks.is_empty());
+    assert!(stats.failed_tags.is_empty());
+    assert_eq!(
+        mut_repo
+            .get_remote_bookmark(remote_symbol("main", "git"))
+            .target,
+        RefTarget::normal(new_commit.id().clone())
+    );
+    assert_eq!(
+        mut_repo.get_git_ref("refs/heads/main".as_ref()),
+        RefTarget::normal(new_commit.id().clone())
+    );
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/main")?
+            .peel_to_commit()?
+            .id(),
+        git_id(&new_commit)
+    );
+    Ok(())
+}
+
 #[test]
 fn test_export_refs_tag_changed() -> TestResult {
     // We can export changes to lightweight and annotated tags. Since jj doesn't
@@ -2707,6 +2759,48 @@ fn test_export_refs_tag_changed() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_export_refs_annotated_tag() -> TestResult {
+    // A tag created with an annotated tag object should be exported as an
+    // annotated tag, not a lightweight tag.
+    let test_data = GitRepoData::create();
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit = write_random_commit(mut_repo);
+    let tagger = Signature {
+        name: "Tagger".to_owned(),
+        email: "tagger@example.com".to_owned(),
+        timestamp: Timestamp {
+            timestamp: MillisSinceEpoch(1_000_000),
+            tz_offset: 60,
+        },
+    };
+    let tag_oid = git::create_annotated_tag(
+        mut_repo,
+        "v1.0".as_ref(),
+        commit.id(),
+        "Release 1.0\n",
+        &tagger,
+    )?;
+    // The tag is exported as part of the transaction
+    let target = RefTarget::normal(commit.id().clone());
+    assert_eq!(mut_repo.get_local_tag("v1.0".as_ref()), target);
+    assert_eq!(mut_repo.get_git_ref("refs/tags/v1.0".as_ref()), target);
+    let stats = git::export_refs(mut_repo)?;
+    assert!(stats.failed_tags.is_empty());
+
+    let git_repo = gix::open(test_data.git_repo.path())?;
+    let git_ref = git_repo.find_reference("refs/tags/v1.0")?;
+    assert_eq!(git_ref.inner.target.try_id(), Some(tag_oid.as_ref()));
+    let tag = git_repo.find_object(tag_oid)?.try_into_tag()?;
+    let tag = tag.decode()?;
+    assert_eq!(tag.name, "v1.0");
+    assert_eq!(tag.message, "Release 1.0\n");
+    assert_eq!(tag.tagger()?.unwrap().name, "Tagger");
+    assert_eq!(tag.target(), git_id(&commit));
+    Ok(())
+}
+
 #[test]
 fn test_export_refs_current_bookmark_changed() -> TestResult {
     // If we update a bookmark that is checked out in the git repo, HEAD gets
@@ -2791,6 +2885,66 @@ fn test_export_refs_worktree_head_changed() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_export_refs_atomic_worktree_head_changed() -> TestResult {
+    let test_data = GitRepoData::create();
+    let import_options = default_import_options();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    testutils::git::set_symbolic_reference(&git_repo, "HEAD", "refs/heads/main");
+
+    let worktree_dir = test_data._temp_dir.path().join("git-wt");
+    let git_workdir = git_repo.workdir().expect("git repo must have workdir");
+    let output = std::process::Command::new("git")
+        .args(["worktree", "add", "-b", "wt-branch"])
+        .arg(&worktree_dir)
+        .current_dir(git_workdir)
+        .output()?;
+    assert!(
+        output.status.success(),
+        "Failed to create worktree: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_head(mut_repo).block_on()?;
+    git::import_refs(mut_repo, &import_options).block_on()?;
+    mut_repo.rebase_descendants().block_on()?;
+
+    // A failing ref leaves the worktree HEAD untouched
+    let new_commit = create_random_commit(mut_repo)
+        .set_parents(vec![jj_id(commit1)])
+        .write_unwrap();
+    let target = RefTarget::normal(new_commit.id().clone());
+    mut_repo.set_local_bookmark_target("wt-branch".as_ref(), target.clone());
+    mut_repo.set_local_bookmark_target("main/sub".as_ref(), target.clone());
+    let stats = git::export_refs_atomic(mut_repo)?;
+    assert_eq!(stats.failed_bookmarks.len(), 1);
+    let git_repo_wt = gix::open(&worktree_dir)?;
+    assert!(!git_repo_wt.head()?.is_detached());
+    assert_eq!(
+        git_repo_wt.head_name()?.unwrap().as_bstr(),
+        b"refs/heads/wt-branch"
+    );
+
+    // The worktree HEAD is detached in the same transaction as the ref update
+    mut_repo.set_local_bookmark_target("main/sub".as_ref(), RefTarget::absent());
+    let stats = git::export_refs_atomic(mut_repo)?;
+    assert!(stats.failed_bookmarks.is_empty());
+    assert!(stats.failed_tags.is_empty());
+    let git_repo_wt = gix::open(&worktree_dir)?;
+    assert!(git_repo_wt.head()?.is_detached());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/wt-branch")?
+            .target()
+            .id(),
+        git_id(&new_commit)
+    );
+    Ok(())
+}
+
 #[test]
 fn test_export_refs_worktree_no_detach() -> TestResult {
     let test_data = GitRepoData::create();
@@ -3191,6 +3345,148 @@ fn test_export_bookmark_on_root_commit() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_export_bookmark_on_hidden_commit() -> TestResult {
+    // We skip export of local bookmarks pointing to hidden commits
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit = write_random_commit(mut_repo);
+    mut_repo.set_local_bookmark_target("hidden".as_ref(), RefTarget::normal(commit.id().clone()));
+    // The view can be inconsistent e.g. after merging concurrent operations
+    mut_repo.remove_head(commit.id());
+    let stats = git::export_refs(mut_repo)?;
+    assert_eq!(stats.failed_bookmarks.len(), 1);
+    assert_eq!(
+        stats.failed_bookmarks[0].0.as_ref(),
+        remote_symbol("hidden", "git")
+    );
+    assert_matches!(
+        stats.failed_bookmarks[0].1,
+        FailedRefExportReason::OnHiddenCommit
+    );
+    assert!(git_repo.try_find_reference("refs/heads/hidden")?.is_none());
+    assert!(
+        mut_repo
+            .get_remote_bookmark(remote_symbol("hidden", "git"))
+            .is_absent()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_export_atomic() -> TestResult {
+    // Check that nothing is exported if any bookmark fails to export
+    let test_data = GitRepoData::create();
+    let git_repo = test_data.git_repo;
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit_a = write_random_commit(mut_repo);
+    let target = RefTarget::normal(commit_a.id().clone());
+    mut_repo.set_local_bookmark_target("main".as_ref(), target.clone());
+    mut_repo.set_local_tag_target("v1.0".as_ref(), target.clone());
+    // `main/sub` will conflict with `main` in Git
+    mut_repo.set_local_bookmark_target("main/sub".as_ref(), target.clone());
+    let stats = git::export_refs_atomic(mut_repo)?;
+    assert_eq!(stats.failed_bookmarks.len(), 1);
+    assert_eq!(
+        stats.failed_bookmarks[0].0.as_ref(),
+        remote_symbol("main/sub", "git")
+    );
+    assert_matches!(
+        stats.failed_bookmarks[0].1,
+        FailedRefExportReason::FailedToSet(_)
+    );
+    assert!(stats.failed_tags.is_empty());
+    assert!(git_repo.find_reference("refs/heads/main").is_err());
+    assert!(git_repo.find_reference("refs/heads/main/sub").is_err());
+    assert!(git_repo.find_reference("refs/tags/v1.0").is_err());
+    assert_eq!(
+        mut_repo.get_remote_bookmark(remote_symbol("main", "git")),
+        RemoteRef::absent()
+    );
+    assert_eq!(
+        mut_repo.get_remote_tag(remote_symbol("v1.0", "git")),
+        RemoteRef::absent()
+    );
+
+    // Without the conflicting bookmark, all refs are exported
+    mut_repo.set_local_bookmark_target("main/sub".as_ref(), RefTarget::absent());
+    let stats = git::export_refs_atomic(mut_repo)?;
+    assert!(stats.failed_bookmarks.is_empty());
+    assert!(stats.failed_tags.is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/heads/main")?.target().id(),
+        git_id(&commit_a)
+    );
+    assert_eq!(
+        git_repo.find_reference("refs/tags/v1.0")?.target().id(),
+        git_id(&commit_a)
+    );
+    assert_eq!(
+        mut_repo.get_remote_bookmark(remote_symbol("main", "git")),
+        RemoteRef {
+            target: target.clone(),
+            state: RemoteRefState::Tracked,
+        },
+    );
+    assert_eq!(
+        mut_repo.get_git_ref("refs/tags/v1.0".as_ref()),
+        target.clone()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_export_atomic_current_bookmark_changed() -> TestResult {
+    // HEAD is detached in the same transaction as the checked out bookmark is
+    // updated, and the preview reports it
+    let test_data = GitRepoData::create();
+    let import_options = default_import_options();
+    let git_repo = test_data.git_repo;
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    testutils::git::set_symbolic_reference(&git_repo, "HEAD", "refs/heads/main");
+    let mut tx = test_data.repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    git::import_head(mut_repo).block_on()?;
+    git::import_refs(mut_repo, &import_options).block_on()?;
+    mut_repo.rebase_descendants().block_on()?;
+
+    let new_commit = create_random_commit(mut_repo)
+        .set_parents(vec![jj_id(commit1)])
+        .write_unwrap();
+    mut_repo.set_local_bookmark_target("main".as_ref(), RefTarget::normal(new_commit.id().clone()));
+    let preview = git::preview_export_refs(mut_repo)?;
+    assert_eq!(
+        preview
+            .detached_head_refs
+            .iter()
+            .map(|name| name.as_str())
+            .collect_vec(),
+        ["refs/heads/main"]
+    );
+    assert!(
+        !git_repo.head()?.is_detached(),
+        "preview doesn't detach HEAD"
+    );
+
+    let stats = git::export_refs_atomic(mut_repo)?;
+    assert!(stats.failed_bookmarks.is_empty());
+    assert!(stats.failed_tags.is_empty());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/heads/main")?
+            .peel_to_commit()?
+            .id()
+            .detach(),
+        git_id(&new_commit)
+    );
+    assert!(git_repo.head()?.is_detached(), "HEAD is detached");
+    assert_eq!(git_repo.head_id()?.detach(), commit1);
+    Ok(())
+}
+
 #[test]
 fn test_export_partial_failure() -> TestResult {
     // Check that we skip bookmarks that fail to export
@@ -3702,6 +3998,100 @@ fn test_reset_head_detached_out_of_sync() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_reset_head_to_exported_bookmark() -> TestResult {
+    // Create colocated workspace
+    let mut config = base_user_config();
+    let mut layer = ConfigLayer::empty(ConfigSource::Default);
+    layer.set_value("git.export.attach-head", true).unwrap();
+    config.add_layer(layer);
+    let settings = UserSettings::from_config(config)?;
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    let git_repo = testutils::git::init(&workspace_root);
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .block_on()?;
+
+    let mut tx = repo.start_transaction();
+
+    // 2 3 4
+    // |/ /
+    // 1 /
+    // |/
+    // root
+    let commit1 = write_random_commit(tx.repo_mut());
+    let commit2 = write_random_commit_with_parents(tx.repo_mut(), &[&commit1]);
+    let commit3 = write_random_commit_with_parents(tx.repo_mut(), &[&commit1]);
+    let commit4 = write_random_commit(tx.repo_mut());
+    tx.repo_mut()
+        .set_local_bookmark_target("main".as_ref(), RefTarget::normal(commit1.id().clone()));
+    git::export_refs(tx.repo_mut())?;
+
+    // unborn -> commit1, which has exactly one exported bookmark
+    git::reset_head(tx.repo_mut(), &commit2).block_on()?;
+    assert_eq!(git_repo.head_name()?.unwrap().as_bstr(), b"refs/heads/main");
+    assert_eq!(git_repo.head_id()?, git_id(&commit1));
+    assert_eq!(
+        tx.repo().git_head(),
+        RefTarget::normal(commit1.id().clone())
+    );
+
+    // External process detaches HEAD, which can be imported as usual
+    testutils::git::set_head_to_id(&git_repo, git_id(&commit1));
+    assert!(git_repo.head()?.is_detached(), "HEAD is detached");
+    git::import_head(tx.repo_mut()).block_on()?;
+    assert_eq!(
+        tx.repo().git_head(),
+        RefTarget::normal(commit1.id().clone())
+    );
+
+    // commit1 -> unborn: HEAD should stay unborn
+    git::reset_head(tx.repo_mut(), &commit4).block_on()?;
+    assert!(git_repo.head()?.is_unborn(), "HEAD is unborn");
+    assert!(tx.repo().git_head().is_absent());
+
+    // Bookmark that isn't exported yet shouldn't be checked out
+    tx.repo_mut()
+        .set_local_bookmark_target("feature".as_ref(), RefTarget::normal(commit1.id().clone()));
+    git::reset_head(tx.repo_mut(), &commit3).block_on()?;
+    assert_eq!(git_repo.head_name()?.unwrap().as_bstr(), b"refs/heads/main");
+
+    // Ambiguous bookmarks: HEAD should be detached
+    git::export_refs(tx.repo_mut())?;
+    git::reset_head(tx.repo_mut(), &commit4).block_on()?;
+    git::reset_head(tx.repo_mut(), &commit2).block_on()?;
+    assert!(git_repo.head()?.is_detached(), "HEAD is detached");
+    assert_eq!(git_repo.head_id()?, git_id(&commit1));
+    Ok(())
+}
+
+#[test]
+fn test_reset_head_to_exported_bookmark_disabled() -> TestResult {
+    // Create colocated workspace without git.export.attach-head
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    let git_repo = testutils::git::init(&workspace_root);
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .block_on()?;
+
+    let mut tx = repo.start_transaction();
+    let commit1 = write_random_commit(tx.repo_mut());
+    let commit2 = write_random_commit_with_parents(tx.repo_mut(), &[&commit1]);
+    tx.repo_mut()
+        .set_local_bookmark_target("main".as_ref(), RefTarget::normal(commit1.id().clone()));
+    git::export_refs(tx.repo_mut())?;
+
+    // HEAD should be detached even though commit1 has exactly one exported
+    // bookmark
+    git::reset_head(tx.repo_mut(), &commit2).block_on()?;
+    assert!(git_repo.head()?.is_detached(), "HEAD is detached");
+    assert_eq!(git_repo.head_id()?, git_id(&commit1));
+    Ok(())
+}
+
 fn get_index_state(workspace_root: &Path) -> String {
     let git_repo = gix::open(workspace_root).unwrap();
     let index = git_repo.index().unwrap();
@@ -3913,6 +4303,116 @@ fn test_reset_head_with_index_merge_conflict() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_reset_head_with_index_mode_changes() -> TestResult {
+    // Create colocated workspace
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    gix::init(&workspace_root)?;
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .block_on()?;
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let base_tree = testutils::create_tree_with(&repo, |builder| {
+        builder
+            .file(repo_path("conflicted-file"), "base\n")
+            .executable(false);
+        builder
+            .file(repo_path("mode-only-file"), "same\n")
+            .executable(false);
+    });
+    // Only the executable bit changes in mode-only-file
+    let left_tree = testutils::create_tree_with(&repo, |builder| {
+        builder
+            .file(repo_path("conflicted-file"), "left\n")
+            .executable(true);
+        builder
+            .file(repo_path("mode-only-file"), "same\n")
+            .executable(true);
+    });
+    let right_tree = testutils::create_tree_with(&repo, |builder| {
+        builder
+            .file(repo_path("conflicted-file"), "right\n")
+            .executable(false);
+        builder
+            .file(repo_path("mode-only-file"), "same\n")
+            .executable(false);
+    });
+
+    let base_commit = mut_repo
+        .new_commit(vec![repo.store().root_commit_id().clone()], base_tree)
+        .write_unwrap();
+    let left_commit = mut_repo
+        .new_commit(vec![base_commit.id().clone()], left_tree)
+        .write_unwrap();
+    let right_commit = mut_repo
+        .new_commit(vec![base_commit.id().clone()], right_tree.clone())
+        .write_unwrap();
+
+    // A mode-only change is reflected in the index
+    let wc_commit = mut_repo
+        .new_commit(
+            vec![left_commit.id().clone()],
+            repo.store().empty_merged_tree(),
+        )
+        .write_unwrap();
+    git::reset_head(mut_repo, &wc_commit).block_on()?;
+    insta::assert_snapshot!(get_index_state(&workspace_root), @"
+    Unconflicted conflicted-file Mode(FILE | FILE_EXECUTABLE)
+    Unconflicted mode-only-file Mode(FILE | FILE_EXECUTABLE)
+    ");
+
+    // Conflict sides keep their own modes
+    let wc_commit = mut_repo
+        .new_commit(
+            vec![left_commit.id().clone(), right_commit.id().clone()],
+            right_tree,
+        )
+        .write_unwrap();
+    git::reset_head(mut_repo, &wc_commit).block_on()?;
+    insta::assert_snapshot!(get_index_state(&workspace_root), @"
+    Base conflicted-file Mode(FILE)
+    Ours conflicted-file Mode(FILE | FILE_EXECUTABLE)
+    Theirs conflicted-file Mode(FILE)
+    Unconflicted mode-only-file Mode(FILE | FILE_EXECUTABLE)
+    ");
+    Ok(())
+}
+
+#[test]
+fn test_update_intent_to_add_modes() -> TestResult {
+    // Create colocated workspace
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    gix::init(&workspace_root)?;
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .block_on()?;
+
+    let old_tree = repo.store().empty_merged_tree();
+    let new_tree = testutils::create_tree_with(&repo, |builder| {
+        builder
+            .file(repo_path("normal-file"), "file\n")
+            .executable(false);
+        builder
+            .file(repo_path("executable-file"), "file\n")
+            .executable(true);
+        builder.symlink(repo_path("symlink"), "./normal-file");
+    });
+    git::update_intent_to_add(repo.as_ref(), &old_tree, &new_tree).block_on()?;
+    insta::assert_snapshot!(get_index_state(&workspace_root), @"
+    Unconflicted executable-file Mode(FILE | FILE_EXECUTABLE)
+    Unconflicted normal-file Mode(FILE)
+    Unconflicted symlink Mode(SYMLINK)
+    ");
+    Ok(())
+}
+
 #[test]
 fn test_reset_head_with_index_file_directory_conflict() -> TestResult {
     // Create colocated workspace
@@ -6523,8 +7023,76 @@ fn test_shallow_commits_lack_parents() -> TestResult {
         vec![jj_id(a)],
         "unshallowed commits have correct parents"
     );
-    // FIXME: new ancestors should be indexed
+    // New ancestors are indexed once the former shallow commits are reindexed
     assert!(!repo.index().has_id(&jj_id(a))?);
+    let mut tx = repo.start_transaction();
+    tx.repo_mut()
+        .reindex_commits(&[jj_id(b), jj_id(c)])
+        .block_on()?;
+    tx.commit("reindex").block_on()?;
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert!(repo.index().is_ancestor(&jj_id(a), &jj_id(d))?);
+    assert!(repo.index().is_ancestor(&jj_id(a), &jj_id(e))?);
+    Ok(())
+}
+
+#[test]
+fn test_grafted_commits_have_replaced_parents() -> TestResult {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+    let import_options = default_import_options();
+
+    // D (`main`)
+    // |
+    // C   // grafted onto A
+    // |
+    // B
+    // |
+    // A   // grafted as root
+    // |
+    // git_root
+    let git_root = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let a = empty_git_commit(&git_repo, "refs/heads/main", &[git_root]);
+    let b = empty_git_commit(&git_repo, "refs/heads/main", &[a]);
+    let c = empty_git_commit(&git_repo, "refs/heads/main", &[b]);
+    let d = empty_git_commit(&git_repo, "refs/heads/main", &[c]);
+
+    let grafts_dir = git_repo.common_dir().join("info");
+    fs::create_dir_all(&grafts_dir)?;
+    fs::write(
+        grafts_dir.join("grafts"),
+        format!("# comment\n{c} {a}\n{a}\n"),
+    )?;
+    // Reload the repo to invalidate in-memory cache
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+
+    let mut tx = repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &import_options).block_on()?;
+    let repo = tx.commit("import").block_on()?;
+    let store = repo.store();
+    let root = store.root_commit_id();
+
+    assert_eq!(*repo.view().heads(), hashset! {jj_id(d)});
+
+    let parents = |commit| {
+        let commit = store.get_commit(&jj_id(commit)).unwrap();
+        commit.parent_ids().to_vec()
+    };
+    assert_eq!(parents(d), vec![jj_id(c)]);
+    assert_eq!(parents(c), vec![jj_id(a)], "grafted parents are used");
+    assert_eq!(
+        parents(a),
+        vec![root.clone()],
+        "grafted roots have no parents"
+    );
+
+    // Commits hidden by grafts aren't imported
+    assert!(repo.index().has_id(&jj_id(a))?);
+    assert!(!repo.index().has_id(&jj_id(b))?);
+    assert!(!repo.index().has_id(&jj_id(git_root))?);
     Ok(())
 }
 
@@ -6662,6 +7230,53 @@ fn test_remote_rename_refs() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_remote_rename_refs_symbolic_head() -> TestResult {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+
+    let mut tx = test_repo.repo.start_transaction();
+    git::add_remote(tx.repo_mut(), "foo".as_ref(), "https://example.com/", None)?;
+    let _repo = tx.commit("test").block_on()?;
+    // Reload after Git configuration change.
+    let repo = &test_repo
+        .env
+        .load_repo_at_head(&testutils::user_settings(), test_repo.repo_path());
+
+    let git_repo = get_git_repo(repo);
+    let commit_foo_main = empty_git_commit(&git_repo, "refs/remotes/foo/main", &[]);
+    testutils::git::set_symbolic_reference(
+        &git_repo,
+        "refs/remotes/foo/HEAD",
+        "refs/remotes/foo/main",
+    );
+
+    let mut tx = repo.start_transaction();
+    git::rename_remote(tx.repo_mut(), "foo".as_ref(), "bar".as_ref())?;
+    let repo = &tx.commit("rename").block_on()?;
+
+    let git_repo = get_git_repo(repo);
+    assert!(
+        git_repo
+            .try_find_reference("refs/remotes/foo/HEAD")?
+            .is_none()
+    );
+    let head_ref = git_repo.find_reference("refs/remotes/bar/HEAD")?;
+    assert_eq!(
+        head_ref
+            .target()
+            .try_name()
+            .map(|name| name.as_bstr().to_owned()),
+        Some("refs/remotes/bar/main".into())
+    );
+    assert_eq!(
+        git_repo
+            .find_reference("refs/remotes/bar/HEAD")?
+            .into_fully_peeled_id()?,
+        commit_foo_main,
+    );
+    Ok(())
+}
+
 fn user_settings_without_change_id() -> UserSettings {
     let mut config = base_user_config();
     let mut layer = ConfigLayer::empty(ConfigSource::Default);
@@ -6755,6 +7370,11 @@ fn test_push_updates_with_options() -> TestResult {
                 "merge_request.create".to_owned(),
                 "merge_request.draft".to_owned(),
             ],
+            no_thin: false,
+            atomic: false,
+            signed: GitPushSigned::No,
+            expected_remote_head: None,
+            tag_source_remote: None,
         },
     )?;
 
@@ -6790,8 +7410,11 @@ fn auto_track_import_options() -> GitImportOptions {
 fn default_import_options() -> GitImportOptions {
     GitImportOptions {
         abandon_unreachable_commits: true,
+        import_deleted_bookmark: GitImportDeletedBookmark::Conflict,
         record_synthetic_predecessors: true,
         remote_auto_track_bookmarks: HashMap::new(),
+        skip_clashing_bookmarks: false,
+        keep_existing_tags: false,
     }
 }
 
@@ -2836,6 +2836,76 @@ fn test_evaluate_expression_tags() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_git_refs() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let root_commit_id = repo.store().root_commit_id().clone();
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+
+    let commit1 = write_random_commit(mut_repo);
+    let commit2 = write_random_commit(mut_repo);
+    let commit3 = write_random_commit(mut_repo);
+    let commit4 = write_random_commit(mut_repo);
+
+    // Can get git refs when there are none
+    assert_eq!(resolve_commit_ids(mut_repo, "git_refs()"), vec![]);
+    // Can get a few git refs, including ones not imported as bookmarks
+    mut_repo.set_git_ref_target(
+        "refs/heads/main".as_ref(),
+        RefTarget::normal(commit1.id().clone()),
+    );
+    mut_repo.set_git_ref_target(
+        "refs/tags/v1.0".as_ref(),
+        RefTarget::normal(commit2.id().clone()),
+    );
+    mut_repo.set_git_ref_target(
+        "refs/remotes/origin/main".as_ref(),
+        RefTarget::normal(commit3.id().clone()),
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs()"),
+        vec![
+            commit3.id().clone(),
+            commit2.id().clone(),
+            commit1.id().clone()
+        ]
+    );
+    // Can get git refs with matching names
+    assert_eq!(
+        resolve_commit_ids(mut_repo, r#"git_refs("refs/tags/*")"#),
+        vec![commit2.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(substring:main)"),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(resolve_commit_ids(mut_repo, "git_refs(exact:main)"), vec![]);
+    // Refs pointing to the root commit are excluded
+    mut_repo.set_git_ref_target(
+        "refs/heads/root".as_ref(),
+        RefTarget::normal(root_commit_id),
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(refs/heads/*)"),
+        vec![commit1.id().clone()]
+    );
+    // Conflicted refs contribute all their added targets
+    mut_repo.set_git_ref_target(
+        "refs/heads/main".as_ref(),
+        RefTarget::from_legacy_form(
+            [commit1.id().clone()],
+            [commit2.id().clone(), commit4.id().clone()],
+        ),
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "git_refs(refs/heads/*)"),
+        vec![commit4.id().clone(), commit2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_remote_tags() {
     let test_repo = TestRepo::init();
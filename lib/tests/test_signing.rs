@@ -1,8 +1,13 @@
+use std::sync::Arc;
+
 use jj_lib::backend::MillisSinceEpoch;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo as _;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::SigStatus;
@@ -17,6 +22,8 @@ use testutils::TestRepoBackend;
 use testutils::TestResult;
 use testutils::TestWorkspace;
 use testutils::create_random_commit;
+use testutils::create_tree;
+use testutils::repo_path;
 use testutils::write_random_commit;
 
 fn user_settings(behavior: SignBehavior) -> UserSettings {
@@ -210,3 +217,100 @@ fn drop_behavior(backend: TestRepoBackend) -> TestResult {
     assert_eq!(rewritten_commit.verification()?, None);
     Ok(())
 }
+
+fn create_conflicted_tree(repo: &Arc<ReadonlyRepo>) -> MergedTree {
+    let path = repo_path("file");
+    let side1 = create_tree(repo, &[(path, "side 1")]);
+    let base = create_tree(repo, &[(path, "base")]);
+    let side2 = create_tree(repo, &[(path, "side 2")]);
+    MergedTree::merge(Merge::from_vec(vec![
+        (side1, "side 1".into()),
+        (base, "base".into()),
+        (side2, "side 2".into()),
+    ]))
+    .block_on()
+    .unwrap()
+}
+
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn conflicted_not_signed(backend: TestRepoBackend) -> TestResult {
+    let settings = user_settings(SignBehavior::Own);
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = test_workspace.repo.clone();
+    let conflicted_tree = create_conflicted_tree(&repo);
+    let mut tx = repo.start_transaction();
+    let commit = create_random_commit(tx.repo_mut())
+        .set_tree(conflicted_tree)
+        .write_unwrap();
+    tx.commit("test").block_on()?;
+
+    let commit = repo.store().get_commit(commit.id())?;
+    assert!(commit.has_conflict());
+    assert_eq!(commit.verification()?, None);
+
+    // Once the conflict is resolved, the rewritten commit is signed.
+    let resolved_tree = create_tree(&repo, &[(repo_path("file"), "resolved")]);
+    let mut tx = repo.start_transaction();
+    let rewritten = tx
+        .repo_mut()
+        .rewrite_commit(&commit)
+        .set_tree(resolved_tree)
+        .write_unwrap();
+
+    let commit = repo.store().get_commit(rewritten.id())?;
+    assert_eq!(commit.verification()?, good_verification());
+    Ok(())
+}
+
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn conflicted_kept_on_rewrite(backend: TestRepoBackend) -> TestResult {
+    let settings = user_settings(SignBehavior::Keep);
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = test_workspace.repo.clone();
+    let conflicted_tree = create_conflicted_tree(&repo);
+    let mut tx = repo.start_transaction();
+    let commit = create_random_commit(tx.repo_mut())
+        .set_tree(conflicted_tree)
+        .set_sign_behavior(SignBehavior::Force)
+        .write_unwrap();
+    tx.commit("test").block_on()?;
+
+    let mut tx = repo.start_transaction();
+    let rewritten = tx
+        .repo_mut()
+        .rewrite_commit(&commit)
+        .set_description("rewritten")
+        .write_unwrap();
+
+    let commit = repo.store().get_commit(rewritten.id())?;
+    assert!(commit.has_conflict());
+    assert_eq!(commit.verification()?, good_verification());
+    Ok(())
+}
+
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn conflicted_forced(backend: TestRepoBackend) -> TestResult {
+    let settings = user_settings(SignBehavior::Force);
+
+    let signer = Signer::new(Some(Box::new(TestSigningBackend)), vec![]);
+    let test_workspace = TestWorkspace::init_with_backend_and_signer(backend, signer, &settings);
+
+    let repo = test_workspace.repo.clone();
+    let conflicted_tree = create_conflicted_tree(&repo);
+    let mut tx = repo.start_transaction();
+    let commit = create_random_commit(tx.repo_mut())
+        .set_tree(conflicted_tree)
+        .write_unwrap();
+    tx.commit("test").block_on()?;
+
+    let commit = repo.store().get_commit(commit.id())?;
+    assert!(commit.has_conflict());
+    assert_eq!(commit.verification()?, good_verification());
+    Ok(())
+}
@@ -49,6 +49,23 @@ pub fn init(directory: impl AsRef<Path>) -> gix::Repository {
     .to_thread_local()
 }
 
+pub fn init_with_object_hash(
+    directory: impl AsRef<Path>,
+    object_hash: gix::hash::Kind,
+) -> gix::Repository {
+    gix::ThreadSafeRepository::init_opts(
+        directory,
+        gix::create::Kind::WithWorktree,
+        gix::create::Options {
+            object_hash: Some(object_hash),
+            ..Default::default()
+        },
+        open_options(),
+    )
+    .unwrap()
+    .to_thread_local()
+}
+
 pub fn init_bare(directory: impl AsRef<Path>) -> gix::Repository {
     gix::ThreadSafeRepository::init_opts(
         directory,